@@ -11,7 +11,7 @@ use json_surf::prelude::*;
 
 
 // Main struct
-#[derive(Serialize, Debug, Deserialize, PartialEq, PartialOrd, Clone)]
+#[derive(Serialize, Debug, Deserialize, PartialEq, Clone)]
 struct UserInfo {
     first: String,
     last: String,
@@ -69,7 +69,7 @@ fn main() {
 
     // See examples for more options
     let users = vec![john_doe.clone(), jane_doe.clone()];
-    let _ = surf.insert(&index_name, &users).unwrap();
+    surf.insert(&index_name, &users).unwrap();
 
     block_thread(1);
 
@@ -140,6 +140,14 @@ impl Ord for UserInfo {
     }
 }
 
+/// Ignore all of this
+/// Convenience method for sorting & likely not required in user code
+impl PartialOrd for UserInfo {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 /// Ignore all of this
 /// Convenience method for sorting & likely not required in user code
 impl Eq for UserInfo {}
@@ -155,6 +163,5 @@ impl Hash for UserInfo {
             state.write_u8(*i);
         }
         state.write_u8(self.age);
-        state.finish();
     }
 }
\ No newline at end of file
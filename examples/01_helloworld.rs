@@ -12,7 +12,7 @@ use json_surf::prelude::*;
 
 
 // Main struct
-#[derive(Serialize, Debug, Deserialize, PartialEq, PartialOrd, Clone)]
+#[derive(Serialize, Debug, Deserialize, PartialEq, Clone)]
 struct UserInfo {
     first: String,
     last: String,
@@ -84,12 +84,12 @@ fn main() {
     // Writing structs
 
     // Option 1: One struct at a time
-    let _ = surfer.insert_struct(&index_name, &john_doe).unwrap();
-    let _ = surfer.insert_struct(&index_name, &jane_doe).unwrap();
+    surfer.insert_struct(&index_name, &john_doe).unwrap();
+    surfer.insert_struct(&index_name, &jane_doe).unwrap();
 
     // Option 2: Write all structs together
     let users = vec![jonny_doe.clone(), jinny_doe.clone()];
-    let _ = surfer.insert_structs(&index_name, &users).unwrap();
+    surfer.insert_structs(&index_name, &users).unwrap();
 
     block_thread(1);
 
@@ -118,16 +118,16 @@ fn main() {
     // Option 1: Delete based on all text fields
     // Before delete
     let before = surfer.read_all_structs::<UserInfo>(&index_name, "doe").unwrap().unwrap();
-    let before: HashSet<UserInfo> = HashSet::from_iter(before.into_iter());
+    let before: HashSet<UserInfo> = HashSet::from_iter(before);
 
     // Delete any occurrence of John (Actual call to delete)
     surfer.delete_structs(&index_name, "john").unwrap();
 
     // After delete
     let after = surfer.read_all_structs::<UserInfo>(&index_name, "doe").unwrap().unwrap();
-    let after: HashSet<UserInfo> = HashSet::from_iter(after.into_iter());
+    let after: HashSet<UserInfo> = HashSet::from_iter(after);
     // Check difference
-    let computed: Vec<UserInfo> = before.difference(&after).map(|e| e.clone()).collect();
+    let computed: Vec<UserInfo> = before.difference(&after).cloned().collect();
     // Only John should be deleted
     let expected = vec![john_doe];
     assert_eq!(expected, computed);
@@ -135,16 +135,16 @@ fn main() {
     // Option 2: Delete based on a specific field
     // Before delete
     let before = surfer.read_all_structs_by_field::<UserInfo>(&index_name, "age", "10").unwrap().unwrap();
-    let before: HashSet<UserInfo> = HashSet::from_iter(before.into_iter());
+    let before: HashSet<UserInfo> = HashSet::from_iter(before);
 
     // Delete any occurrence where age = 10 (Actual call to delete)
     surfer.delete_structs_by_field(&index_name, "age", "10").unwrap();
 
     // After delete
     let after = surfer.read_all_structs_by_field::<UserInfo>(&index_name, "age", "10").unwrap().unwrap();
-    let after: HashSet<UserInfo> = HashSet::from_iter(after.into_iter());
+    let after: HashSet<UserInfo> = HashSet::from_iter(after);
     // Check difference
-    let mut computed: Vec<UserInfo> = before.difference(&after).map(|e| e.clone()).collect();
+    let mut computed: Vec<UserInfo> = before.difference(&after).cloned().collect();
     computed.sort();
     // Both Jonny & Jinny should be deleted
     let mut expected = vec![jonny_doe, jinny_doe];
@@ -180,6 +180,13 @@ impl Ord for UserInfo {
     }
 }
 
+/// Convenience method for sorting & likely not required in user code
+impl PartialOrd for UserInfo {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 /// Convenience method for sorting & likely not required in user code
 impl Eq for UserInfo {}
 
@@ -193,6 +200,5 @@ impl Hash for UserInfo {
             state.write_u8(*i);
         }
         state.write_u8(self.age);
-        state.finish();
     }
 }
\ No newline at end of file
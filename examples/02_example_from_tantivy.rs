@@ -52,7 +52,7 @@ fn main() {
     let old_man = OldMan::new(title, body);
 
     // Insert the data so that store as only one document
-    let _ = surfer.insert_struct(&name, &old_man).unwrap();
+    surfer.insert_struct(&name, &old_man).unwrap();
     println!("Inserting document: 1");
 
     // Give some time to indexing to complete
@@ -66,7 +66,7 @@ fn main() {
     assert_eq!(computed, vec![old_man.clone()]);
 
     // Insert the data so that store as two document
-    let _ = surfer.insert_struct(&name, &old_man).unwrap();
+    surfer.insert_struct(&name, &old_man).unwrap();
     println!("Inserting document: 1");
 
     // Give some time to indexing to complete
@@ -84,9 +84,9 @@ fn main() {
     let mut documents = Vec::with_capacity(50);
     while i < 50 {
         documents.push(old_man.clone());
-        i = i + 1;
+        i += 1;
     };
-    let _ = surfer.insert_structs(&name, &documents).unwrap();
+    surfer.insert_structs(&name, &documents).unwrap();
     println!("Inserting document: 50");
 
     // Give some time to indexing to complete
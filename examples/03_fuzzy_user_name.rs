@@ -1,12 +1,13 @@
 use std::convert::TryFrom;
 use std::fs::remove_dir_all;
+use std::collections::HashMap;
 use std::cmp::{Ord, Ordering, Eq};
 
 use serde::{Serialize, Deserialize};
 
 use json_surf::prelude::*;
 
-#[derive(Serialize, Debug, Deserialize, PartialEq, PartialOrd, Clone)]
+#[derive(Serialize, Debug, Deserialize, PartialEq, Clone)]
 struct User {
     first: String,
     last: String,
@@ -51,7 +52,7 @@ fn main() {
     let jane_doe = User::new(first, last);
 
     let users = vec![john_doe.clone(), jane_doe.clone()];
-    let _ = surfer.insert_structs(&name, &users).unwrap();
+    surfer.insert_structs(&name, &users).unwrap();
     println!("===========================");
     println!("Insert: John & Jane Doe");
     println!("---------------------------");
@@ -77,8 +78,8 @@ fn main() {
     let mod_query = fuzz.lookup(query);
     assert!(mod_query.is_some());
     let adjusted = mod_query.unwrap();
-    assert!(adjusted.len() >= 1);
-    let adjusted = adjusted.get(0).unwrap();
+    assert!(!adjusted.is_empty());
+    let adjusted = adjusted.first().unwrap();
     let mut computed = surfer.read_structs::<User>(&name, adjusted, Some(100), None).unwrap().unwrap();
     computed.sort();
     let mut expected = vec![john_doe.clone(), jane_doe.clone()];
@@ -91,6 +92,19 @@ fn main() {
     println!("{:#?}", computed);
     println!("------------------------------------");
 
+    // No corpus required, typos are matched against indexed terms
+    let mut fuzziness = HashMap::new();
+    fuzziness.insert("last".to_string(), Fuzziness::new(1, true));
+    let mut computed = surfer.read_fuzzy_structs::<User>(&name, query, &fuzziness, Some(100), None).unwrap().unwrap();
+    computed.sort();
+    assert_eq!(computed, expected);
+
+    println!("====================================");
+    println!("Fuzzy Query: '{}' Select: Jane & John Doe", query);
+    println!("------------------------------------");
+    println!("{:#?}", computed);
+    println!("------------------------------------");
+
 
     // Clean-up
     let path = surfer.which_index(&name).unwrap();
@@ -121,3 +135,9 @@ impl Ord for User {
         }
     }
 }
+
+impl PartialOrd for User {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
@@ -1,4 +1,3 @@
-use serde_value;
 use serde::{Serialize, Deserialize};
 use tantivy::schema::Schema;
 use tantivy::schema::FieldEntry;
@@ -0,0 +1,426 @@
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::sync::Arc;
+
+use serde::Serialize;
+use tantivy::collector::{Collector, SegmentCollector};
+use tantivy::fastfield::FastValue;
+use tantivy::schema::{Field, IndexRecordOption};
+use tantivy::{DocId, DocSet, InvertedIndexReader, Score, SegmentLocalId, SegmentReader, TERMINATED};
+
+use crate::prelude::*;
+use crate::registry::SurferFieldTypes;
+use crate::sorting::FastFieldValues;
+
+/// Width of a date histogram bucket, over fields holding unix timestamps in seconds
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum DateInterval {
+    Hour,
+    Day,
+}
+
+impl DateInterval {
+    fn seconds(self) -> i64 {
+        match self {
+            DateInterval::Hour => 60 * 60,
+            DateInterval::Day => 24 * 60 * 60,
+        }
+    }
+}
+
+/// Bucketed counts over the documents matching a query
+/// * `Terms` - Most frequent values of any indexed field. Numeric fast fields are read for the
+///   matching documents only; other fields walk the whole term dictionary & its postings, so the
+///   cost grows with the index rather than with the hits.
+/// * `Histogram` - Fixed width buckets of a numeric fast field
+/// * `DateHistogram` - Hourly or daily buckets of a numeric fast field
+#[derive(Debug, Clone, PartialEq)]
+pub enum Aggregation {
+    Terms { field_name: String, size: usize },
+    Histogram { field_name: String, interval: f64 },
+    DateHistogram { field_name: String, interval: DateInterval },
+}
+
+impl Aggregation {
+    /// Top size values by document count, cheap on numeric fast fields only
+    pub fn terms(field_name: &str, size: usize) -> Self {
+        Aggregation::Terms { field_name: field_name.to_string(), size }
+    }
+    /// Buckets of interval width starting at zero
+    pub fn histogram(field_name: &str, interval: f64) -> Self {
+        Aggregation::Histogram { field_name: field_name.to_string(), interval }
+    }
+    pub fn date_histogram(field_name: &str, interval: DateInterval) -> Self {
+        Aggregation::DateHistogram { field_name: field_name.to_string(), interval }
+    }
+    pub fn resolve_field_name(&self) -> &String {
+        match self {
+            Aggregation::Terms { field_name, .. } => field_name,
+            Aggregation::Histogram { field_name, .. } => field_name,
+            Aggregation::DateHistogram { field_name, .. } => field_name,
+        }
+    }
+    /// Checks the field can be aggregated on
+    pub(crate) fn resolve_field(&self, schema: &SurferSchema) -> Result<(Field, SurferFieldTypes), IndexError> {
+        let field_name = self.resolve_field_name();
+        let message = format!("Unable to aggregate on: {}", field_name);
+        let field_type = schema.resolve_mapping().get(field_name);
+        let field = schema.get_field(field_name);
+        if field_type.is_none() || field.is_none() {
+            let reason = format!("Missing field: {}", field_name);
+            return Err(IndexError::new(message, reason));
+        };
+        let field_type = field_type.unwrap().clone();
+        let field = field.unwrap();
+        let field_entry = schema.get_field_entry(field);
+        match self {
+            Aggregation::Terms { .. } => {
+                if field_type == SurferFieldTypes::Bytes || !field_entry.is_indexed() {
+                    let reason = format!("Not an indexed field: {}", field_name);
+                    return Err(IndexError::new(message, reason));
+                };
+            }
+            Aggregation::Histogram { interval, .. } => {
+                if !interval.is_finite() || *interval <= 0f64 {
+                    let reason = format!("Invalid interval: {}", interval);
+                    return Err(IndexError::new(message, reason));
+                };
+                if !field_entry.is_int_fast() {
+                    let reason = format!("Not a numeric fast field: {}", field_name);
+                    return Err(IndexError::new(message, reason));
+                };
+            }
+            Aggregation::DateHistogram { .. } => {
+                if !field_entry.is_int_fast() || field_type == SurferFieldTypes::F64 {
+                    let reason = format!("Not an integer fast field: {}", field_name);
+                    return Err(IndexError::new(message, reason));
+                };
+            }
+        };
+        Ok((field, field_type))
+    }
+}
+
+/// Value a bucket stands for
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum BucketKey {
+    Text(String),
+    U64(u64),
+    I64(i64),
+    F64(f64),
+}
+
+/// Number of matching documents in a bucket
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Bucket {
+    key: BucketKey,
+    count: u64,
+}
+
+impl Bucket {
+    pub fn new(key: BucketKey, count: u64) -> Self {
+        Self {
+            key,
+            count,
+        }
+    }
+    pub fn resolve_key(&self) -> &BucketKey {
+        &self.key
+    }
+    pub fn resolve_count(&self) -> u64 {
+        self.count
+    }
+}
+
+/// Output of one aggregation, empty buckets are left out
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Buckets {
+    field_name: String,
+    buckets: Vec<Bucket>,
+}
+
+impl Buckets {
+    pub fn new(field_name: String, buckets: Vec<Bucket>) -> Self {
+        Self {
+            field_name,
+            buckets,
+        }
+    }
+    pub fn resolve_field_name(&self) -> &String {
+        &self.field_name
+    }
+    /// Terms by count, histograms by key
+    pub fn resolve_buckets(&self) -> &Vec<Bucket> {
+        &self.buckets
+    }
+}
+
+/// Documents read along with the aggregations over every match
+#[derive(Debug, Clone, Serialize)]
+pub struct Aggregated<T> {
+    documents: Vec<T>,
+    aggregations: Vec<Buckets>,
+}
+
+impl<T> Aggregated<T> {
+    pub fn new(documents: Vec<T>, aggregations: Vec<Buckets>) -> Self {
+        Self {
+            documents,
+            aggregations,
+        }
+    }
+    pub fn resolve_documents(&self) -> &Vec<T> {
+        &self.documents
+    }
+    pub fn into_documents(self) -> Vec<T> {
+        self.documents
+    }
+    /// In the order the aggregations were asked for
+    pub fn resolve_aggregations(&self) -> &Vec<Buckets> {
+        &self.aggregations
+    }
+}
+
+/// Term bytes or bucket number
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub(crate) enum RawKey {
+    Term(Vec<u8>),
+    Bucket(i64),
+}
+
+pub(crate) type RawCounts = HashMap<RawKey, u64>;
+
+/// Runs the aggregations alongside a query
+#[derive(Clone)]
+pub(crate) struct AggregationCollector {
+    aggregations: Vec<(Aggregation, Field, SurferFieldTypes)>,
+}
+
+impl AggregationCollector {
+    pub(crate) fn new(schema: &SurferSchema, aggregations: &[Aggregation]) -> Result<Self, IndexError> {
+        let mut resolved = Vec::with_capacity(aggregations.len());
+        for aggregation in aggregations {
+            let (field, field_type) = aggregation.resolve_field(schema)?;
+            resolved.push((aggregation.clone(), field, field_type));
+        };
+        Ok(Self { aggregations: resolved })
+    }
+    /// Decodes the merged counts into buckets
+    pub(crate) fn resolve_buckets(&self, fruit: Vec<RawCounts>) -> Vec<Buckets> {
+        self.aggregations
+            .iter()
+            .zip(fruit)
+            .map(|((aggregation, _, field_type), counts)| {
+                let buckets = match aggregation {
+                    Aggregation::Terms { size, .. } => {
+                        let mut buckets: Vec<(Vec<u8>, u64)> = counts
+                            .into_iter()
+                            .filter_map(|(key, count)| match key {
+                                RawKey::Term(term) => Some((term, count)),
+                                RawKey::Bucket(_) => None,
+                            })
+                            .collect();
+                        buckets.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+                        buckets
+                            .into_iter()
+                            .take(*size)
+                            .filter_map(|(term, count)| decode_term(field_type, &term).map(|key| Bucket::new(key, count)))
+                            .collect()
+                    }
+                    Aggregation::Histogram { interval, .. } => {
+                        sorted_buckets(counts, |bucket| BucketKey::F64(bucket as f64 * interval))
+                    }
+                    Aggregation::DateHistogram { interval, .. } => {
+                        sorted_buckets(counts, |bucket| BucketKey::I64(bucket * interval.seconds()))
+                    }
+                };
+                Buckets::new(aggregation.resolve_field_name().clone(), buckets)
+            })
+            .collect()
+    }
+}
+
+fn sorted_buckets<F: Fn(i64) -> BucketKey>(counts: RawCounts, key: F) -> Vec<Bucket> {
+    let mut buckets: Vec<(i64, u64)> = counts
+        .into_iter()
+        .filter_map(|(raw, count)| match raw {
+            RawKey::Bucket(bucket) => Some((bucket, count)),
+            RawKey::Term(_) => None,
+        })
+        .collect();
+    buckets.sort_by_key(|(bucket, _)| *bucket);
+    buckets.into_iter().map(|(bucket, count)| Bucket::new(key(bucket), count)).collect()
+}
+
+/// Term dictionary keys hold text as is & numbers as big endian u64
+fn decode_term(field_type: &SurferFieldTypes, term: &[u8]) -> Option<BucketKey> {
+    if *field_type == SurferFieldTypes::String {
+        return std::str::from_utf8(term).ok().map(|text| BucketKey::Text(text.to_string()));
+    };
+    let value = u64::from_be_bytes(term.try_into().ok()?);
+    match field_type {
+        SurferFieldTypes::U64 => Some(BucketKey::U64(value)),
+        SurferFieldTypes::I64 => Some(BucketKey::I64(i64::from_u64(value))),
+        SurferFieldTypes::F64 => Some(BucketKey::F64(f64::from_u64(value))),
+        _ => None,
+    }
+}
+
+enum SegmentAggregation {
+    Terms { inverted_index: Arc<InvertedIndexReader>, matched: Vec<bool> },
+    FastTerms { values: FastFieldValues, counts: RawCounts },
+    Histogram { values: FastFieldValues, interval: f64, counts: RawCounts },
+    DateHistogram { values: FastFieldValues, interval: i64, counts: RawCounts },
+}
+
+impl SegmentAggregation {
+    fn collect(&mut self, doc: DocId) {
+        match self {
+            SegmentAggregation::Terms { matched, .. } => matched[doc as usize] = true,
+            SegmentAggregation::FastTerms { values, counts } => {
+                let term = values.key(doc).to_be_bytes().to_vec();
+                *counts.entry(RawKey::Term(term)).or_insert(0) += 1;
+            }
+            SegmentAggregation::Histogram { values, interval, counts } => {
+                let bucket = (values.as_f64(doc) / *interval).floor() as i64;
+                *counts.entry(RawKey::Bucket(bucket)).or_insert(0) += 1;
+            }
+            SegmentAggregation::DateHistogram { values, interval, counts } => {
+                let bucket = values.as_i64(doc).div_euclid(*interval);
+                *counts.entry(RawKey::Bucket(bucket)).or_insert(0) += 1;
+            }
+        };
+    }
+    fn harvest(self) -> RawCounts {
+        match self {
+            SegmentAggregation::Terms { inverted_index, matched } => {
+                let mut counts = RawCounts::new();
+                if !matched.iter().any(|is_matched| *is_matched) {
+                    return counts;
+                };
+                let mut terms = inverted_index.terms().stream();
+                while terms.advance() {
+                    let mut postings = inverted_index.read_postings_from_terminfo(terms.value(), IndexRecordOption::Basic);
+                    let mut count = 0u64;
+                    let mut doc = postings.doc();
+                    while doc != TERMINATED {
+                        if matched[doc as usize] {
+                            count += 1;
+                        };
+                        doc = postings.advance();
+                    };
+                    if count > 0 {
+                        *counts.entry(RawKey::Term(terms.key().to_vec())).or_insert(0) += count;
+                    };
+                };
+                counts
+            }
+            SegmentAggregation::FastTerms { counts, .. } => counts,
+            SegmentAggregation::Histogram { counts, .. } => counts,
+            SegmentAggregation::DateHistogram { counts, .. } => counts,
+        }
+    }
+}
+
+pub(crate) struct AggregationSegmentCollector {
+    aggregations: Vec<SegmentAggregation>,
+}
+
+impl Collector for AggregationCollector {
+    type Fruit = Vec<RawCounts>;
+    type Child = AggregationSegmentCollector;
+
+    fn for_segment(&self, _: SegmentLocalId, reader: &SegmentReader) -> tantivy::Result<Self::Child> {
+        let mut aggregations = Vec::with_capacity(self.aggregations.len());
+        for (aggregation, field, _) in &self.aggregations {
+            let segment_aggregation = match aggregation {
+                // Fast values encode numbers the same way as the term dictionary
+                Aggregation::Terms { .. } => match FastFieldValues::open(reader, *field) {
+                    Some(values) => SegmentAggregation::FastTerms {
+                        values,
+                        counts: RawCounts::new(),
+                    },
+                    None => SegmentAggregation::Terms {
+                        inverted_index: reader.inverted_index(*field),
+                        matched: vec![false; reader.max_doc() as usize],
+                    },
+                },
+                Aggregation::Histogram { field_name, interval } => SegmentAggregation::Histogram {
+                    values: open_values(reader, *field, field_name)?,
+                    interval: *interval,
+                    counts: RawCounts::new(),
+                },
+                Aggregation::DateHistogram { field_name, interval } => SegmentAggregation::DateHistogram {
+                    values: open_values(reader, *field, field_name)?,
+                    interval: interval.seconds(),
+                    counts: RawCounts::new(),
+                },
+            };
+            aggregations.push(segment_aggregation);
+        };
+        Ok(AggregationSegmentCollector { aggregations })
+    }
+
+    fn requires_scoring(&self) -> bool {
+        false
+    }
+
+    fn merge_fruits(&self, segment_fruits: Vec<Self::Fruit>) -> tantivy::Result<Self::Fruit> {
+        let mut merged = vec![RawCounts::new(); self.aggregations.len()];
+        for fruit in segment_fruits {
+            for (counts, segment_counts) in merged.iter_mut().zip(fruit) {
+                for (key, count) in segment_counts {
+                    *counts.entry(key).or_insert(0) += count;
+                };
+            };
+        };
+        Ok(merged)
+    }
+}
+
+fn open_values(reader: &SegmentReader, field: Field, field_name: &str) -> tantivy::Result<FastFieldValues> {
+    FastFieldValues::open(reader, field).ok_or_else(|| {
+        let error = format!("Field: {} is not a numeric fast field", field_name);
+        tantivy::TantivyError::SchemaError(error)
+    })
+}
+
+impl SegmentCollector for AggregationSegmentCollector {
+    type Fruit = Vec<RawCounts>;
+
+    fn collect(&mut self, doc: DocId, _: Score) {
+        for aggregation in self.aggregations.iter_mut() {
+            aggregation.collect(doc);
+        };
+    }
+
+    fn harvest(self) -> Self::Fruit {
+        self.aggregations.into_iter().map(SegmentAggregation::harvest).collect()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_decode_term() {
+        let key = decode_term(&SurferFieldTypes::String, b"fee");
+        assert_eq!(key, Some(BucketKey::Text("fee".to_string())));
+        let key = decode_term(&SurferFieldTypes::I64, &(-5i64).to_u64().to_be_bytes());
+        assert_eq!(key, Some(BucketKey::I64(-5)));
+        let key = decode_term(&SurferFieldTypes::F64, &(2.5f64).to_u64().to_be_bytes());
+        assert_eq!(key, Some(BucketKey::F64(2.5)));
+        assert_eq!(decode_term(&SurferFieldTypes::U64, b"short"), None);
+    }
+
+    #[test]
+    fn validate_bucket_json() {
+        let bucket = Bucket::new(BucketKey::Text("fee".to_string()), 3);
+        assert_eq!(serde_json::to_string(&bucket).unwrap(), r#"{"key":"fee","count":3}"#);
+        let bucket = Bucket::new(BucketKey::I64(-3600), 1);
+        assert_eq!(serde_json::to_string(&bucket).unwrap(), r#"{"key":-3600,"count":1}"#);
+    }
+}
@@ -1,3 +1,6 @@
+// failure_derive expands into impls nested in a const block
+#![allow(non_local_definitions)]
+
 use std::fmt::Debug;
 use std::io;
 use std::convert::From;
@@ -36,6 +39,14 @@ impl IndexError {
             reason,
         }
     }
+    /// What was being attempted
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+    /// Why it failed
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
 }
 
 impl From<OpenDirectoryError> for IndexError {
@@ -134,7 +145,7 @@ mod tests {
     #[test]
     fn validate_index_error_from_open_directory_error() {
         let path = PathBuf::from_str("doesnotexist").unwrap();
-        assert_eq!(path.exists(), false);
+        assert!(!path.exists());
         let error = OpenDirectoryError::DoesNotExist(path);
         let error: IndexError = error.into();
         assert_eq!(format!("{}", error), error.to_string());
@@ -0,0 +1,167 @@
+use std::fmt;
+
+use tantivy::query::{AllQuery, BooleanQuery, Occur, Query};
+
+use crate::prelude::*;
+
+/// Boolean expression over conditions, compiled into a single query
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expression {
+    /// Matches every document
+    All,
+    Condition(AndCondition),
+    /// Matches when every child matches, same as All when empty
+    And(Vec<Expression>),
+    /// Matches when any child matches, matches nothing when empty
+    Or(Vec<Expression>),
+    Not(Box<Expression>),
+}
+
+impl Expression {
+    pub fn all() -> Self {
+        Expression::All
+    }
+    pub fn condition(condition: AndCondition) -> Self {
+        Expression::Condition(condition)
+    }
+    pub fn and(expressions: Vec<Expression>) -> Self {
+        Expression::And(expressions)
+    }
+    pub fn or(expressions: Vec<Expression>) -> Self {
+        Expression::Or(expressions)
+    }
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(expression: Expression) -> Self {
+        Expression::Not(Box::new(expression))
+    }
+    /// Does any clause contribute a relevance score
+    pub(crate) fn is_scored<F>(&self, is_filter: &F) -> bool
+        where F: Fn(&AndCondition) -> bool {
+        match self {
+            Expression::All | Expression::Not(_) => false,
+            Expression::Condition(condition) => !is_filter(condition),
+            Expression::And(expressions) | Expression::Or(expressions) => {
+                expressions.iter().any(|e| e.is_scored(is_filter))
+            }
+        }
+    }
+    /// Builds the query, leaves are built by the caller
+    pub(crate) fn compile<F>(&self, build: &F) -> Result<Box<dyn Query>, IndexError>
+        where F: Fn(&AndCondition) -> Result<Box<dyn Query>, IndexError> {
+        let query: Box<dyn Query> = match self {
+            Expression::All => Box::new(AllQuery),
+            Expression::Condition(condition) => build(condition)?,
+            Expression::And(expressions) if expressions.is_empty() => Box::new(AllQuery),
+            Expression::And(expressions) => Self::compile_clauses(expressions, Occur::Must, build)?,
+            Expression::Or(expressions) => Self::compile_clauses(expressions, Occur::Should, build)?,
+            Expression::Not(expression) => {
+                // A query made only of exclusions matches nothing
+                let clauses: Vec<(Occur, Box<dyn Query>)> = vec![
+                    (Occur::Must, Box::new(AllQuery)),
+                    (Occur::MustNot, expression.compile(build)?),
+                ];
+                Box::new(BooleanQuery::from(clauses))
+            }
+        };
+        Ok(query)
+    }
+    fn compile_clauses<F>(expressions: &[Expression], occur: Occur, build: &F) -> Result<Box<dyn Query>, IndexError>
+        where F: Fn(&AndCondition) -> Result<Box<dyn Query>, IndexError> {
+        let mut clauses = Vec::<(Occur, Box<dyn Query>)>::with_capacity(expressions.len());
+        for expression in expressions {
+            clauses.push((occur, expression.compile(build)?));
+        };
+        Ok(Box::new(BooleanQuery::from(clauses)))
+    }
+}
+
+impl From<AndCondition> for Expression {
+    fn from(condition: AndCondition) -> Self {
+        Expression::Condition(condition)
+    }
+}
+
+/// Same semantics as the OR of ANDs in Surf::apply
+impl From<&[OrCondition]> for Expression {
+    fn from(conditions: &[OrCondition]) -> Self {
+        let mut expressions = Vec::with_capacity(conditions.len());
+        for condition in conditions {
+            let and = condition.resolve_conditions();
+            if and.is_empty() {
+                continue;
+            };
+            let and = and.iter().cloned().map(Expression::Condition).collect();
+            expressions.push(Expression::And(and));
+        };
+        Expression::Or(expressions)
+    }
+}
+
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |f: &mut fmt::Formatter<'_>, expressions: &[Expression], separator: &str| -> fmt::Result {
+            for (index, expression) in expressions.iter().enumerate() {
+                if index > 0 {
+                    write!(f, " {} ", separator)?;
+                };
+                match expression {
+                    Expression::And(_) | Expression::Or(_) => write!(f, "({})", expression)?,
+                    _ => write!(f, "{}", expression)?,
+                };
+            };
+            Ok(())
+        };
+        match self {
+            Expression::All => write!(f, "*"),
+            Expression::Condition(condition) => write!(f, "{}", condition),
+            Expression::And(expressions) if expressions.is_empty() => write!(f, "*"),
+            Expression::And(expressions) => join(f, expressions, "AND"),
+            Expression::Or(expressions) if expressions.is_empty() => write!(f, "NONE"),
+            Expression::Or(expressions) => join(f, expressions, "OR"),
+            Expression::Not(expression) => match expression.as_ref() {
+                Expression::And(_) | Expression::Or(_) => write!(f, "NOT ({})", expression),
+                _ => write!(f, "NOT {}", expression),
+            },
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_expression_display() {
+        let expression = Expression::or(vec![
+            Expression::and(vec![
+                AndCondition::new("last".to_string(), "doe".to_string()).into(),
+                AndCondition::gte("age", "18").into(),
+            ]),
+            Expression::not(AndCondition::new("first".to_string(), "jane".to_string()).into()),
+        ]);
+        assert_eq!(expression.to_string(), "(last = doe AND age >= 18) OR NOT first = jane");
+    }
+
+    #[test]
+    fn validate_expression_from_conditions() {
+        let conditions = vec![
+            OrCondition::from(("age".to_string(), "10".to_string())),
+            OrCondition::new(vec![]),
+        ];
+        let computed = Expression::from(conditions.as_slice());
+        let expected = Expression::or(vec![
+            Expression::and(vec![AndCondition::new("age".to_string(), "10".to_string()).into()]),
+        ]);
+        assert_eq!(computed, expected);
+    }
+
+    #[test]
+    fn validate_expression_scoring() {
+        let is_filter = |condition: &AndCondition| *condition.resolve_operator() != Operator::Equal;
+        let range = Expression::and(vec![AndCondition::gt("age", "1").into(), Expression::all()]);
+        assert!(!range.is_scored(&is_filter));
+        let term = Expression::or(vec![range, AndCondition::new("a".to_string(), "b".to_string()).into()]);
+        assert!(term.is_scored(&is_filter));
+    }
+}
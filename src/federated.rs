@@ -0,0 +1,173 @@
+use std::cmp::Ordering;
+
+use serde::Serialize;
+use tantivy::{DocAddress, Score};
+
+use crate::prelude::*;
+
+/// Hit from a read across several indexes, tagged with the index it came from
+#[derive(Debug, Clone, Serialize)]
+pub struct FederatedHit<T> {
+    index_name: String,
+    score: Score,
+    reference: SegmentDocRef,
+    document: T,
+}
+
+impl<T> FederatedHit<T> {
+    pub fn new(index_name: String, score: Score, reference: SegmentDocRef, document: T) -> Self {
+        Self {
+            index_name,
+            score,
+            reference,
+            document,
+        }
+    }
+    pub fn resolve_index_name(&self) -> &String {
+        &self.index_name
+    }
+    /// Zero for sorted reads unless ties are broken by score
+    pub fn resolve_score(&self) -> Score {
+        self.score
+    }
+    /// Only meaningful within the source index
+    pub fn resolve_reference(&self) -> &SegmentDocRef {
+        &self.reference
+    }
+    pub fn resolve_document(&self) -> &T {
+        &self.document
+    }
+    pub fn into_document(self) -> T {
+        self.document
+    }
+}
+
+/// Hit of one of the indexes, waiting to be merged
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Candidate {
+    position: usize,
+    score: Score,
+    key: Option<u64>,
+    address: DocAddress,
+}
+
+impl Candidate {
+    /// Ranked by score, position is the one of the index in the request
+    pub(crate) fn scored(position: usize, score: Score, address: DocAddress) -> Self {
+        Self {
+            position,
+            score,
+            key: None,
+            address,
+        }
+    }
+    /// Ranked by sort key, then tie score
+    pub(crate) fn keyed(position: usize, key: u64, score: Score, address: DocAddress) -> Self {
+        Self {
+            position,
+            score,
+            key: Some(key),
+            address,
+        }
+    }
+    pub(crate) fn resolve_position(&self) -> usize {
+        self.position
+    }
+    pub(crate) fn resolve_score(&self) -> Score {
+        self.score
+    }
+    pub(crate) fn resolve_address(&self) -> DocAddress {
+        self.address
+    }
+    /// Earlier ranked first, ties go to the index listed first
+    fn rank(&self, other: &Self) -> Ordering {
+        let ranked = match (self.key, other.key) {
+            (Some(key), Some(other_key)) => key
+                .cmp(&other_key)
+                .then_with(|| other.score.partial_cmp(&self.score).unwrap_or(Ordering::Equal)),
+            _ => other.score.partial_cmp(&self.score).unwrap_or(Ordering::Equal),
+        };
+        ranked.then_with(|| (self.position, self.address).cmp(&(other.position, other.address)))
+    }
+}
+
+/// Global page out of the hits of every index
+pub(crate) fn merge_candidates(mut candidates: Vec<Candidate>, paging: &Paging) -> Vec<Candidate> {
+    candidates.sort_by(Candidate::rank);
+    candidates
+        .into_iter()
+        .skip(paging.resolve_offset())
+        .take(paging.resolve_limit())
+        .collect()
+}
+
+/// Checks the indexes can be read together
+pub(crate) fn validate_federation(names: &[String], paging: &Paging) -> Result<(), IndexError> {
+    let message = "Unable to read across indexes".to_string();
+    if names.is_empty() {
+        let reason = "Need at least one index".to_string();
+        return Err(IndexError::new(message, reason));
+    };
+    for (position, name) in names.iter().enumerate() {
+        if names[..position].contains(name) {
+            let reason = format!("Index listed twice: {}", name);
+            return Err(IndexError::new(message, reason));
+        };
+    };
+    if paging.resolve_cursor().is_some() {
+        let reason = "Cursors don't span indexes, use an offset".to_string();
+        return Err(IndexError::new(message, reason));
+    };
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_merge_by_score() {
+        let candidates = vec![
+            Candidate::scored(0, 1f32, DocAddress(0, 0)),
+            Candidate::scored(1, 3f32, DocAddress(0, 0)),
+            Candidate::scored(0, 2f32, DocAddress(0, 1)),
+            Candidate::scored(1, 2f32, DocAddress(0, 1)),
+        ];
+        let computed = merge_candidates(candidates.clone(), &Paging::new(10, 0));
+        let expected = vec![candidates[1], candidates[2], candidates[3], candidates[0]];
+        assert_eq!(computed, expected);
+        let computed = merge_candidates(candidates.clone(), &Paging::new(2, 1));
+        assert_eq!(computed, vec![candidates[2], candidates[3]]);
+    }
+
+    #[test]
+    fn validate_merge_by_key() {
+        let candidates = vec![
+            Candidate::keyed(0, 7, 0f32, DocAddress(0, 0)),
+            Candidate::keyed(1, 3, 0f32, DocAddress(0, 0)),
+            Candidate::keyed(1, 7, 0f32, DocAddress(0, 1)),
+        ];
+        let computed = merge_candidates(candidates.clone(), &Paging::new(10, 0));
+        assert_eq!(computed, vec![candidates[1], candidates[0], candidates[2]]);
+
+        // Tie scores
+        let candidates = vec![
+            Candidate::keyed(0, 7, 1f32, DocAddress(0, 0)),
+            Candidate::keyed(1, 7, 2f32, DocAddress(0, 0)),
+        ];
+        let computed = merge_candidates(candidates.clone(), &Paging::new(10, 0));
+        assert_eq!(computed, vec![candidates[1], candidates[0]]);
+    }
+
+    #[test]
+    fn invalid_federation() {
+        let names = vec!["a".to_string(), "b".to_string()];
+        assert!(validate_federation(&names, &Paging::default()).is_ok());
+        assert!(validate_federation(&[], &Paging::default()).is_err());
+        let twice = vec!["a".to_string(), "a".to_string()];
+        assert!(validate_federation(&twice, &Paging::default()).is_err());
+        let cursor = Cursor::new(1f32, SegmentDocRef::new("0123456789abcdef0123456789abcdef".to_string(), 0));
+        assert!(validate_federation(&names, &Paging::after(10, cursor)).is_err());
+    }
+}
@@ -2,8 +2,12 @@ use std::path::PathBuf;
 use std::convert::{From, TryFrom};
 
 
+use serde::Serialize;
 use symspell;
 use symspell::{AsciiStringStrategy, SymSpell, Verbosity};
+use tantivy::query::{BooleanQuery, FuzzyTermQuery, Occur, Query};
+use tantivy::schema::Field;
+use tantivy::Term;
 use crate::prelude::*;
 
 /// One word fuzzier e.g. name city country
@@ -15,7 +19,7 @@ pub struct FuzzyWord {
 impl FuzzyWord {
     pub fn new(corpus: Option<Vec<FuzzyConfig>>) -> Self {
         let mut engine: SymSpell<AsciiStringStrategy> = SymSpell::default();
-        if let None = corpus {
+        if corpus.is_none() {
             return Self {
                 corpus,
                 engine,
@@ -33,7 +37,7 @@ impl FuzzyWord {
 
         for config in &corpus {
             engine.load_dictionary(
-                &config.corpus.as_path().to_string_lossy().to_string(),
+                &config.corpus.as_path().to_string_lossy(),
                 config.term_index,
                 config.count_index,
                 &config.separator,
@@ -45,6 +49,18 @@ impl FuzzyWord {
             engine,
         }
     }
+    /// Bootstrap from word & frequency pairs e.g. the terms of an index
+    pub fn from_words<I: IntoIterator<Item=(String, u64)>>(words: I) -> Self {
+        let mut engine: SymSpell<AsciiStringStrategy> = SymSpell::default();
+        for (word, count) in words {
+            let line = format!("{}\t{}", word, count);
+            engine.load_dictionary_line(&line, 0, 1, "\t");
+        };
+        Self {
+            corpus: None,
+            engine,
+        }
+    }
     pub fn corpus(&self) -> Option<&Vec<FuzzyConfig>> {
         self.corpus.as_ref()
     }
@@ -147,6 +163,173 @@ impl From<PathBuf> for FuzzyConfig {
     }
 }
 
+/// Typo tolerance of a term search
+/// * `distance` - Levenshtein distance, at most 2
+/// * `transposition` - Swapping two adjacent characters costs one edit instead of two
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Fuzziness {
+    distance: u8,
+    transposition: bool,
+}
+
+impl Fuzziness {
+    pub fn new(distance: u8, transposition: bool) -> Self {
+        Self {
+            distance,
+            transposition,
+        }
+    }
+    pub fn resolve_distance(&self) -> u8 {
+        self.distance
+    }
+    pub fn resolve_transposition(&self) -> bool {
+        self.transposition
+    }
+}
+
+/// One typo, swaps count as one
+impl Default for Fuzziness {
+    fn default() -> Self {
+        Self::new(1, true)
+    }
+}
+
+/// When to suggest a corrected query
+/// * `threshold` - Suggest when there are fewer hits than this
+/// * `rerun` - Search again with the suggestion & return those hits instead
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Suggest {
+    threshold: usize,
+    rerun: bool,
+}
+
+impl Suggest {
+    pub fn new(threshold: usize, rerun: bool) -> Self {
+        Self {
+            threshold,
+            rerun,
+        }
+    }
+    pub fn resolve_threshold(&self) -> usize {
+        self.threshold
+    }
+    pub fn resolve_rerun(&self) -> bool {
+        self.rerun
+    }
+}
+
+/// Suggest & search again when nothing was found
+impl Default for Suggest {
+    fn default() -> Self {
+        Self::new(1, true)
+    }
+}
+
+/// Hits along with the "did you mean" suggestion
+#[derive(Clone, Debug, Serialize)]
+pub struct Suggested<T> {
+    documents: Vec<T>,
+    suggestion: Option<String>,
+    rerun: bool,
+}
+
+impl<T> Suggested<T> {
+    pub fn new(documents: Vec<T>, suggestion: Option<String>, rerun: bool) -> Self {
+        Self {
+            documents,
+            suggestion,
+            rerun,
+        }
+    }
+    pub fn resolve_documents(&self) -> &Vec<T> {
+        &self.documents
+    }
+    pub fn into_documents(self) -> Vec<T> {
+        self.documents
+    }
+    /// Corrected query, none when every word was found
+    pub fn resolve_suggestion(&self) -> Option<&String> {
+        self.suggestion.as_ref()
+    }
+    /// Are the hits for the suggestion rather than the query
+    pub fn resolve_rerun(&self) -> bool {
+        self.rerun
+    }
+}
+
+/// Corrects each word of the query, field prefixes & operators are kept
+pub(crate) fn correct_query(speller: &FuzzyWord, query: &str) -> Option<String> {
+    let mut corrected = false;
+    let mut words = Vec::<String>::new();
+    for token in query.split_whitespace() {
+        let (prefix, word) = match token.find(':') {
+            Some(position) => token.split_at(position + 1),
+            None => ("", token),
+        };
+        let operator = word == "AND" || word == "OR" || word == "NOT";
+        if operator || word.is_empty() || !word.chars().all(char::is_alphabetic) {
+            words.push(token.to_string());
+            continue;
+        };
+        let word = word.to_lowercase();
+        let suggestion = speller
+            .lookup(&word)
+            .and_then(|suggestions| suggestions.into_iter().next());
+        match suggestion {
+            Some(suggestion) if suggestion != word => {
+                corrected = true;
+                words.push(format!("{}{}", prefix, suggestion));
+            }
+            _ => words.push(token.to_string()),
+        };
+    };
+    if corrected {
+        Some(words.join(" "))
+    } else {
+        None
+    }
+}
+
+/// Words one adjacent swap away
+fn transpositions(text: &str) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut variants = Vec::<String>::new();
+    for i in 1..chars.len() {
+        if chars[i - 1] == chars[i] {
+            continue;
+        };
+        let mut variant = chars.clone();
+        variant.swap(i - 1, i);
+        let variant: String = variant.into_iter().collect();
+        if !variants.contains(&variant) {
+            variants.push(variant);
+        };
+    };
+    variants
+}
+
+/// Fuzzy term query for an indexed term
+pub(crate) fn build_fuzzy_query(field: Field, text: &str, fuzziness: &Fuzziness) -> Result<Box<dyn Query>, IndexError> {
+    let distance = fuzziness.resolve_distance();
+    if distance > 2 {
+        let message = format!("Invalid fuzzy search: {}", text);
+        let reason = format!("Distance: {} is more than 2", distance);
+        return Err(IndexError::new(message, reason));
+    };
+    let query = FuzzyTermQuery::new(Term::from_field_text(field, text), distance, fuzziness.resolve_transposition());
+    if !fuzziness.resolve_transposition() || distance == 0 {
+        return Ok(Box::new(query));
+    };
+    // tantivy drops the transposition flag, so swaps are searched with one edit less
+    let mut clauses = Vec::<(Occur, Box<dyn Query>)>::new();
+    clauses.push((Occur::Should, Box::new(query)));
+    for variant in transpositions(text) {
+        let query = FuzzyTermQuery::new(Term::from_field_text(field, &variant), distance - 1, true);
+        clauses.push((Occur::Should, Box::new(query)));
+    };
+    Ok(Box::new(BooleanQuery::from(clauses)))
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -186,6 +369,25 @@ mod tests {
         assert_eq!(suggestions, vec!["saurav".to_string()]);
     }
 
+    #[test]
+    fn validate_transpositions() {
+        assert_eq!(transpositions("deo"), vec!["edo".to_string(), "doe".to_string()]);
+        assert_eq!(transpositions("aab"), vec!["aba".to_string()]);
+        assert!(transpositions("a").is_empty());
+        let field = Field::from_field_id(0);
+        assert!(build_fuzzy_query(field, "deo", &Fuzziness::new(3, false)).is_err());
+    }
+
+    #[test]
+    fn validate_correct_query() {
+        let words = vec![("doe".to_string(), 2), ("john".to_string(), 1), ("jane".to_string(), 1)];
+        let speller = FuzzyWord::from_words(words);
+        assert_eq!(correct_query(&speller, "deo"), Some("doe".to_string()));
+        assert_eq!(correct_query(&speller, "Jhon AND last:deo"), Some("john AND last:doe".to_string()));
+        assert_eq!(correct_query(&speller, "john doe"), None);
+        assert_eq!(correct_query(&speller, "age:20 xyzzy"), None);
+    }
+
     #[test]
     fn validate_fuzzy_word() {
         let word = FuzzyWord::new(None);
@@ -203,7 +405,7 @@ mod tests {
         let path = format!("{}/{}", home, "foo.txt");
         let path = Path::new(&path);
         {
-            let result = File::create(&path);
+            let result = File::create(path);
             assert!(result.is_ok());
         }
 
@@ -0,0 +1,141 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use tantivy::Snippet;
+
+/// How matched words are marked up in snippets
+/// * `fields` - Text fields to highlight, all of them when none
+/// * `pre_tag` & `post_tag` - Wrapped around each matched word
+/// * `fragment_length` - Maximum characters per snippet
+/// * `escape` - Escape the text as html, tags are left as they are
+#[derive(Clone, Debug, PartialEq)]
+pub struct Highlight {
+    fields: Option<Vec<String>>,
+    pre_tag: String,
+    post_tag: String,
+    fragment_length: usize,
+    escape: bool,
+}
+
+impl Highlight {
+    pub fn new(fields: Option<Vec<String>>, pre_tag: &str, post_tag: &str, fragment_length: usize, escape: bool) -> Self {
+        Self {
+            fields,
+            pre_tag: pre_tag.to_string(),
+            post_tag: post_tag.to_string(),
+            fragment_length,
+            escape,
+        }
+    }
+    pub fn resolve_fields(&self) -> Option<&Vec<String>> {
+        self.fields.as_ref()
+    }
+    pub fn resolve_pre_tag(&self) -> &String {
+        &self.pre_tag
+    }
+    pub fn resolve_post_tag(&self) -> &String {
+        &self.post_tag
+    }
+    pub fn resolve_fragment_length(&self) -> usize {
+        self.fragment_length
+    }
+    pub fn resolve_escape(&self) -> bool {
+        self.escape
+    }
+    /// Is the field part of the highlight
+    pub(crate) fn includes(&self, field_name: &str) -> bool {
+        match &self.fields {
+            Some(fields) => fields.iter().any(|f| f == field_name),
+            None => true,
+        }
+    }
+    /// Fragment with matched words wrapped in the tags
+    pub(crate) fn render(&self, snippet: &Snippet) -> String {
+        let fragment = snippet.fragments();
+        let mut rendered = String::with_capacity(fragment.len());
+        let mut start_from = 0;
+        for section in snippet.highlighted() {
+            let (start, stop) = section.bounds();
+            rendered.push_str(&self.text(&fragment[start_from..start]));
+            rendered.push_str(&self.pre_tag);
+            rendered.push_str(&self.text(&fragment[start..stop]));
+            rendered.push_str(&self.post_tag);
+            start_from = stop;
+        };
+        rendered.push_str(&self.text(&fragment[start_from..]));
+        rendered
+    }
+    fn text(&self, text: &str) -> String {
+        if !self.escape {
+            return text.to_string();
+        };
+        let mut escaped = String::with_capacity(text.len());
+        for c in text.chars() {
+            match c {
+                '&' => escaped.push_str("&amp;"),
+                '<' => escaped.push_str("&lt;"),
+                '>' => escaped.push_str("&gt;"),
+                '"' => escaped.push_str("&quot;"),
+                '\'' => escaped.push_str("&#39;"),
+                c => escaped.push(c),
+            };
+        };
+        escaped
+    }
+}
+
+/// Bold html, 150 characters, all text fields
+impl Default for Highlight {
+    fn default() -> Self {
+        Self::new(None, "<b>", "</b>", 150, true)
+    }
+}
+
+/// Document along with the highlighted snippet of each matched text field
+#[derive(Clone, Debug, Serialize)]
+pub struct Highlighted<T> {
+    document: T,
+    snippets: BTreeMap<String, String>,
+}
+
+impl<T> Highlighted<T> {
+    pub fn new(document: T, snippets: BTreeMap<String, String>) -> Self {
+        Self {
+            document,
+            snippets,
+        }
+    }
+    pub fn resolve_document(&self) -> &T {
+        &self.document
+    }
+    pub fn into_document(self) -> T {
+        self.document
+    }
+    /// Snippets by field name, fields without a match are left out
+    pub fn resolve_snippets(&self) -> &BTreeMap<String, String> {
+        &self.snippets
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_highlight_fields() {
+        let highlight = Highlight::default();
+        assert!(highlight.includes("title"));
+        let highlight = Highlight::new(Some(vec!["body".to_string()]), "[", "]", 20, false);
+        assert!(highlight.includes("body"));
+        assert!(!highlight.includes("title"));
+    }
+
+    #[test]
+    fn validate_escape() {
+        let highlight = Highlight::default();
+        assert_eq!(highlight.text("a < b & 'c'"), "a &lt; b &amp; &#39;c&#39;");
+        let highlight = Highlight::new(None, "[", "]", 20, false);
+        assert_eq!(highlight.text("a < b"), "a < b");
+    }
+}
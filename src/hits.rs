@@ -0,0 +1,208 @@
+use std::convert::TryFrom;
+use std::fmt;
+
+use serde::Serialize;
+use tantivy::{DocAddress, DocId, Score, SegmentId, Searcher};
+
+use crate::prelude::*;
+
+/// Reference to a document within one segment, only valid until the segment is merged
+///
+/// Holds the segment id rather than its position, so it survives reloads. Tantivy merges
+/// segments in the background after any commit, the reference no longer resolves from then on,
+/// nor once the document is deleted. Explanations, similar documents & cursors inherit this.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct SegmentDocRef {
+    segment: String,
+    doc: DocId,
+}
+
+impl SegmentDocRef {
+    pub fn new(segment: String, doc: DocId) -> Self {
+        Self {
+            segment,
+            doc,
+        }
+    }
+    pub fn resolve_segment(&self) -> &String {
+        &self.segment
+    }
+    pub fn resolve_doc(&self) -> DocId {
+        self.doc
+    }
+    pub(crate) fn from_address(searcher: &Searcher, address: DocAddress) -> Self {
+        let segment = searcher.segment_reader(address.0).segment_id().uuid_string();
+        Self::new(segment, address.1)
+    }
+    /// Address in the searcher, none when the segment is gone or the document deleted
+    pub(crate) fn resolve_address(&self, searcher: &Searcher) -> Option<DocAddress> {
+        let address = self.resolve_position(searcher)?;
+        if searcher.segment_reader(address.0).is_deleted(self.doc) {
+            return None;
+        };
+        Some(address)
+    }
+    /// Address in the searcher whether deleted or not, none when the segment is gone
+    pub(crate) fn resolve_position(&self, searcher: &Searcher) -> Option<DocAddress> {
+        let position = searcher
+            .segment_readers()
+            .iter()
+            .position(|reader| reader.segment_id().uuid_string() == self.segment)?;
+        let reader = searcher.segment_reader(position as u32);
+        if self.doc >= reader.max_doc() {
+            return None;
+        };
+        Some(DocAddress(position as u32, self.doc))
+    }
+}
+
+impl fmt::Display for SegmentDocRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.segment, self.doc)
+    }
+}
+
+impl Serialize for SegmentDocRef {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl TryFrom<&str> for SegmentDocRef {
+    type Error = IndexError;
+    fn try_from(reference: &str) -> Result<Self, Self::Error> {
+        let invalid = || IndexError::new("Invalid document reference", reference);
+        let mut parts = reference.split('/');
+        let segment = parts.next().ok_or_else(invalid)?;
+        let doc = parts.next().ok_or_else(invalid)?;
+        if parts.next().is_some() {
+            return Err(invalid());
+        };
+        let segment = SegmentId::from_uuid_string(segment).map_err(|_| invalid())?;
+        let doc = doc.parse::<DocId>().map_err(|_| invalid())?;
+        Ok(Self::new(segment.uuid_string(), doc))
+    }
+}
+
+/// Document along with its relevance score
+#[derive(Debug, Clone, Serialize)]
+pub struct Hit<T> {
+    score: Score,
+    reference: SegmentDocRef,
+    document: T,
+}
+
+impl<T> Hit<T> {
+    pub fn new(score: Score, reference: SegmentDocRef, document: T) -> Self {
+        Self {
+            score,
+            reference,
+            document,
+        }
+    }
+    pub fn resolve_score(&self) -> Score {
+        self.score
+    }
+    pub fn resolve_reference(&self) -> &SegmentDocRef {
+        &self.reference
+    }
+    pub fn resolve_document(&self) -> &T {
+        &self.document
+    }
+    pub fn into_document(self) -> T {
+        self.document
+    }
+}
+
+/// Hit left out because it scored below the cutoff
+#[derive(Debug, Clone, Serialize)]
+pub struct Dropped {
+    score: Score,
+    cutoff: Score,
+    margin: Score,
+    reference: SegmentDocRef,
+}
+
+impl Dropped {
+    pub fn new(score: Score, cutoff: Score, reference: SegmentDocRef) -> Self {
+        Self {
+            score,
+            cutoff,
+            margin: cutoff - score,
+            reference,
+        }
+    }
+    pub fn resolve_score(&self) -> Score {
+        self.score
+    }
+    pub fn resolve_cutoff(&self) -> Score {
+        self.cutoff
+    }
+    /// How far below the cutoff the hit scored
+    pub fn resolve_margin(&self) -> Score {
+        self.margin
+    }
+    pub fn resolve_reference(&self) -> &SegmentDocRef {
+        &self.reference
+    }
+}
+
+/// Documents read along with the hits the cutoff dropped
+#[derive(Debug, Clone, Serialize)]
+pub struct Debugged<T> {
+    documents: Vec<T>,
+    dropped: Vec<Dropped>,
+}
+
+impl<T> Debugged<T> {
+    pub fn new(documents: Vec<T>, dropped: Vec<Dropped>) -> Self {
+        Self {
+            documents,
+            dropped,
+        }
+    }
+    pub fn resolve_documents(&self) -> &Vec<T> {
+        &self.documents
+    }
+    pub fn into_documents(self) -> Vec<T> {
+        self.documents
+    }
+    /// Closest misses first
+    pub fn resolve_dropped(&self) -> &Vec<Dropped> {
+        &self.dropped
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_segment_doc_ref() {
+        let segment = "3dd4b4b8b2ab4a4a9c0c3c9c3e4b7c1d".to_string();
+        let reference = SegmentDocRef::new(segment.clone(), 42);
+        let token = reference.to_string();
+        assert_eq!(token, format!("{}/42", segment));
+        assert_eq!(SegmentDocRef::try_from(token.as_str()).unwrap(), reference);
+        assert_eq!(serde_json::to_string(&reference).unwrap(), format!("\"{}\"", token));
+    }
+
+    #[test]
+    fn validate_dropped_margin() {
+        let reference = SegmentDocRef::new("3dd4b4b8b2ab4a4a9c0c3c9c3e4b7c1d".to_string(), 1);
+        let dropped = Dropped::new(60f32, 90f32, reference);
+        assert_eq!(dropped.resolve_margin(), 30f32);
+        let json = serde_json::to_value(&dropped).unwrap();
+        assert_eq!(json["margin"], 30f64);
+    }
+
+    #[test]
+    fn invalid_segment_doc_ref() {
+        assert!(SegmentDocRef::try_from("").is_err());
+        assert!(SegmentDocRef::try_from("3dd4b4b8b2ab4a4a9c0c3c9c3e4b7c1d").is_err());
+        assert!(SegmentDocRef::try_from("zz/1").is_err());
+        assert!(SegmentDocRef::try_from("3dd4b4b8b2ab4a4a9c0c3c9c3e4b7c1d/x").is_err());
+        assert!(SegmentDocRef::try_from("3dd4b4b8b2ab4a4a9c0c3c9c3e4b7c1d/1/2").is_err());
+    }
+}
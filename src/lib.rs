@@ -206,6 +206,18 @@ pub mod errors;
 pub mod utils;
 pub mod registry;
 pub mod fuzzy;
+pub mod paging;
+pub mod sorting;
+pub mod expression;
+pub mod sql;
+pub mod highlight;
+pub mod hits;
+pub mod aggregation;
+pub mod stats;
+pub mod scan;
+pub mod federated;
+pub mod similar;
+pub mod relevance;
 
 #[cfg(test)]
 mod tests {
@@ -213,7 +225,7 @@ mod tests {
 
     use serde::{Serialize};
 
-    use serde_value;
+    
     use std::collections::BTreeMap;
     use std::fmt;
     use tantivy::schema::{Schema, IntOptions, TEXT, STORED};
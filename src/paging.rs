@@ -0,0 +1,458 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::convert::TryFrom;
+use std::fmt;
+
+use serde::{Serialize};
+
+use tantivy::collector::{Collector, SegmentCollector};
+use tantivy::{DocAddress, DocId, Score, SegmentId, SegmentLocalId, SegmentReader, Searcher};
+
+use crate::prelude::*;
+
+/// Where a result page starts & how long it is
+#[derive(Debug, Clone, PartialEq)]
+pub struct Paging {
+    limit: usize,
+    offset: usize,
+    cursor: Option<Cursor>,
+}
+
+impl Paging {
+    /// Skip offset hits then take limit
+    pub fn new(limit: usize, offset: usize) -> Self {
+        Self {
+            limit,
+            offset,
+            cursor: None,
+        }
+    }
+    /// Take limit hits ranked after the cursor
+    pub fn after(limit: usize, cursor: Cursor) -> Self {
+        Self {
+            limit,
+            offset: 0,
+            cursor: Some(cursor),
+        }
+    }
+    pub fn resolve_limit(&self) -> usize {
+        self.limit
+    }
+    pub fn resolve_offset(&self) -> usize {
+        self.offset
+    }
+    pub fn resolve_cursor(&self) -> Option<&Cursor> {
+        self.cursor.as_ref()
+    }
+    /// Cursor placed in the searcher, if any, keyed for sorted reads & unkeyed for score reads
+    pub(crate) fn resolve_position(&self, searcher: &Searcher, keyed: bool) -> Result<Option<Position>, IndexError> {
+        self.cursor
+            .as_ref()
+            .map(|cursor| cursor.resolve_position(searcher, keyed))
+            .transpose()
+    }
+}
+
+/// First ten hits
+impl Default for Paging {
+    fn default() -> Self {
+        Self::new(10, 0)
+    }
+}
+
+/// Position of a hit in the ranking: score or sort key & tie score first, then doc address
+///
+/// Holds a document reference rather than the address, so it survives reloads.
+/// It goes stale once the segment of the hit is merged away.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cursor {
+    score: f32,
+    key: Option<u64>,
+    reference: SegmentDocRef,
+}
+
+impl Cursor {
+    pub fn new(score: f32, reference: SegmentDocRef) -> Self {
+        Self {
+            score,
+            key: None,
+            reference,
+        }
+    }
+    /// Cursor of a hit from a sorted read
+    pub fn keyed(key: u64, reference: SegmentDocRef) -> Self {
+        Self::keyed_with_score(key, 0f32, reference)
+    }
+    /// Cursor of a hit from a sorted read with ties broken by score
+    pub fn keyed_with_score(key: u64, score: f32, reference: SegmentDocRef) -> Self {
+        Self {
+            score,
+            key: Some(key),
+            reference,
+        }
+    }
+    pub fn resolve_score(&self) -> f32 {
+        self.score
+    }
+    pub fn resolve_key(&self) -> Option<u64> {
+        self.key
+    }
+    pub fn resolve_reference(&self) -> &SegmentDocRef {
+        &self.reference
+    }
+    /// Opaque token to hand out to clients
+    pub fn to_token(&self) -> String {
+        let token = format!("{:08x}.{}.{}", self.score.to_bits(), self.reference.resolve_segment(), self.reference.resolve_doc());
+        match self.key {
+            Some(key) => format!("{}.{:x}", token, key),
+            None => token,
+        }
+    }
+    /// Placed in the searcher, fails when the segment of the hit is gone or the cursor is of another kind of read
+    pub(crate) fn resolve_position(&self, searcher: &Searcher, keyed: bool) -> Result<Position, IndexError> {
+        match (keyed, self.key.is_some()) {
+            (true, false) => return Err(IndexError::new("Invalid cursor", "Sorted reads need the cursor of a sorted read")),
+            (false, true) => return Err(IndexError::new("Invalid cursor", "Score reads need the cursor of a score read")),
+            _ => {}
+        };
+        let address = self.reference.resolve_position(searcher).ok_or_else(|| {
+            let reason = format!("Segment is gone: {}", self.reference.resolve_segment());
+            IndexError::new("Invalid cursor", &reason)
+        })?;
+        Ok(Position {
+            score: self.score,
+            key: self.key,
+            address,
+        })
+    }
+}
+
+/// Cursor placed in a searcher, the address only holds for that searcher
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Position {
+    score: f32,
+    key: Option<u64>,
+    address: DocAddress,
+}
+
+impl Position {
+    /// Is the hit sorted after this position
+    pub(crate) fn is_before_key(&self, key: u64, score: f32, address: DocAddress) -> bool {
+        match key.cmp(&self.key.unwrap_or(0)) {
+            Ordering::Greater => true,
+            Ordering::Equal => self.is_before(score, address),
+            Ordering::Less => false,
+        }
+    }
+    /// Is the hit ranked after this position
+    pub(crate) fn is_before(&self, score: f32, address: DocAddress) -> bool {
+        match score.partial_cmp(&self.score) {
+            Some(Ordering::Less) => true,
+            Some(Ordering::Equal) => address > self.address,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Cursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_token())
+    }
+}
+
+impl Serialize for Cursor {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_token())
+    }
+}
+
+impl TryFrom<&str> for Cursor {
+    type Error = IndexError;
+    fn try_from(token: &str) -> Result<Self, Self::Error> {
+        let invalid = || IndexError::new("Invalid cursor", token);
+        let parts: Vec<&str> = token.split('.').collect();
+        if parts.len() != 3 && parts.len() != 4 {
+            return Err(invalid());
+        };
+        let score = u32::from_str_radix(parts[0], 16).map_err(|_| invalid())?;
+        let segment = SegmentId::from_uuid_string(parts[1]).map_err(|_| invalid())?;
+        let doc = parts[2].parse::<DocId>().map_err(|_| invalid())?;
+        let reference = SegmentDocRef::new(segment.uuid_string(), doc);
+        if parts.len() == 3 {
+            return Ok(Self::new(f32::from_bits(score), reference));
+        };
+        let key = u64::from_str_radix(parts[3], 16).map_err(|_| invalid())?;
+        Ok(Self::keyed_with_score(key, f32::from_bits(score), reference))
+    }
+}
+
+/// One page of results along with the total number of hits
+#[derive(Debug, Clone, Serialize)]
+pub struct Page<T> {
+    documents: Vec<T>,
+    total: usize,
+    cursor: Option<Cursor>,
+}
+
+impl<T> Page<T> {
+    pub fn new(documents: Vec<T>, total: usize, cursor: Option<Cursor>) -> Self {
+        Self {
+            documents,
+            total,
+            cursor,
+        }
+    }
+    pub fn resolve_documents(&self) -> &Vec<T> {
+        &self.documents
+    }
+    pub fn into_documents(self) -> Vec<T> {
+        self.documents
+    }
+    /// Total hits across all pages
+    pub fn resolve_total(&self) -> usize {
+        self.total
+    }
+    /// Cursor for the next page, none when this was the last one
+    pub fn resolve_cursor(&self) -> Option<&Cursor> {
+        self.cursor.as_ref()
+    }
+}
+
+/// Ranked hit, greater means ranked earlier
+#[derive(PartialEq)]
+struct Ranked(Score, DocAddress);
+
+impl Eq for Ranked {}
+
+impl Ord for Ranked {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0
+            .partial_cmp(&other.0)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| other.1.cmp(&self.1))
+    }
+}
+
+impl PartialOrd for Ranked {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Keeps the best limit hits
+fn push_ranked(heap: &mut BinaryHeap<Reverse<Ranked>>, limit: usize, ranked: Ranked) {
+    if heap.len() < limit {
+        heap.push(Reverse(ranked));
+    } else if let Some(mut worst) = heap.peek_mut() {
+        if worst.0 < ranked {
+            *worst = Reverse(ranked);
+        }
+    }
+}
+
+fn into_sorted(heap: BinaryHeap<Reverse<Ranked>>) -> Vec<(Score, DocAddress)> {
+    heap.into_sorted_vec()
+        .into_iter()
+        .map(|Reverse(Ranked(score, address))| (score, address))
+        .collect()
+}
+
+/// Same ranking as TopDocs, only hits ranked after the cursor are collected
+pub(crate) struct TopDocsAfter {
+    limit: usize,
+    cursor: Position,
+}
+
+impl TopDocsAfter {
+    pub(crate) fn new(limit: usize, cursor: Position) -> Self {
+        Self {
+            limit,
+            cursor,
+        }
+    }
+}
+
+pub(crate) struct TopDocsAfterSegment {
+    limit: usize,
+    cursor: Position,
+    segment: SegmentLocalId,
+    heap: BinaryHeap<Reverse<Ranked>>,
+}
+
+impl Collector for TopDocsAfter {
+    type Fruit = Vec<(Score, DocAddress)>;
+    type Child = TopDocsAfterSegment;
+
+    fn for_segment(&self, segment_local_id: SegmentLocalId, _: &SegmentReader) -> tantivy::Result<Self::Child> {
+        Ok(TopDocsAfterSegment {
+            limit: self.limit,
+            cursor: self.cursor,
+            segment: segment_local_id,
+            heap: BinaryHeap::with_capacity(self.limit),
+        })
+    }
+
+    fn requires_scoring(&self) -> bool {
+        true
+    }
+
+    fn merge_fruits(&self, segment_fruits: Vec<Self::Fruit>) -> tantivy::Result<Self::Fruit> {
+        let mut heap = BinaryHeap::with_capacity(self.limit);
+        for fruit in segment_fruits {
+            for (score, address) in fruit {
+                push_ranked(&mut heap, self.limit, Ranked(score, address));
+            }
+        };
+        Ok(into_sorted(heap))
+    }
+}
+
+impl SegmentCollector for TopDocsAfterSegment {
+    type Fruit = Vec<(Score, DocAddress)>;
+
+    fn collect(&mut self, doc: DocId, score: Score) {
+        let address = DocAddress(self.segment, doc);
+        if self.cursor.is_before(score, address) {
+            push_ranked(&mut self.heap, self.limit, Ranked(score, address));
+        };
+    }
+
+    fn harvest(self) -> Self::Fruit {
+        into_sorted(self.heap)
+    }
+}
+
+
+/// Passes on to the wrapped collector only the hits scored at or above the cutoff
+pub(crate) struct AboveScore<C> {
+    cutoff: Score,
+    collector: C,
+}
+
+impl<C> AboveScore<C> {
+    pub(crate) fn new(cutoff: Score, collector: C) -> Self {
+        Self {
+            cutoff,
+            collector,
+        }
+    }
+}
+
+pub(crate) struct AboveScoreSegment<C> {
+    cutoff: Score,
+    collector: C,
+}
+
+impl<C: Collector> Collector for AboveScore<C> {
+    type Fruit = C::Fruit;
+    type Child = AboveScoreSegment<C::Child>;
+
+    fn for_segment(&self, segment_local_id: SegmentLocalId, segment: &SegmentReader) -> tantivy::Result<Self::Child> {
+        Ok(AboveScoreSegment {
+            cutoff: self.cutoff,
+            collector: self.collector.for_segment(segment_local_id, segment)?,
+        })
+    }
+
+    fn requires_scoring(&self) -> bool {
+        true
+    }
+
+    fn merge_fruits(&self, segment_fruits: Vec<Self::Fruit>) -> tantivy::Result<Self::Fruit> {
+        self.collector.merge_fruits(segment_fruits)
+    }
+}
+
+impl<C: SegmentCollector> SegmentCollector for AboveScoreSegment<C> {
+    type Fruit = C::Fruit;
+
+    fn collect(&mut self, doc: DocId, score: Score) {
+        if score >= self.cutoff {
+            self.collector.collect(doc, score);
+        };
+    }
+
+    fn harvest(self) -> Self::Fruit {
+        self.collector.harvest()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEGMENT: &str = "0123456789abcdef0123456789abcdef";
+
+    fn reference(doc: DocId) -> SegmentDocRef {
+        SegmentDocRef::new(SEGMENT.to_string(), doc)
+    }
+
+    fn position(score: f32, key: Option<u64>, address: DocAddress) -> Position {
+        Position {
+            score,
+            key,
+            address,
+        }
+    }
+
+    #[test]
+    fn validate_cursor_token() {
+        let cursor = Cursor::new(1.25, reference(42));
+        let token = cursor.to_token();
+        let computed = Cursor::try_from(token.as_str()).unwrap();
+        assert_eq!(computed, cursor);
+        assert_eq!(format!("{}", cursor), token);
+        assert_eq!(serde_json::to_string(&cursor).unwrap(), format!("\"{}\"", token));
+
+        let cursor = Cursor::keyed(u64::MAX - 7, reference(2));
+        let token = cursor.to_token();
+        let computed = Cursor::try_from(token.as_str()).unwrap();
+        assert_eq!(computed, cursor);
+        assert_eq!(computed.resolve_key(), Some(u64::MAX - 7));
+
+        let cursor = Cursor::keyed_with_score(3, 0.75, reference(2));
+        let computed = Cursor::try_from(cursor.to_token().as_str()).unwrap();
+        assert_eq!(computed, cursor);
+    }
+
+    #[test]
+    fn invalid_cursor_token() {
+        assert!(Cursor::try_from("").is_err());
+        assert!(Cursor::try_from("zz.1.2").is_err());
+        assert!(Cursor::try_from("3fa00000.1").is_err());
+        assert!(Cursor::try_from("3fa00000.-1.2").is_err());
+        assert!(Cursor::try_from("3fa00000.1.2.zz").is_err());
+        assert!(Cursor::try_from("3fa00000.1.2.3.4").is_err());
+        // Segment ordinals are not accepted, they shift on reloads
+        assert!(Cursor::try_from("3fa00000.1.2").is_err());
+        assert!(Cursor::try_from(format!("3fa00000.{}.2", SEGMENT).as_str()).is_ok());
+    }
+
+    #[test]
+    fn validate_cursor_ordering() {
+        let cursor = position(1.0, None, DocAddress(0, 5));
+        assert!(cursor.is_before(0.5, DocAddress(0, 1)));
+        assert!(cursor.is_before(1.0, DocAddress(0, 6)));
+        assert!(cursor.is_before(1.0, DocAddress(1, 0)));
+        assert!(!cursor.is_before(1.0, DocAddress(0, 5)));
+        assert!(!cursor.is_before(1.0, DocAddress(0, 4)));
+        assert!(!cursor.is_before(2.0, DocAddress(0, 9)));
+
+        let cursor = position(1.0, Some(3), DocAddress(0, 5));
+        assert!(cursor.is_before_key(4, 9.0, DocAddress(0, 0)));
+        assert!(cursor.is_before_key(3, 0.5, DocAddress(0, 0)));
+        assert!(cursor.is_before_key(3, 1.0, DocAddress(0, 6)));
+        assert!(!cursor.is_before_key(3, 2.0, DocAddress(0, 9)));
+        assert!(!cursor.is_before_key(2, 0.0, DocAddress(0, 9)));
+    }
+
+    #[test]
+    fn validate_ranked_heap() {
+        let mut heap = BinaryHeap::new();
+        push_ranked(&mut heap, 2, Ranked(1.0, DocAddress(0, 2)));
+        push_ranked(&mut heap, 2, Ranked(2.0, DocAddress(0, 3)));
+        push_ranked(&mut heap, 2, Ranked(1.0, DocAddress(0, 1)));
+        let computed = into_sorted(heap);
+        assert_eq!(computed, vec![(2.0, DocAddress(0, 3)), (1.0, DocAddress(0, 1))]);
+    }
+}
@@ -1,4 +1,4 @@
-pub use crate::registry::{Surfer, SurferBuilder, SurferSchema, Control, AndCondition, OrCondition, Surf};
+pub use crate::registry::{Surfer, SurferBuilder, SurferSchema, Control, AndCondition, OrCondition, Operator, Surf, RejectedDocument};
 pub use crate::errors::IndexError;
 
 pub use crate::utils::field_names;
@@ -12,7 +12,20 @@ pub(crate) use crate::seed::open_index;
 pub(crate) use crate::seed::open_mmap_directory;
 pub(crate) use crate::seed::open_index_writer;
 pub(crate) use crate::seed::open_index_reader;
+pub(crate) use crate::seed::open_snapshot_reader;
 pub(crate) use crate::seed::resolve_home;
 pub(crate) use crate::seed::resolve_index_directory_path;
 
-pub use crate::fuzzy::{FuzzyConfig, FuzzyWord};
+pub use crate::fuzzy::{FuzzyConfig, FuzzyWord, Fuzziness, Suggest, Suggested};
+pub use crate::paging::{Paging, Page, Cursor};
+pub use crate::sorting::{Sort, SortOrder, Tiebreak};
+pub use crate::expression::Expression;
+pub use crate::sql::Statement;
+pub use crate::highlight::{Highlight, Highlighted};
+pub use crate::hits::{Hit, SegmentDocRef, Dropped, Debugged};
+pub use crate::aggregation::{Aggregation, DateInterval, Bucket, BucketKey, Buckets, Aggregated};
+pub use crate::stats::{Stats, Percentile};
+pub use crate::scan::Scan;
+pub use crate::federated::FederatedHit;
+pub use crate::similar::MoreLikeThis;
+pub use crate::relevance::Cutoff;
@@ -1,55 +1,170 @@
 use std::collections::{HashMap, BTreeMap, HashSet};
+use std::collections::hash_map::Entry;
 use std::convert::TryFrom;
 use std::ops::{Deref, DerefMut};
 use std::fmt;
 use std::hash::{Hash, Hasher};
-
-use tantivy::schema::{Schema, Field, TextOptions, IntOptions, IndexRecordOption};
-use tantivy::{Index, IndexReader, IndexWriter, Document, Term, DocAddress};
-use tantivy::query::{QueryParser, TermQuery};
-use tantivy::collector::{TopDocs};
+use std::fs::remove_dir_all;
+use std::ops::Bound;
+use std::str::FromStr;
+
+use tantivy::schema::{Schema, Field, FieldType, TextOptions, IntOptions, IndexRecordOption, INDEXED, FAST};
+use tantivy::{Index, IndexReader, IndexWriter, Document, Term, DocAddress, DocId, Searcher, SegmentReader, SnippetGenerator};
+use tantivy::query::{QueryParser, TermQuery, RangeQuery, RegexQuery, PhraseQuery, BooleanQuery, BoostQuery, Occur, Query};
+use tantivy::collector::{TopDocs, Count};
+use tantivy::fastfield::{FastValue, FastFieldReader};
 use tantivy::schema::Value as SchemaValue;
 
 
 use crate::prelude::*;
 use crate::prelude::join;
+use crate::paging::{TopDocsAfter, AboveScore};
+use crate::sorting::TopDocsByField;
+use crate::fuzzy::{build_fuzzy_query, correct_query};
+use crate::aggregation::AggregationCollector;
+use crate::stats::StatsCollector;
+use crate::federated::{Candidate, merge_candidates, validate_federation};
 
 use serde::{Serialize};
 use serde::de::DeserializeOwned;
 use std::fmt::{Debug, Display};
 
+/// Top docs as scored by tantivy
+type ScoredAddresses = Vec<(f32, DocAddress)>;
+
+/// Hidden field giving every document a unique id, deletes by address go through it
+///
+/// Indexes written before it existed are opened without it & fall back to deleting by terms.
+const ID_FIELD: &str = "_surf_id";
 
 #[derive(Clone, Eq, PartialEq)]
+#[non_exhaustive]
 pub enum SurferFieldTypes {
     U64,
     I64,
     F64,
     String,
     Bytes,
+    /// Indexed as text & searched like it, read back as true or false
+    Bool,
 }
 
-#[derive(Clone, Eq, PartialEq)]
+impl SurferFieldTypes {
+    /// Fields full text reads & deletes by value search
+    pub(crate) fn is_text(&self) -> bool {
+        matches!(self, SurferFieldTypes::String | SurferFieldTypes::Bool)
+    }
+}
+
+#[derive(Clone)]
 pub struct SurferSchema {
     schema: Schema,
     mappings: HashMap<String, SurferFieldTypes>,
     track_tf: bool,
     track_tf_idf: bool,
+    boosts: HashMap<String, f32>,
+    default_fields: Option<Vec<String>>,
 }
 
 impl SurferSchema {
     pub fn new(schema: Schema, mappings: HashMap<String, SurferFieldTypes>, track_tf: bool, track_tf_idf: bool) -> Self {
+        let schema = with_id_field(schema);
         Self {
             schema,
             mappings,
             track_tf,
             track_tf_idf,
+            boosts: HashMap::new(),
+            default_fields: None,
         }
     }
     pub fn resolve_mapping(&self) -> &HashMap<String, SurferFieldTypes> {
         &self.mappings
     }
+    /// Multiplies the score of hits on the field, 1.0 is neutral
+    pub fn set_field_boost(&mut self, field_name: &str, boost: f32) -> Result<(), IndexError> {
+        let message = format!("Unable to boost field: {}", field_name);
+        if !self.mappings.contains_key(field_name) {
+            let reason = format!("Missing field: {}", field_name);
+            return Err(IndexError::new(message, reason));
+        };
+        if !boost.is_finite() || boost <= 0f32 {
+            let reason = format!("Boost must be a positive number: {}", boost);
+            return Err(IndexError::new(message, reason));
+        };
+        self.boosts.insert(field_name.to_string(), boost);
+        Ok(())
+    }
+    pub fn resolve_field_boost(&self, field_name: &str) -> Option<f32> {
+        self.boosts.get(field_name).copied()
+    }
+    pub fn resolve_field_boosts(&self) -> &HashMap<String, f32> {
+        &self.boosts
+    }
+    /// Text fields searched by queries without a field prefix, all text fields when not set
+    pub fn set_default_fields(&mut self, field_names: &[String]) -> Result<(), IndexError> {
+        let message = "Unable to set default fields".to_string();
+        if field_names.is_empty() {
+            let reason = "Need at least one field".to_string();
+            return Err(IndexError::new(message, reason));
+        };
+        for field_name in field_names {
+            if self.mappings.get(field_name) != Some(&SurferFieldTypes::String) {
+                let reason = format!("Default fields need a text field: {}", field_name);
+                return Err(IndexError::new(message, reason));
+            };
+        };
+        self.default_fields = Some(field_names.to_vec());
+        Ok(())
+    }
+    pub fn resolve_default_fields(&self) -> Option<&Vec<String>> {
+        self.default_fields.as_ref()
+    }
+    /// None for indexes written before the hidden id existed
+    pub(crate) fn resolve_id_field(&self) -> Option<Field> {
+        self.schema.get_field(ID_FIELD)
+    }
+    /// Copy without the hidden id when the index on disk has none
+    pub(crate) fn aligned_with(&self, schema: &Schema) -> Self {
+        if schema.get_field(ID_FIELD).is_some() || self.resolve_id_field().is_none() {
+            return self.clone();
+        };
+        let mut builder = Schema::builder();
+        for (_, entry) in self.schema.fields() {
+            if entry.name() != ID_FIELD {
+                builder.add_field(entry.clone());
+            };
+        };
+        let mut aligned = self.clone();
+        aligned.schema = builder.build();
+        aligned
+    }
+    /// Copy with the given boosts taking over the configured ones
+    pub(crate) fn with_field_boosts(&self, boosts: &HashMap<String, f32>) -> Result<Self, IndexError> {
+        let mut schema = self.clone();
+        for (field_name, boost) in boosts {
+            schema.set_field_boost(field_name, *boost)?;
+        };
+        Ok(schema)
+    }
+}
+
+impl PartialEq for SurferSchema {
+    fn eq(&self, other: &Self) -> bool {
+        let boosts = self.boosts.len() == other.boosts.len() && self.boosts
+            .iter()
+            .all(|(field_name, boost)| other.boosts.get(field_name).map(|other| other.to_bits()) == Some(boost.to_bits()));
+        self.schema == other.schema
+            && self.mappings == other.mappings
+            && self.track_tf == other.track_tf
+            && self.track_tf_idf == other.track_tf_idf
+            && self.default_fields == other.default_fields
+            && boosts
+    }
 }
 
+impl Eq for SurferSchema {}
+
 impl Deref for SurferSchema {
     type Target = Schema;
     fn deref(&self) -> &Self::Target {
@@ -82,10 +197,76 @@ impl fmt::Display for SurferSchema {
             let debug = format!("Name: {} Type: {:?}\n", entry.name(), entry.field_type().value_type());
             let _ = write!(f, "{}", debug);
         };
-        write!(f, "\n")
+        writeln!(f)
     }
 }
 
+/// Appends the hidden id field, indexed to delete by & fast to read back per hit
+fn with_id_field(schema: Schema) -> Schema {
+    if schema.get_field(ID_FIELD).is_some() {
+        return schema;
+    };
+    let mut builder = Schema::builder();
+    for (_, entry) in schema.fields() {
+        builder.add_field(entry.clone());
+    };
+    builder.add_u64_field(ID_FIELD, INDEXED | FAST);
+    builder.build()
+}
+
+/// Reads a document of the searcher, also used by scans which hold a searcher of their own
+pub(crate) fn read_document(schema: &SurferSchema, searcher: &Searcher, doc_address: DocAddress) -> Result<Document, IndexError> {
+    let mut document = searcher.doc(doc_address)?;
+    add_byte_arrays(schema, searcher.segment_reader(doc_address.0), doc_address.1, &mut document);
+    Ok(document)
+}
+
+/// Byte arrays aren't in the doc store, they are copied over from the fast fields
+fn add_byte_arrays(schema: &SurferSchema, segment_reader: &SegmentReader, doc: DocId, document: &mut Document) {
+    let fast_fields = segment_reader.fast_fields();
+    for (field_name, field_type) in schema.resolve_mapping() {
+        if *field_type != SurferFieldTypes::Bytes {
+            continue;
+        };
+        let bytes = schema.get_field(field_name).and_then(|field| fast_fields.bytes(field).map(|bytes| (field, bytes)));
+        if let Some((field, bytes)) = bytes {
+            document.add_bytes(field, bytes.get_bytes(doc).to_vec());
+        };
+    };
+}
+
+/// Json out of a document, typed from the mappings, only the given fields when some
+pub(crate) fn to_value(name: &str, schema: &SurferSchema, document: &Document, fields: Option<&[String]>) -> Result<serde_json::Value, IndexError> {
+    let mappings = schema.resolve_mapping();
+
+    let mut object = serde_json::Map::new();
+    for (field, field_values) in document.get_sorted_field_values() {
+        let field_name = schema.get_field_name(field);
+        if let Some(fields) = fields {
+            if !fields.iter().any(|f| f == field_name) {
+                continue;
+            };
+        };
+        let fv = field_values.first();
+        if fv.is_none() {
+            let message = format!("Unable to jsonify: {}", name);
+            let reason = format!("Field: {} does not have any value", field_name);
+            let error = IndexError::new(message, reason);
+            return Err(error);
+        };
+        let value = match (mappings.get(field_name), fv.unwrap().value()) {
+            (Some(SurferFieldTypes::Bool), SchemaValue::Str(text)) => serde_json::Value::Bool(text == "true"),
+            (_, SchemaValue::Str(text)) => serde_json::Value::from(text.as_str()),
+            (_, SchemaValue::U64(value)) => serde_json::Value::from(*value),
+            (_, SchemaValue::I64(value)) => serde_json::Value::from(*value),
+            (_, SchemaValue::F64(value)) => serde_json::Value::from(*value),
+            (_, SchemaValue::Bytes(value)) => serde_json::Value::from(value.clone()),
+            (_, value) => serde_json::to_value(value)?,
+        };
+        object.insert(field_name.to_string(), value);
+    };
+    Ok(serde_json::Value::Object(object))
+}
 
 /// Builder struct for Surfer
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -102,18 +283,15 @@ impl fmt::Display for SurferBuilder {
             let home = resolve_index_directory_path(name, home);
             let home = match home {
                 Ok(h) => h.to_string_lossy().to_string(),
-                Err(e) => format!("<PathError {}>", e.to_string())
+                Err(e) => format!("<PathError {}>", e)
             };
-            let _ = write!(f, "Index: {} Location: {}\n", name, home);
+            let _ = writeln!(f, "Index: {} Location: {}", name, home);
             let _ = write!(f, "{}", schema);
         }
-        write!(f, "\n")
+        writeln!(f)
     }
 }
 
-#[derive(Serialize)]
-struct SingleValuedNamedFieldDocument<'a>(BTreeMap<&'a str, &'a SchemaValue>);
-
 /// Default impl to get things going
 impl Default for SurferBuilder {
     fn default() -> Self {
@@ -151,21 +329,161 @@ impl SurferBuilder {
     pub fn add_struct<T: Serialize>(&mut self, name: String, data: &T) {
         self.add_serde::<T>(name, data);
     }
+    /// Add a serializable rust struct with per field options e.g. fast fields
+    pub fn add_struct_with_control<T: Serialize>(&mut self, name: String, data: &T, control: &HashMap<String, Control>) -> Result<(), IndexError> {
+        let (schema, mappings) = to_schema(data, Some(control))?;
+        let schema = SurferSchema::new(schema, mappings, false, false);
+        self.schemas.insert(name, schema);
+        Ok(())
+    }
+}
+
+/// Comparison of a field against the condition value
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Operator {
+    Equal,
+    GreaterThan,
+    GreaterOrEqual,
+    LessThan,
+    LessOrEqual,
+    /// Inclusive on both ends, holds the upper bound
+    Between(String),
+    /// Words in order, holds how many extra positions may sit between them
+    Phrase(u32),
+    /// Terms starting with the value e.g. jo*
+    Prefix,
+    /// Terms matching the pattern, * for any run & ? for any one character
+    Wildcard,
+    /// Terms matching the regular expression
+    Regex,
+    /// Terms within a few typos of each word
+    Fuzzy(Fuzziness),
+}
+
+impl Operator {
+    /// Numeric comparisons backed by a range query
+    pub fn is_comparison(&self) -> bool {
+        matches!(self, Operator::GreaterThan | Operator::GreaterOrEqual | Operator::LessThan | Operator::LessOrEqual | Operator::Between(_))
+    }
+    /// Text matches backed by a phrase or regex query
+    pub fn is_text_match(&self) -> bool {
+        matches!(self, Operator::Phrase(_) | Operator::Prefix | Operator::Wildcard | Operator::Regex | Operator::Fuzzy(_))
+    }
+    /// Range form of the operator, none for text matches
+    fn resolve_range(&self) -> Option<RangeOperator> {
+        match self {
+            Operator::Equal => Some(RangeOperator::Equal),
+            Operator::GreaterThan => Some(RangeOperator::GreaterThan),
+            Operator::GreaterOrEqual => Some(RangeOperator::GreaterOrEqual),
+            Operator::LessThan => Some(RangeOperator::LessThan),
+            Operator::LessOrEqual => Some(RangeOperator::LessOrEqual),
+            Operator::Between(_) => Some(RangeOperator::Between),
+            Operator::Phrase(_) | Operator::Prefix | Operator::Wildcard | Operator::Regex | Operator::Fuzzy(_) => None,
+        }
+    }
+}
+
+/// Operators a range query can express, the upper bound of BETWEEN is passed along
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum RangeOperator {
+    Equal,
+    GreaterThan,
+    GreaterOrEqual,
+    LessThan,
+    LessOrEqual,
+    Between,
+}
+
+impl RangeOperator {
+    /// Lower & upper bound of the range
+    fn resolve_bounds<V: Clone>(self, value: V, upper: Option<V>) -> (Bound<V>, Bound<V>) {
+        match self {
+            RangeOperator::Equal => (Bound::Included(value.clone()), Bound::Included(value)),
+            RangeOperator::GreaterThan => (Bound::Excluded(value), Bound::Unbounded),
+            RangeOperator::GreaterOrEqual => (Bound::Included(value), Bound::Unbounded),
+            RangeOperator::LessThan => (Bound::Unbounded, Bound::Excluded(value)),
+            RangeOperator::LessOrEqual => (Bound::Unbounded, Bound::Included(value)),
+            RangeOperator::Between => (Bound::Included(value), upper.map_or(Bound::Unbounded, Bound::Included)),
+        }
+    }
+}
+
+impl Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> std::fmt::Result {
+        let symbol = match self {
+            Operator::Equal => "=",
+            Operator::GreaterThan => ">",
+            Operator::GreaterOrEqual => ">=",
+            Operator::LessThan => "<",
+            Operator::LessOrEqual => "<=",
+            Operator::Between(_) => "BETWEEN",
+            Operator::Phrase(_) => "PHRASE",
+            Operator::Prefix | Operator::Wildcard => "LIKE",
+            Operator::Regex => "REGEX",
+            Operator::Fuzzy(_) => "~",
+        };
+        write!(f, "{}", symbol)
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct AndCondition {
     field_name: String,
     field_value: String,
+    operator: Operator,
 }
 
 impl AndCondition {
     pub fn new(field_name: String, field_value: String) -> Self {
+        Self::with_operator(field_name, field_value, Operator::Equal)
+    }
+    pub fn with_operator(field_name: String, field_value: String, operator: Operator) -> Self {
         Self {
             field_name,
             field_value,
+            operator,
         }
     }
+    /// Similar to SQL field > value
+    pub fn gt(field_name: &str, field_value: &str) -> Self {
+        Self::with_operator(field_name.to_string(), field_value.to_string(), Operator::GreaterThan)
+    }
+    /// Similar to SQL field >= value
+    pub fn gte(field_name: &str, field_value: &str) -> Self {
+        Self::with_operator(field_name.to_string(), field_value.to_string(), Operator::GreaterOrEqual)
+    }
+    /// Similar to SQL field < value
+    pub fn lt(field_name: &str, field_value: &str) -> Self {
+        Self::with_operator(field_name.to_string(), field_value.to_string(), Operator::LessThan)
+    }
+    /// Similar to SQL field <= value
+    pub fn lte(field_name: &str, field_value: &str) -> Self {
+        Self::with_operator(field_name.to_string(), field_value.to_string(), Operator::LessOrEqual)
+    }
+    /// Similar to SQL field BETWEEN lower AND upper
+    pub fn between(field_name: &str, lower: &str, upper: &str) -> Self {
+        Self::with_operator(field_name.to_string(), lower.to_string(), Operator::Between(upper.to_string()))
+    }
+    /// Words of the value in order, up to slop extra positions apart
+    pub fn phrase(field_name: &str, field_value: &str, slop: u32) -> Self {
+        Self::with_operator(field_name.to_string(), field_value.to_string(), Operator::Phrase(slop))
+    }
+    /// Similar to SQL field LIKE 'jo%'
+    pub fn prefix(field_name: &str, prefix: &str) -> Self {
+        Self::with_operator(field_name.to_string(), prefix.to_string(), Operator::Prefix)
+    }
+    /// Similar to SQL field LIKE 'j_h%' written as j?h*
+    pub fn wildcard(field_name: &str, pattern: &str) -> Self {
+        Self::with_operator(field_name.to_string(), pattern.to_string(), Operator::Wildcard)
+    }
+    /// Matches whole terms against the regular expression, literals are lowercased like the terms
+    pub fn regex(field_name: &str, pattern: &str) -> Self {
+        Self::with_operator(field_name.to_string(), pattern.to_string(), Operator::Regex)
+    }
+    /// Every word of the value within the fuzziness of an indexed term e.g. deo for doe
+    pub fn fuzzy(field_name: &str, field_value: &str, fuzziness: Fuzziness) -> Self {
+        Self::with_operator(field_name.to_string(), field_value.to_string(), Operator::Fuzzy(fuzziness))
+    }
     pub fn update_field_value(&mut self, field_value: String) {
         self.field_value = field_value;
     }
@@ -175,6 +493,21 @@ impl AndCondition {
     pub fn resolve_field_value(&self) -> &String {
         &self.field_value
     }
+    pub fn resolve_operator(&self) -> &Operator {
+        &self.operator
+    }
+}
+
+impl Display for AndCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.operator {
+            Operator::Between(upper) => write!(f, "{} BETWEEN {} AND {}", self.field_name, self.field_value, upper),
+            Operator::Phrase(slop) => write!(f, "{} PHRASE \"{}\"~{}", self.field_name, self.field_value, slop),
+            Operator::Prefix => write!(f, "{} LIKE {}*", self.field_name, self.field_value.trim_end_matches('*')),
+            Operator::Fuzzy(fuzziness) => write!(f, "{} ~ {}~{}", self.field_name, self.field_value, fuzziness.resolve_distance()),
+            operator => write!(f, "{} {} {}", self.field_name, operator, self.field_value),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -187,7 +520,7 @@ impl Display for OrCondition {
         let mut fragments = Vec::<String>::new();
         for (index, condition) in self.conditions.iter().enumerate() {
             if index == 0 {
-                let fragment = format!("{} = {}", condition.resolve_field_name(), condition.resolve_field_value());
+                let fragment = condition.to_string();
                 fragments.push(fragment);
             }
         }
@@ -218,7 +551,153 @@ impl From<(String, String)> for OrCondition {
     }
 }
 
+/// Parses a condition value for a numeric field
+fn parse_field_value<V: FromStr>(field_value: &str) -> Result<V, IndexError> where V::Err: Display {
+    field_value.parse::<V>().map_err(|e| {
+        let message = format!("Invalid search: {}", field_value);
+        let reason = e.to_string();
+        IndexError::new(message, reason)
+    })
+}
+
+/// Floats this many representable values apart are equal e.g. 0.1 + 0.2 & 0.3
+const FLOAT_EQUALITY_ULPS: u64 = 4;
+
+/// Range matching a float & its neighbours within rounding error
+fn resolve_float_window(value: f64) -> (Bound<f64>, Bound<f64>) {
+    let bits = value.to_u64();
+    let lower = f64::from_u64(bits.saturating_sub(FLOAT_EQUALITY_ULPS));
+    let upper = f64::from_u64(bits.saturating_add(FLOAT_EQUALITY_ULPS));
+    (Bound::Included(lower), Bound::Included(upper))
+}
+
+/// Fields that keep terms as they are instead of lowercasing them
+fn is_raw_field(schema: &Schema, field: Field) -> bool {
+    match schema.get_field_entry(field).field_type() {
+        FieldType::Str(options) => options
+            .get_indexing_options()
+            .map(|indexing| indexing.tokenizer() == "raw")
+            .unwrap_or(false),
+        _ => false,
+    }
+}
+
+/// Lowercases the pattern unless the field keeps terms as they are
+fn normalize_pattern(schema: &Schema, field: Field, pattern: &str) -> String {
+    if is_raw_field(schema, field) {
+        pattern.to_string()
+    } else {
+        pattern.to_lowercase()
+    }
+}
+
+/// Lowercases the literals of a regular expression, escapes, class names & flags are kept
+fn normalize_regex(schema: &Schema, field: Field, pattern: &str) -> String {
+    if is_raw_field(schema, field) {
+        return pattern.to_string();
+    };
+    let mut normalized = String::with_capacity(pattern.len());
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                normalized.push(c);
+                let escaped = chars.next();
+                if let Some(escaped) = escaped {
+                    normalized.push(escaped);
+                };
+                // Unicode classes e.g. \pL or \p{Lu}
+                if escaped == Some('p') || escaped == Some('P') {
+                    if let Some(c) = chars.next() {
+                        normalized.push(c);
+                        if c == '{' {
+                            for c in chars.by_ref() {
+                                normalized.push(c);
+                                if c == '}' {
+                                    break;
+                                };
+                            };
+                        };
+                    };
+                };
+            }
+            // Flags & group names e.g. (?U) or (?P<Name>
+            '(' if chars.peek() == Some(&'?') => {
+                normalized.push(c);
+                for c in chars.by_ref() {
+                    normalized.push(c);
+                    if c == ')' || c == ':' || c == '>' {
+                        break;
+                    };
+                };
+            }
+            c => normalized.extend(c.to_lowercase()),
+        };
+    };
+    normalized
+}
+
+/// Escapes regular expression syntax
+fn escape_pattern(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if "\\.+*?()|[]{}^$#&-~".contains(c) {
+            escaped.push('\\');
+        };
+        escaped.push(c);
+    };
+    escaped
+}
+
+/// Phrase query, slop is spread over the gaps between the words
+fn build_phrase_query(terms: Vec<(usize, Term)>, slop: u32) -> Result<Box<dyn Query>, String> {
+    const MAX_PHRASES: usize = 1024;
+    if terms.is_empty() {
+        return Err("Phrase has no words".to_string());
+    };
+    if terms.len() == 1 {
+        let term = terms.into_iter().next().unwrap().1;
+        return Ok(Box::new(TermQuery::new(term, IndexRecordOption::WithFreqs)));
+    };
+    // Every way of adding up to slop extra positions across the gaps
+    let gaps = terms.len() - 1;
+    let mut spreads: Vec<Vec<usize>> = vec![vec![]];
+    for _ in 0..gaps {
+        let mut next = Vec::new();
+        for spread in spreads {
+            let used: usize = spread.iter().sum();
+            for extra in 0..=(slop as usize - std::cmp::min(used, slop as usize)) {
+                let mut spread = spread.clone();
+                spread.push(extra);
+                next.push(spread);
+            };
+        };
+        if next.len() > MAX_PHRASES {
+            return Err(format!("Slop: {} is too large for a phrase of {} words", slop, terms.len()));
+        };
+        spreads = next;
+    };
+    let mut clauses = Vec::<(Occur, Box<dyn Query>)>::with_capacity(spreads.len());
+    for spread in spreads {
+        let mut shift = 0;
+        let mut phrase = Vec::with_capacity(terms.len());
+        for (i, (position, term)) in terms.iter().enumerate() {
+            if i > 0 {
+                shift += spread[i - 1];
+            };
+            phrase.push((position + shift, term.clone()));
+        };
+        clauses.push((Occur::Should, Box::new(PhraseQuery::new_with_offset(phrase))));
+    };
+    if clauses.len() == 1 {
+        return Ok(clauses.pop().unwrap().1);
+    };
+    Ok(Box::new(BooleanQuery::from(clauses)))
+}
+
 /// Surfer: Client API
+///
+/// Reads take any deserializable T, use serde_json::Value when the struct isn't at hand.
 pub struct Surf {
     surfer: Surfer
 }
@@ -230,23 +709,102 @@ impl Surf {
         }
     }
     /// Use apply to manage and limit the output
-    pub fn apply<T: Serialize + DeserializeOwned>(&mut self, index_name: &str, conditions: &Vec<OrCondition>, limit: Option<usize>, score: Option<f32>) -> Result<Option<Vec<T>>, IndexError> {
+    pub fn apply<T: Serialize + DeserializeOwned>(&mut self, index_name: &str, conditions: &[OrCondition], limit: Option<usize>, score: Option<f32>) -> Result<Option<Vec<T>>, IndexError> {
         self.surfer.multiple_structs_by_field(index_name, conditions, limit, score)
     }
+    /// Use apply_page to walk through the output one page at a time
+    pub fn apply_page<T: Serialize + DeserializeOwned>(&mut self, index_name: &str, conditions: &[OrCondition], paging: &Paging, score: Option<f32>) -> Result<Option<Page<T>>, IndexError> {
+        self.surfer.multiple_structs_page(index_name, conditions, paging, score)
+    }
+    /// Use apply_scored to see the score & reference of each hit
+    pub fn apply_scored<T: Serialize + DeserializeOwned>(&mut self, index_name: &str, conditions: &[OrCondition], limit: Option<usize>, score: Option<f32>) -> Result<Option<Vec<Hit<T>>>, IndexError> {
+        self.surfer.multiple_scored_structs(index_name, conditions, limit, score)
+    }
+    /// Use apply_boosted to weigh some fields more than others, best hits first
+    pub fn apply_boosted<T: Serialize + DeserializeOwned>(&mut self, index_name: &str, conditions: &[OrCondition], boosts: &HashMap<String, f32>, limit: Option<usize>, score: Option<f32>) -> Result<Option<Vec<T>>, IndexError> {
+        self.surfer.multiple_boosted_structs(index_name, conditions, boosts, limit, score)
+    }
+    /// Use apply_normalized for a cutoff on 0-1 scores, relative to the top hit or min-max
+    pub fn apply_normalized<T: Serialize + DeserializeOwned>(&mut self, index_name: &str, conditions: &[OrCondition], limit: Option<usize>, cutoff: &Cutoff) -> Result<Option<Vec<Hit<T>>>, IndexError> {
+        self.surfer.multiple_normalized_structs(index_name, &Expression::from(conditions), limit, cutoff)
+    }
+    /// Use apply_expression_normalized for nested AND, OR & NOT with a cutoff on 0-1 scores
+    pub fn apply_expression_normalized<T: Serialize + DeserializeOwned>(&mut self, index_name: &str, expression: &Expression, limit: Option<usize>, cutoff: &Cutoff) -> Result<Option<Vec<Hit<T>>>, IndexError> {
+        self.surfer.multiple_normalized_structs(index_name, expression, limit, cutoff)
+    }
+    /// Use count_where for the number of matches, every match counts regardless of score
+    pub fn count_where(&mut self, index_name: &str, conditions: &[OrCondition]) -> Result<usize, IndexError> {
+        self.surfer.count_by_field(index_name, conditions)
+    }
+    /// Use aggregate_expression for dashboards e.g. Expression::all() for the whole index
+    pub fn aggregate_expression(&mut self, index_name: &str, expression: &Expression, aggregations: &[Aggregation]) -> Result<Vec<Buckets>, IndexError> {
+        self.surfer.aggregate_by_expression(index_name, expression, aggregations)
+    }
+    /// Use stats_expression for summaries e.g. the mean age of everyone named Doe
+    pub fn stats_expression(&mut self, index_name: &str, expression: &Expression, field_name: &str, percents: &[f64]) -> Result<Stats, IndexError> {
+        self.surfer.stats_by_expression(index_name, expression, field_name, percents)
+    }
+    /// Use apply_debug to see which hits the score cutoff dropped
+    pub fn apply_debug<T: Serialize + DeserializeOwned>(&mut self, index_name: &str, conditions: &[OrCondition], limit: Option<usize>, score: Option<f32>) -> Result<Option<Debugged<T>>, IndexError> {
+        self.surfer.multiple_structs_debug(index_name, conditions, limit, score)
+    }
+    /// Use explain_apply to see how the conditions score a document
+    pub fn explain_apply(&mut self, index_name: &str, conditions: &[OrCondition], reference: &SegmentDocRef) -> Result<Option<serde_json::Value>, IndexError> {
+        self.surfer.explain_conditions(index_name, conditions, reference)
+    }
+    /// Use apply_sorted to order the output by a numeric fast field
+    pub fn apply_sorted<T: Serialize + DeserializeOwned>(&mut self, index_name: &str, conditions: &[OrCondition], sort: &Sort, paging: &Paging, score: Option<f32>) -> Result<Option<Page<T>>, IndexError> {
+        self.surfer.multiple_structs_sorted(index_name, conditions, sort, paging, score)
+    }
+    /// Use apply_federated to run the conditions across several indexes, best hits first
+    pub fn apply_federated<T: Serialize + DeserializeOwned>(&mut self, index_names: &[String], conditions: &[OrCondition], paging: &Paging, score: Option<f32>) -> Result<Page<FederatedHit<T>>, IndexError> {
+        self.surfer.multiple_structs_federated(index_names, conditions, paging, score)
+    }
+    /// Use apply_federated_sorted to run the conditions across several indexes, ordered by a numeric fast field
+    pub fn apply_federated_sorted<T: Serialize + DeserializeOwned>(&mut self, index_names: &[String], conditions: &[OrCondition], sort: &Sort, paging: &Paging, score: Option<f32>) -> Result<Page<FederatedHit<T>>, IndexError> {
+        self.surfer.multiple_structs_federated_sorted(index_names, conditions, sort, paging, score)
+    }
+    /// Use apply_expression for nested AND, OR & NOT
+    pub fn apply_expression<T: Serialize + DeserializeOwned>(&mut self, index_name: &str, expression: &Expression, limit: Option<usize>, score: Option<f32>) -> Result<Option<Vec<T>>, IndexError> {
+        self.surfer.read_structs_by_expression(index_name, expression, limit, score)
+    }
+    /// Runs a SQL like statement e.g. SELECT first, age FROM users WHERE age > 30 LIMIT 10
+    pub fn query(&mut self, query: &str) -> Result<Option<Page<serde_json::Value>>, IndexError> {
+        self.surfer.query_page(query)
+    }
+    /// Runs a SQL like statement, the selected fields are read into T
+    pub fn query_structs<T: DeserializeOwned>(&mut self, query: &str) -> Result<Option<Page<T>>, IndexError> {
+        let page = self.surfer.query_page(query)?;
+        if page.is_none() {
+            return Ok(None);
+        };
+        let page = page.unwrap();
+        let total = page.resolve_total();
+        let cursor = page.resolve_cursor().cloned();
+        let mut docs = Vec::with_capacity(page.resolve_documents().len());
+        for doc in page.into_documents() {
+            docs.push(serde_json::from_value::<T>(doc)?);
+        };
+        Ok(Some(Page::new(docs, total, cursor)))
+    }
     /// Similar to SQL Select
-    pub fn select<T: Serialize + DeserializeOwned>(&mut self, index_name: &str, conditions: &Vec<OrCondition>) -> Result<Option<Vec<T>>, IndexError> {
+    pub fn select<T: Serialize + DeserializeOwned>(&mut self, index_name: &str, conditions: &[OrCondition]) -> Result<Option<Vec<T>>, IndexError> {
         let limit = Some(100usize);
         let score = Some(0f32);
         self.apply(index_name, conditions, limit, score)
     }
     /// Similar to SQL Insert
-    pub fn insert<T: Serialize>(&mut self, name: &str, payload: &Vec<T>) -> Result<(), IndexError> {
+    pub fn insert<T: Serialize>(&mut self, name: &str, payload: &[T]) -> Result<(), IndexError> {
         self.surfer.insert_structs::<T>(name, payload)
     }
     /// Aims to be similar to SQL Delete
     pub fn delete(&mut self, index_name: &str, field_name: &str, field_value: &str) -> Result<(), IndexError> {
         self.surfer.delete_structs_by_field(index_name, field_name, field_value)
     }
+    /// Similar to SQL Delete with a where clause, returns number of documents removed
+    pub fn delete_where(&mut self, index_name: &str, conditions: &[OrCondition]) -> Result<usize, IndexError> {
+        self.surfer.delete_by_conditions(index_name, conditions)
+    }
 }
 
 impl Deref for Surf {
@@ -284,6 +842,9 @@ pub struct Surfer {
     readers: HashMap<String, Option<IndexReader>>,
     writers: HashMap<String, Option<IndexWriter>>,
     schemas: HashMap<String, SurferSchema>,
+    spellers: HashMap<String, FuzzyWord>,
+    index_spellers: HashMap<String, FuzzyWord>,
+    ids: HashMap<String, u64>,
 }
 
 impl Surfer {
@@ -313,117 +874,209 @@ impl Surfer {
         }
         self.indexes.get(name)
     }
+    /// Registers an index on a live surfer, no-op if already registered with the same schema
+    pub fn create_index(&mut self, name: &str, schema: SurferSchema) -> Result<(), IndexError> {
+        if let Some(existing) = self.schemas.get(name) {
+            if *existing == schema.aligned_with(existing) {
+                return Ok(());
+            };
+            let message = format!("Unable to create index: {}", name);
+            let reason = "Index is already registered with a different schema".to_string();
+            return Err(IndexError::new(message, reason));
+        };
+
+        let index = initialize_mmap(name, &self.home, &schema)?;
+        let schema = schema.aligned_with(&index.schema());
+        if index.schema() != *schema {
+            let message = format!("Unable to create index: {}", name);
+            let reason = "Index on disk has a different schema".to_string();
+            return Err(IndexError::new(message, reason));
+        };
+
+        let fields = extract_schema_fields(&schema);
+        self.indexes.insert(name.to_string(), index);
+        self.fields.insert(name.to_string(), fields);
+        self.readers.insert(name.to_string(), None);
+        self.writers.insert(name.to_string(), None);
+        self.schemas.insert(name.to_string(), schema);
+        Ok(())
+    }
+    /// Registers an index for a serializable rust struct on a live surfer
+    pub fn add_struct<T: Serialize>(&mut self, name: &str, data: &T) -> Result<(), IndexError> {
+        let (schema, mappings) = to_schema(data, None)?;
+        let schema = SurferSchema::new(schema, mappings, false, false);
+        self.create_index(name, schema)
+    }
+    /// Registers an index for a serializable rust struct with per field options on a live surfer
+    pub fn add_struct_with_control<T: Serialize>(&mut self, name: &str, data: &T, control: &HashMap<String, Control>) -> Result<(), IndexError> {
+        let (schema, mappings) = to_schema(data, Some(control))?;
+        let schema = SurferSchema::new(schema, mappings, false, false);
+        self.create_index(name, schema)
+    }
     /// Inserts a struct
     pub fn insert_struct<T: Serialize>(&mut self, name: &str, data: &T) -> Result<(), IndexError> {
-        let data = serde_json::to_string(data)?;
         let writer = self.writers.get(name);
         if writer.is_none() {
             return Ok(());
         };
 
-        let index = self.indexes.get(name).unwrap();
-        let schema = &index.schema();
+        let document = self._build_document(name, data)?;
+        self._prepare_index_writer(name)?;
+        self._commit_documents(name, vec![document])
+    }
+    /// Inserts a structs, all or nothing
+    pub fn insert_structs<T: Serialize>(&mut self, name: &str, payload: &[T]) -> Result<(), IndexError> {
+        {
+            let result = self._prepare_index_writer(name);
+            if result.is_err() {
+                return Ok(());
+            };
+        }
 
-        let writer = writer.unwrap();
-        if writer.is_none() {
-            let writer = open_index_writer(index)?;
-            self.writers.insert(name.to_string(), Some(writer));
-        };
+        let mut documents = Vec::<Document>::with_capacity(payload.len());
+        for data in payload {
+            let document = self._build_document(name, data)?;
+            documents.push(document);
+        }
 
-        let writer = self.writers.get_mut(name).unwrap().as_mut().unwrap();
-        let document = schema.parse_document(&data)?;
-        writer.add_document(document);
-        writer.commit()?;
-        Ok(())
+        self._commit_documents(name, documents)
     }
-    /// Inserts a structs
-    pub fn insert_structs<T: Serialize>(&mut self, name: &str, payload: &Vec<T>) -> Result<(), IndexError> {
+    /// Inserts a structs, commits the valid ones & returns the rejected ones
+    pub fn insert_structs_partially<T: Serialize>(&mut self, name: &str, payload: &[T]) -> Result<Vec<RejectedDocument>, IndexError> {
         {
             let result = self._prepare_index_writer(name);
             if result.is_err() {
-                return Ok(());
+                return Ok(Vec::new());
             };
         }
 
-        let writer = self.writers.get_mut(name).unwrap().as_mut().unwrap();
+        let mut documents = Vec::<Document>::with_capacity(payload.len());
+        let mut rejected = Vec::<RejectedDocument>::new();
+        for (position, data) in payload.iter().enumerate() {
+            let document = self._build_document(name, data);
+            match document {
+                Ok(document) => documents.push(document),
+                Err(error) => rejected.push(RejectedDocument::new(position, error)),
+            };
+        }
 
-        let index = self.indexes.get(name).unwrap();
-        let schema = &index.schema();
-        for data in payload {
-            let data = serde_json::to_string(data)?;
-            let document = schema.parse_document(&data)?;
+        self._commit_documents(name, documents)?;
+        Ok(rejected)
+    }
+    /// Hands out the hidden ids & rolls back on a failed commit so nothing stays buffered
+    fn _commit_documents(&mut self, name: &str, documents: Vec<Document>) -> Result<(), IndexError> {
+        let id_field = self._resolve_surfer_schema(name)?.resolve_id_field();
+        let mut next_id = match id_field {
+            Some(id_field) => self._resolve_next_id(name, id_field)?,
+            None => 0,
+        };
+
+        let writer = self.writers.get_mut(name).unwrap().as_mut().unwrap();
+        for mut document in documents {
+            if let Some(id_field) = id_field {
+                document.add_u64(id_field, next_id);
+                next_id += 1;
+            };
             writer.add_document(document);
         }
-
-        writer.commit()?;
-        Ok(())
+        let result = writer.commit();
+        if let Err(e) = result {
+            let _ = writer.rollback();
+            return Err(IndexError::from(e));
+        };
+        if id_field.is_some() {
+            self.ids.insert(name.to_string(), next_id);
+        };
+        self._reload_index_reader(name)
+    }
+    /// Ids increase per index, the first write picks up after the largest id on disk
+    fn _resolve_next_id(&mut self, name: &str, id_field: Field) -> Result<u64, IndexError> {
+        if let Some(next_id) = self.ids.get(name) {
+            return Ok(*next_id);
+        };
+        self._prepare_index_reader(name)?;
+        let searcher = self.readers.get(name).unwrap().as_ref().unwrap().searcher();
+        let next_id = searcher
+            .segment_readers()
+            .iter()
+            .filter_map(|segment_reader| segment_reader.fast_fields().u64(id_field))
+            .map(|reader| reader.max_value() + 1)
+            .max()
+            .unwrap_or(0);
+        Ok(next_id)
+    }
+    /// Booleans go in as text & byte arrays as bytes, the rest is parsed by tantivy
+    fn _build_document<T: Serialize>(&self, name: &str, data: &T) -> Result<Document, IndexError> {
+        let schema = self._resolve_surfer_schema(name)?;
+        let mut data = serde_json::to_value(data)?;
+        let mut bytes = Vec::<(Field, Vec<u8>)>::new();
+        if let Some(object) = data.as_object_mut() {
+            for (field_name, field_type) in schema.resolve_mapping() {
+                match (field_type, object.get(field_name)) {
+                    (SurferFieldTypes::Bool, Some(serde_json::Value::Bool(flag))) => {
+                        let flag = serde_json::Value::String(flag.to_string());
+                        object.insert(field_name.clone(), flag);
+                    }
+                    (SurferFieldTypes::Bytes, Some(serde_json::Value::Array(_))) => {
+                        let value = object.remove(field_name).unwrap();
+                        let value = serde_json::from_value::<Vec<u8>>(value).map_err(|e| {
+                            let message = format!("Unable to insert into: {}", name);
+                            let reason = format!("Field: {} is not a byte array: {}", field_name, e);
+                            IndexError::new(message, reason)
+                        })?;
+                        if let Some(field) = schema.get_field(field_name) {
+                            bytes.push((field, value));
+                        };
+                    }
+                    _ => {}
+                };
+            };
+        };
+        let mut document = schema.parse_document(&data.to_string())?;
+        for (field, value) in bytes {
+            document.add_bytes(field, value);
+        };
+        Ok(document)
+    }
+    /// Stored fields along with byte arrays, which tantivy keeps in fast fields only
+    fn _read_document(&self, name: &str, searcher: &Searcher, doc_address: DocAddress) -> Result<Document, IndexError> {
+        let schema = self._resolve_surfer_schema(name)?;
+        read_document(schema, searcher, doc_address)
     }
-    /// Massive hack look away ;)
     fn jsonify(&self, name: &str, document: &Document) -> Result<String, IndexError> {
-        let schema = self.indexes.get(name).unwrap().schema();
-
-        let mut field_map = BTreeMap::new();
-        for (field, field_values) in document.get_sorted_field_values() {
-            let field_name = schema.get_field_name(field);
-            let fv = field_values.get(0);
-            if fv.is_none() {
-                let message = format!("Unable to jsonify: {}", name);
-                let reason = format!("Field: {} does not have any value", field_name);
-                let error = IndexError::new(message, reason);
-                return Err(error);
-            };
-            let fv = fv.unwrap().value();
-            field_map.insert(field_name, fv);
-        };
-        let payload = SingleValuedNamedFieldDocument(field_map);
-        let result = serde_json::to_string(&payload)
-            .map_err(|e| {
-                let message = "Unable to serialize struct".to_string();
-                let reason = e.to_string();
-                IndexError::new(
-                    message,
-                    reason,
-                )
-            });
-        result
+        let value = self._to_value(name, document, None)?;
+        Ok(value.to_string())
+    }
+    /// Json typed from the mappings, only the given fields when some
+    fn _to_value(&self, name: &str, document: &Document, fields: Option<&[String]>) -> Result<serde_json::Value, IndexError> {
+        let schema = self._resolve_surfer_schema(name)?;
+        to_value(name, schema, document, fields)
     }
     fn _is_index_valid(&self, name: &str) -> bool {
         let index = self.indexes.get(name);
-        if index.is_some() {
-            true
-        } else {
-            false
-        }
+        index.is_some()
     }
     fn _is_reader_valid(&self, name: &str) -> bool {
         if !self.readers.contains_key(name) {
             return false;
         }
         let reader = self.readers.get(name).unwrap();
-        if reader.is_some() {
-            true
-        } else {
-            false
-        }
+        reader.is_some()
     }
     fn _is_writer_valid(&self, name: &str) -> bool {
         if !self.writers.contains_key(name) {
             return false;
         }
         let writer = self.writers.get(name).unwrap();
-        if writer.is_some() {
-            true
-        } else {
-            false
-        }
+        writer.is_some()
     }
     fn _prepare_index_writer(&mut self, index_name: &str) -> Result<(), IndexError> {
         if !self._is_index_valid(index_name) {
-            let message = format!("Unable to prepare the writer");
+            let message = "Unable to prepare the writer".to_string();
             let reason = format!("Index was missing: {} ", index_name);
             return Err(IndexError::new(message, reason));
         };
-        if self._is_writer_valid(&index_name) {
+        if self._is_writer_valid(index_name) {
             return Ok(());
         };
         let index = self.indexes.get(index_name).unwrap();
@@ -434,11 +1087,11 @@ impl Surfer {
 
     fn _prepare_index_reader(&mut self, index_name: &str) -> Result<(), IndexError> {
         if !self._is_index_valid(index_name) {
-            let message = format!("Unable to prepare the reader");
+            let message = "Unable to prepare the reader".to_string();
             let reason = format!("Index was missing: {} ", index_name);
             return Err(IndexError::new(message, reason));
         };
-        if self._is_reader_valid(&index_name) {
+        if self._is_reader_valid(index_name) {
             return Ok(());
         };
         let index = self.indexes.get(index_name).unwrap();
@@ -446,20 +1099,35 @@ impl Surfer {
         let _ = self.readers.insert(index_name.to_string(), Some(reader));
         Ok(())
     }
+    /// Makes a commit visible right away instead of waiting on the reload policy
+    ///
+    /// The dictionary built from the index no longer matches it, so it is dropped.
+    fn _reload_index_reader(&mut self, index_name: &str) -> Result<(), IndexError> {
+        self.index_spellers.remove(index_name);
+        if let Some(Some(reader)) = self.readers.get(index_name) {
+            reader.reload()?;
+        };
+        Ok(())
+    }
     fn _build_terms(&self, schema: &SurferSchema, field_value: &str) -> Result<Vec<Term>, IndexError> {
-        let mut field_names = Vec::<&String>::with_capacity(schema.mappings.len());
+        let mut field_names = Vec::<(&String, &SurferFieldTypes)>::with_capacity(schema.mappings.len());
         for (field_name, field_type) in schema.mappings.iter() {
-            match field_type {
-                SurferFieldTypes::String => field_names.push(field_name),
-                _ => {}
+            if field_type.is_text() {
+                field_names.push((field_name, field_type));
             };
         }
         let mut terms = Vec::<Term>::with_capacity(schema.mappings.len());
         if field_names.is_empty() {
             return Ok(terms);
         };
-        for field_name in field_names {
-            let term = self._build_term(schema, field_name, field_value)?;
+        for (field_name, field_type) in field_names {
+            // Booleans match as text here, only searches on the field itself want true or false
+            let term = if *field_type == SurferFieldTypes::Bool {
+                let field = schema.get_field(field_name).unwrap();
+                Term::from_field_text(field, field_value)
+            } else {
+                self._build_term(schema, field_name, field_value)?
+            };
             terms.push(term);
         }
         Ok(terms)
@@ -469,7 +1137,7 @@ impl Surfer {
 
         let field_type = mappings.get(field_name);
         if field_type.is_none() {
-            let message = format!("Unable to perform search");
+            let message = "Unable to perform search".to_string();
             let reason = format!("Missing field: {}", field_name);
             return Err(IndexError::new(message, reason));
         };
@@ -477,7 +1145,7 @@ impl Surfer {
 
         let field = schema.get_field(field_name);
         if field.is_none() {
-            let message = format!("Unable to perform search");
+            let message = "Unable to perform search".to_string();
             let reason = format!("Missing field: {}", field_name);
             return Err(IndexError::new(message, reason));
         };
@@ -516,66 +1184,271 @@ impl Surfer {
                 let reason = "Cant search on bytes".to_string();
                 return Err(IndexError::new(message, reason));
             }
+            SurferFieldTypes::Bool => {
+                let field_value = field_value.parse::<bool>().map_err(|e| {
+                    let message = format!("Invalid search: {}", field_value);
+                    let reason = e.to_string();
+                    IndexError::new(message, reason)
+                })?;
+                Term::from_field_text(field, &field_value.to_string())
+            }
         };
 
         Ok(term)
     }
 
-    fn _build_term_query(&self, term: Term, segment_postings_options: Option<IndexRecordOption>) -> Result<TermQuery, IndexError> {
-        let segment_postings_options = match segment_postings_options {
-            Some(option) => option,
-            None => IndexRecordOption::Basic,
-        };
-        Ok(TermQuery::new(term, segment_postings_options))
-    }
-
-    fn _resolve_surfer_schema(&self, index_name: &str) -> Result<&SurferSchema, IndexError> {
-        let schema = self.schemas.get(index_name);
-        if schema.is_none() {
-            let message = format!("Invalid index operation for {}", index_name);
-            let reason = format!("No schema found for index: {}", index_name);
-            return Err(IndexError::new(message, reason));
-        };
-        let schema = schema.unwrap();
-        Ok(schema)
+    /// Comparisons & float equality
+    fn _is_range_condition(&self, schema: &SurferSchema, condition: &AndCondition) -> bool {
+        let field_type = schema.resolve_mapping().get(condition.resolve_field_name());
+        let operator = condition.resolve_operator();
+        operator.is_comparison() || (*operator == Operator::Equal && field_type == Some(&SurferFieldTypes::F64))
     }
-
-    fn _resolve_limit(&self, limit: Option<usize>) -> usize {
-        match limit {
-            Some(limit) => limit,
-            None => 10
+    /// Range & regex matches are filters & carry a constant score
+    fn _is_filter_condition(&self, schema: &SurferSchema, condition: &AndCondition) -> bool {
+        match condition.resolve_operator() {
+            Operator::Prefix | Operator::Wildcard | Operator::Regex | Operator::Fuzzy(_) => true,
+            _ => self._is_range_condition(schema, condition),
         }
     }
+    /// Phrase query for phrases, regex query for prefix, wildcard & regex
+    fn _build_text_query(&self, index: &Index, schema: &SurferSchema, condition: &AndCondition) -> Result<Box<dyn Query>, IndexError> {
+        let field_name = condition.resolve_field_name();
+        let field_value = condition.resolve_field_value();
+        let message = format!("Invalid search: {}", condition);
+        let field = schema.get_field(field_name);
+        if field.is_none() || schema.resolve_mapping().get(field_name) != Some(&SurferFieldTypes::String) {
+            let reason = format!("Text matches need a text field: {}", field_name);
+            return Err(IndexError::new(message, reason));
+        };
+        let field = field.unwrap();
 
-    fn _resolve_score(&self, score: Option<f32>) -> f32 {
-        match score {
-            Some(score) => score,
-            None => 90f32
-        }
-    }
+        let pattern = match condition.resolve_operator() {
+            Operator::Phrase(slop) => {
+                let tokenizer = index.tokenizer_for_field(field)?;
+                let mut terms = Vec::<(usize, Term)>::new();
+                let mut stream = tokenizer.token_stream(field_value);
+                stream.process(&mut |token| terms.push((token.position, Term::from_field_text(field, &token.text))));
+                return build_phrase_query(terms, *slop).map_err(|reason| IndexError::new(message, reason));
+            }
+            Operator::Fuzzy(fuzziness) => {
+                let words = self._tokenize(index, field, field_value)?;
+                if words.is_empty() {
+                    return Err(IndexError::new(message, "Nothing to search for".to_string()));
+                };
+                let mut clauses = Vec::<(Occur, Box<dyn Query>)>::with_capacity(words.len());
+                for word in words {
+                    clauses.push((Occur::Must, build_fuzzy_query(field, &word, fuzziness)?));
+                };
+                return Ok(Box::new(BooleanQuery::from(clauses)));
+            }
+            Operator::Prefix => {
+                let prefix = normalize_pattern(schema, field, field_value.trim_end_matches('*'));
+                format!("{}.*", escape_pattern(&prefix))
+            }
+            Operator::Wildcard => {
+                let pattern = normalize_pattern(schema, field, field_value);
+                let mut regex = String::with_capacity(pattern.len() * 2);
+                for c in pattern.chars() {
+                    match c {
+                        '*' => regex.push_str(".*"),
+                        '?' => regex.push('.'),
+                        c => regex.push_str(&escape_pattern(&c.to_string())),
+                    };
+                };
+                regex
+            }
+            _ => normalize_regex(schema, field, field_value),
+        };
+        let query = RegexQuery::from_pattern(&pattern, field).map_err(|e| IndexError::new(message, e.to_string()))?;
+        Ok(Box::new(query))
+    }
+    /// Words of the text as the field indexes them
+    fn _tokenize(&self, index: &Index, field: Field, text: &str) -> Result<Vec<String>, IndexError> {
+        let tokenizer = index.tokenizer_for_field(field)?;
+        let mut words = Vec::<String>::new();
+        let mut stream = tokenizer.token_stream(text);
+        stream.process(&mut |token| words.push(token.text.clone()));
+        Ok(words)
+    }
+    /// Typo tolerant query over the words of the query, per field fuzziness
+    fn _build_fuzzy_query(&self, name: &str, query: &str, fuzziness: &HashMap<String, Fuzziness>) -> Result<Box<dyn Query>, IndexError> {
+        let index = self.indexes.get(name).unwrap();
+        let schema = self._resolve_surfer_schema(name)?;
+        let mappings = schema.resolve_mapping();
+        let message = format!("Invalid fuzzy search: {}", query);
+
+        let mut fields = Vec::<(Field, Fuzziness)>::new();
+        if fuzziness.is_empty() {
+            for (field, entry) in schema.fields() {
+                if mappings.get(entry.name()) == Some(&SurferFieldTypes::String) {
+                    fields.push((field, Fuzziness::default()));
+                };
+            };
+        };
+        for (field_name, field_fuzziness) in fuzziness {
+            let field = schema.get_field(field_name);
+            if field.is_none() || mappings.get(field_name) != Some(&SurferFieldTypes::String) {
+                let reason = format!("Fuzzy search needs a text field: {}", field_name);
+                return Err(IndexError::new(message, reason));
+            };
+            fields.push((field.unwrap(), *field_fuzziness));
+        };
+
+        let mut clauses = Vec::<(Occur, Box<dyn Query>)>::new();
+        for (field, field_fuzziness) in fields {
+            for word in self._tokenize(index, field, query)? {
+                clauses.push((Occur::Should, build_fuzzy_query(field, &word, &field_fuzziness)?));
+            };
+        };
+        Ok(Box::new(BooleanQuery::from(clauses)))
+    }
+    /// Condition query, scaled by the boost of its field unless it only filters
+    fn _build_condition_query(&self, index: &Index, schema: &SurferSchema, condition: &AndCondition) -> Result<Box<dyn Query>, IndexError> {
+        let query = self._build_unboosted_condition_query(index, schema, condition)?;
+        let boost = schema.resolve_field_boost(condition.resolve_field_name());
+        match boost {
+            Some(boost) if !self._is_filter_condition(schema, condition) => Ok(Box::new(BoostQuery::new(query, boost))),
+            _ => Ok(query),
+        }
+    }
+    /// Term query for equality, range query for comparisons & float equality
+    fn _build_unboosted_condition_query(&self, index: &Index, schema: &SurferSchema, condition: &AndCondition) -> Result<Box<dyn Query>, IndexError> {
+        let field_name = condition.resolve_field_name();
+        let field_value = condition.resolve_field_value();
+        let operator = condition.resolve_operator();
+        let range = match operator.resolve_range() {
+            Some(range) => range,
+            None => return self._build_text_query(index, schema, condition),
+        };
+        if !self._is_range_condition(schema, condition) {
+            let term = self._build_term(schema, field_name, field_value)?;
+            let query = self._build_term_query(term, None)?;
+            return Ok(Box::new(query));
+        };
+
+        let field_type = schema.resolve_mapping().get(field_name);
+        let field = schema.get_field(field_name);
+        if field_type.is_none() || field.is_none() {
+            let message = "Unable to perform search".to_string();
+            let reason = format!("Missing field: {}", field_name);
+            return Err(IndexError::new(message, reason));
+        };
+        let field = field.unwrap();
+        let upper = match operator {
+            Operator::Between(upper) => Some(upper.as_str()),
+            _ => None,
+        };
+
+        let query = match field_type.unwrap() {
+            SurferFieldTypes::U64 => {
+                let (lower, upper) = range.resolve_bounds(parse_field_value::<u64>(field_value)?, upper.map(parse_field_value::<u64>).transpose()?);
+                RangeQuery::new_u64_bounds(field, lower, upper)
+            }
+            SurferFieldTypes::I64 => {
+                let (lower, upper) = range.resolve_bounds(parse_field_value::<i64>(field_value)?, upper.map(parse_field_value::<i64>).transpose()?);
+                RangeQuery::new_i64_bounds(field, lower, upper)
+            }
+            SurferFieldTypes::F64 => {
+                let value = parse_field_value::<f64>(field_value)?;
+                let (lower, upper) = match range {
+                    RangeOperator::Equal => resolve_float_window(value),
+                    _ => range.resolve_bounds(value, upper.map(parse_field_value::<f64>).transpose()?),
+                };
+                RangeQuery::new_f64_bounds(field, lower, upper)
+            }
+            _ => {
+                let message = format!("Invalid search: {}", condition);
+                let reason = format!("Comparisons need a numeric field: {}", field_name);
+                return Err(IndexError::new(message, reason));
+            }
+        };
+        Ok(Box::new(query))
+    }
+
+    fn _build_term_query(&self, term: Term, segment_postings_options: Option<IndexRecordOption>) -> Result<TermQuery, IndexError> {
+        let segment_postings_options = segment_postings_options.unwrap_or(IndexRecordOption::Basic);
+        Ok(TermQuery::new(term, segment_postings_options))
+    }
+
+    fn _resolve_surfer_schema(&self, index_name: &str) -> Result<&SurferSchema, IndexError> {
+        let schema = self.schemas.get(index_name);
+        if schema.is_none() {
+            let message = format!("Invalid index operation for {}", index_name);
+            let reason = format!("No schema found for index: {}", index_name);
+            return Err(IndexError::new(message, reason));
+        };
+        let schema = schema.unwrap();
+        Ok(schema)
+    }
+
+    fn _resolve_limit(&self, limit: Option<usize>) -> usize {
+        limit.unwrap_or(10)
+    }
+
+    /// Raw score cutoff, see Cutoff for normalized ones
+    fn _resolve_score(&self, score: Option<f32>) -> f32 {
+        score.unwrap_or(90f32)
+    }
 
     /// Uses term search
     pub fn delete_structs_by_field(&mut self, index_name: &str, field_name: &str, field_value: &str) -> Result<(), IndexError> {
         let schema = self._resolve_surfer_schema(index_name)?;
-        let term = self._build_term(&schema, field_name, field_value)?;
-        let _ = self._prepare_index_writer(index_name)?;
+        let term = self._build_term(schema, field_name, field_value)?;
+        self._prepare_index_writer(index_name)?;
         let writer = self.writers.get_mut(index_name).unwrap().as_mut().unwrap();
         let _ = writer.delete_term(term);
         let _ = writer.commit()?;
-        Ok(())
+        self._reload_index_reader(index_name)
     }
 
     /// Uses full text serach
     pub fn delete_structs(&mut self, index_name: &str, field_value: &str) -> Result<(), IndexError> {
         let schema = self._resolve_surfer_schema(index_name)?;
-        let terms = self._build_terms(&schema, field_value)?;
-        let _ = self._prepare_index_writer(index_name)?;
+        let terms = self._build_terms(schema, field_value)?;
+        self._prepare_index_writer(index_name)?;
         let writer = self.writers.get_mut(index_name).unwrap().as_mut().unwrap();
         for i in 0..terms.len() {
             let term = terms.get(i).unwrap().to_owned();
             let _ = writer.delete_term(term);
         }
         let _ = writer.commit()?;
+        self._reload_index_reader(index_name)
+    }
+
+    /// Deletes all documents, keeps the index
+    pub fn truncate(&mut self, index_name: &str) -> Result<(), IndexError> {
+        self._prepare_index_writer(index_name)?;
+        let writer = self.writers.get_mut(index_name).unwrap().as_mut().unwrap();
+        writer.delete_all_documents()?;
+        writer.commit()?;
+        self._reload_index_reader(index_name)
+    }
+
+    /// Closes reader & writer, removes the index directory & unregisters the schema
+    ///
+    /// The index stays registered when closing the writer or removing the directory fails.
+    pub fn drop_index(&mut self, index_name: &str) -> Result<(), IndexError> {
+        let path = self.which_index(index_name);
+        if path.is_none() {
+            let message = format!("Unable to drop index: {}", index_name);
+            let reason = format!("Index was missing: {} ", index_name);
+            return Err(IndexError::new(message, reason));
+        };
+        let path = path.unwrap();
+
+        if let Some(writer) = self.writers.get_mut(index_name).and_then(Option::take) {
+            writer.wait_merging_threads()?;
+        };
+        remove_dir_all(path)?;
+
+        self.writers.remove(index_name);
+        self.readers.remove(index_name);
+        self.indexes.remove(index_name);
+        self.fields.remove(index_name);
+        self.schemas.remove(index_name);
+        self.spellers.remove(index_name);
+        self.index_spellers.remove(index_name);
+        self.ids.remove(index_name);
         Ok(())
     }
 
@@ -589,6 +1462,19 @@ impl Surfer {
         self.multiple_structs_by_field::<T>(index_name, &conditions, limit, score)
     }
 
+    /// Lazily reads every live document e.g. for backups, reindexing or offline analytics
+    pub fn scan<T: DeserializeOwned>(&mut self, name: &str) -> Result<Option<Scan<T>>, IndexError> {
+        {
+            let result = self._prepare_index_reader(name);
+            if result.is_err() {
+                return Ok(None);
+            };
+        }
+        let schema = self._resolve_surfer_schema(name)?.clone();
+        // Leasing from the shared reader would block other reads for as long as the scan lives
+        let reader = open_snapshot_reader(self.indexes.get(name).unwrap())?;
+        Ok(Some(Scan::new(name, schema, reader.searcher())))
+    }
     /// Reads as string
     pub fn read_string(&mut self, name: &str, query: &str, limit: Option<usize>, score: Option<f32>) -> Result<Option<Vec<String>>, IndexError> {
         {
@@ -597,19 +1483,11 @@ impl Surfer {
                 return Ok(None);
             };
         }
-        let index = self.indexes.get(name).unwrap();
         let reader = self.readers.get(name).unwrap().as_ref().unwrap();
-
-        let default_fields = self.fields.get(name).unwrap().clone();
         let searcher = reader.searcher();
 
-        let query_parser = QueryParser::for_index(&index, default_fields);
-        let query = query_parser.parse_query(query)?;
-        let limit = if limit.is_some() {
-            limit.unwrap()
-        } else {
-            10
-        };
+        let query = self._build_all_fields_query(name, query)?;
+        let limit = limit.unwrap_or(10);
         let top_docs = searcher.search(&query, &TopDocs::with_limit(limit))?;
 
         let mut docs = Vec::with_capacity(top_docs.len());
@@ -617,54 +1495,150 @@ impl Surfer {
             if score.is_some() && doc_score < score.unwrap() {
                 continue;
             }
-            let doc = searcher.doc(doc_address)?;
+            let doc = self._read_document(name, &searcher, doc_address)?;
             let doc = self.jsonify(name, &doc)?;
             docs.push(doc);
         };
         Ok(Some(docs))
     }
+    /// Reads only the given stored fields into a, likely smaller, struct
+    pub fn read_projected_structs<T: DeserializeOwned>(&mut self, name: &str, query: &str, fields: &[String], limit: Option<usize>, score: Option<f32>) -> Result<Option<Vec<T>>, IndexError> {
+        {
+            let result = self._prepare_index_reader(name);
+            if result.is_err() {
+                return Ok(None);
+            };
+        }
+        self._validate_projection(name, fields)?;
+        let reader = self.readers.get(name).unwrap().as_ref().unwrap();
+        let searcher = reader.searcher();
 
-    /// Reads as struct
-    pub fn read_all_structs<T: Serialize + DeserializeOwned>(&mut self, name: &str, query: &str) -> Result<Option<Vec<T>>, IndexError> {
-        self.read_structs(name, query, None, None)
-    }
+        let query = self._build_full_text_query(name, query)?;
+        let limit = self._resolve_limit(limit);
+        let cutoff = score.unwrap_or(f32::NEG_INFINITY);
+        let top_docs = searcher.search(&query, &AboveScore::new(cutoff, TopDocs::with_limit(limit)))?;
 
-    /// Reads as struct
-    pub fn read_structs<T: Serialize + DeserializeOwned>(&mut self, name: &str, query: &str, limit: Option<usize>, score: Option<f32>) -> Result<Option<Vec<T>>, IndexError> {
+        let mut docs = Vec::with_capacity(top_docs.len());
+        for (_, doc_address) in top_docs {
+            let doc = self._read_document(name, &searcher, doc_address)?;
+            let doc = self._to_value(name, &doc, Some(fields))?;
+            let doc = serde_json::from_value::<T>(doc)?;
+            docs.push(doc);
+        };
+        Ok(Some(docs))
+    }
+    /// Every projected field has to be stored, byte arrays are read from fast fields instead
+    fn _validate_projection(&self, name: &str, fields: &[String]) -> Result<(), IndexError> {
+        let schema = self._resolve_surfer_schema(name)?;
+        for field_name in fields {
+            let message = format!("Unable to project: {}", name);
+            let field = match schema.get_field(field_name) {
+                Some(field) => field,
+                None => return Err(IndexError::new(message, format!("Missing field: {}", field_name))),
+            };
+            let is_bytes = schema.resolve_mapping().get(field_name) == Some(&SurferFieldTypes::Bytes);
+            if !is_bytes && !schema.get_field_entry(field).is_stored() {
+                return Err(IndexError::new(message, format!("Not a stored field: {}", field_name)));
+            };
+        };
+        Ok(())
+    }
+    /// Reads as string along with highlighted snippets
+    pub fn read_highlighted_string(&mut self, name: &str, query: &str, highlight: &Highlight, limit: Option<usize>, score: Option<f32>) -> Result<Option<Vec<Highlighted<String>>>, IndexError> {
         {
             let result = self._prepare_index_reader(name);
             if result.is_err() {
                 return Ok(None);
             };
         }
-        let index = self.indexes.get(name).unwrap();
+        let query = self._build_all_fields_query(name, query)?;
+        let docs = self._read_highlighted(name, &query, highlight, limit, score)?;
+        Ok(Some(docs))
+    }
+    /// Reads as struct along with highlighted snippets
+    pub fn read_highlighted_structs<T: Serialize + DeserializeOwned>(&mut self, name: &str, query: &str, highlight: &Highlight, limit: Option<usize>, score: Option<f32>) -> Result<Option<Vec<Highlighted<T>>>, IndexError> {
+        {
+            let result = self._prepare_index_reader(name);
+            if result.is_err() {
+                return Ok(None);
+            };
+        }
+        let query = self._build_full_text_query(name, query)?;
+        let hits = self._read_highlighted(name, &query, highlight, limit, score)?;
+        let mut docs = Vec::with_capacity(hits.len());
+        for hit in hits {
+            let doc = serde_json::from_str::<T>(hit.resolve_document())?;
+            docs.push(Highlighted::new(doc, hit.resolve_snippets().clone()));
+        };
+        Ok(Some(docs))
+    }
+    /// Top hits as json along with a snippet per matched text field
+    fn _read_highlighted(&self, name: &str, query: &dyn Query, highlight: &Highlight, limit: Option<usize>, score: Option<f32>) -> Result<Vec<Highlighted<String>>, IndexError> {
         let reader = self.readers.get(name).unwrap().as_ref().unwrap();
+        let searcher = reader.searcher();
+        let schema = self._resolve_surfer_schema(name)?;
+        let mappings = schema.resolve_mapping();
+        if let Some(fields) = highlight.resolve_fields() {
+            for field_name in fields {
+                if mappings.get(field_name) != Some(&SurferFieldTypes::String) {
+                    let message = format!("Unable to highlight: {}", name);
+                    let reason = format!("Not a text field: {}", field_name);
+                    return Err(IndexError::new(message, reason));
+                };
+            };
+        };
 
-        let surfer_schema = self.schemas.get(name).unwrap();
-        let mappings = surfer_schema.resolve_mapping();
-
-        let mut fields = Vec::<Field>::with_capacity(mappings.len());
-        for (f, fe) in surfer_schema.schema.fields() {
-            let name = fe.name();
-            if !mappings.contains_key(name) {
+        let mut generators = Vec::<(String, SnippetGenerator)>::new();
+        for (field_name, field_type) in mappings {
+            if *field_type != SurferFieldTypes::String || !highlight.includes(field_name) {
                 continue;
             };
-            let ft = mappings.get(name).unwrap();
-            match ft {
-                SurferFieldTypes::String => fields.push(f),
-                _ => {}
+            let field = schema.get_field(field_name).unwrap();
+            let mut generator = SnippetGenerator::create(&searcher, query, field)?;
+            generator.set_max_num_chars(highlight.resolve_fragment_length());
+            generators.push((field_name.to_string(), generator));
+        };
+
+        let limit = self._resolve_limit(limit);
+        let top_docs = searcher.search(query, &TopDocs::with_limit(limit))?;
+        let mut docs = Vec::with_capacity(top_docs.len());
+        for (doc_score, doc_address) in top_docs {
+            if score.is_some() && doc_score < score.unwrap() {
+                continue;
             }
+            let doc = self._read_document(name, &searcher, doc_address)?;
+            let mut snippets = BTreeMap::new();
+            for (field_name, generator) in &generators {
+                let snippet = generator.snippet_from_doc(&doc);
+                if snippet.highlighted().is_empty() {
+                    continue;
+                };
+                snippets.insert(field_name.to_string(), highlight.render(&snippet));
+            };
+            let doc = self.jsonify(name, &doc)?;
+            docs.push(Highlighted::new(doc, snippets));
         };
+        Ok(docs)
+    }
+
+    /// Reads as struct
+    pub fn read_all_structs<T: Serialize + DeserializeOwned>(&mut self, name: &str, query: &str) -> Result<Option<Vec<T>>, IndexError> {
+        self.read_structs(name, query, None, None)
+    }
 
+    /// Reads as struct
+    pub fn read_structs<T: Serialize + DeserializeOwned>(&mut self, name: &str, query: &str, limit: Option<usize>, score: Option<f32>) -> Result<Option<Vec<T>>, IndexError> {
+        {
+            let result = self._prepare_index_reader(name);
+            if result.is_err() {
+                return Ok(None);
+            };
+        }
+        let reader = self.readers.get(name).unwrap().as_ref().unwrap();
         let searcher = reader.searcher();
 
-        let query_parser = QueryParser::for_index(&index, fields);
-        let query = query_parser.parse_query(query)?;
-        let limit = if limit.is_some() {
-            limit.unwrap()
-        } else {
-            10
-        };
+        let query = self._build_full_text_query(name, query)?;
+        let limit = limit.unwrap_or(10);
         let top_docs = searcher.search(&query, &TopDocs::with_limit(limit))?;
 
         let mut docs = Vec::with_capacity(top_docs.len());
@@ -672,963 +1646,3891 @@ impl Surfer {
             if score.is_some() && doc_score < score.unwrap() {
                 continue;
             }
-            let doc = searcher.doc(doc_address)?;
+            let doc = self._read_document(name, &searcher, doc_address)?;
             let doc = self.jsonify(name, &doc)?;
             let doc = serde_json::from_str::<T>(&doc).unwrap();
             docs.push(doc);
         };
         Ok(Some(docs))
     }
-    /// Uses term search
-    fn multiple_structs_by_field<T: Serialize + DeserializeOwned>(&mut self, index_name: &str, conditions: &Vec<OrCondition>, limit: Option<usize>, score: Option<f32>) -> Result<Option<Vec<T>>, IndexError> {
-        let _ = self._prepare_index_reader(index_name)?;
-        let reader = self.readers.get(index_name).unwrap().as_ref().unwrap();
+    /// Reads as struct, the given boosts take over the ones of the schema for this call
+    pub fn read_boosted_structs<T: Serialize + DeserializeOwned>(&mut self, name: &str, query: &str, boosts: &HashMap<String, f32>, limit: Option<usize>, score: Option<f32>) -> Result<Option<Vec<T>>, IndexError> {
+        {
+            let result = self._prepare_index_reader(name);
+            if result.is_err() {
+                return Ok(None);
+            };
+        }
+        let reader = self.readers.get(name).unwrap().as_ref().unwrap();
         let searcher = reader.searcher();
-        let limit = self._resolve_limit(limit);
-        let cutoff = self._resolve_score(score);
-        let schema = self._resolve_surfer_schema(index_name)?;
-        let mut all_docs = HashSet::<SurferDocAddress>::new();
-
-        for condition in conditions {
-            let and = condition.resolve_conditions();
-            let mut docs = HashSet::new();
-            for (i, c) in and.iter().enumerate() {
-                let field_name = c.resolve_field_name();
-                let field_value = c.resolve_field_value();
-                let term = self._build_term(schema, field_name, field_value)?;
-                let query = self._build_term_query(term, None)?;
-                let mut tmp = HashSet::new();
-                let top_docs = searcher
-                    .search(&query, &TopDocs::with_limit(limit))
-                    .map_err(|e| {
-                        let message = "Error while term query".to_string();
-                        let reason = e.to_string();
-                        IndexError::new(message, reason)
-                    })?;
-
-
-                for (score, address) in top_docs.to_owned() {
-                    if score < cutoff {
-                        continue;
-                    };
-
-                    let address = SurferDocAddress::from(address);
-                    // println!("Found: {}", address);
-                    if i == 0 {
-                        tmp.insert(address);
-                        continue;
-                    }
+        let index = self.indexes.get(name).unwrap();
+        let schema = self._resolve_surfer_schema(name)?.with_field_boosts(boosts)?;
 
-                    if docs.contains(&address) {
-                        tmp.insert(address);
-                    }
-                }
+        let query = self._build_boosted_query(index, &schema, query)?;
+        let limit = self._resolve_limit(limit);
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(limit))?;
 
-                if tmp.is_empty() {
-                    docs.clear();
-                    break;
-                } else {
-                    docs.extend(tmp);
-                }
+        let mut docs = Vec::with_capacity(top_docs.len());
+        for (doc_score, doc_address) in top_docs {
+            if score.is_some() && doc_score < score.unwrap() {
+                continue;
             }
-            all_docs.extend(docs);
-        };
-
-        let mut docs = Vec::with_capacity(all_docs.len());
-        for doc_address in all_docs {
-            let doc = searcher.doc(doc_address.0)?;
-            let doc = self.jsonify(index_name, &doc)?;
-            let doc = serde_json::from_str::<T>(&doc).unwrap();
+            let doc = self._read_document(name, &searcher, doc_address)?;
+            let doc = self.jsonify(name, &doc)?;
+            let doc = serde_json::from_str::<T>(&doc)?;
             docs.push(doc);
         };
         Ok(Some(docs))
     }
-}
-
-/// Panics if somethings goes wrong
-impl Surfer {
-    pub fn new(builder: SurferBuilder) -> Self {
-        Surfer::try_from(builder).unwrap()
+    /// Number of documents matching the query, without reading any of them
+    pub fn count(&mut self, name: &str, query: &str) -> Result<Option<usize>, IndexError> {
+        {
+            let result = self._prepare_index_reader(name);
+            if result.is_err() {
+                return Ok(None);
+            };
+        }
+        let reader = self.readers.get(name).unwrap().as_ref().unwrap();
+        let searcher = reader.searcher();
+        let query = self._build_full_text_query(name, query)?;
+        let count = searcher.search(&query, &Count)?;
+        Ok(Some(count))
     }
-}
-
-/// Opens mmap dir
-fn initialize_mmap(name: &str, home: &str, schema: &Schema) -> Result<Index, IndexError> {
-    let path = resolve_index_directory_path(name, Some(home))?;
-    if path.exists() {
-        let dir = open_mmap_directory(path)?;
-        open_index(dir, None)
-    } else {
-        let dir = open_mmap_directory(path)?;
-        open_index(dir, Some(&schema))
+    /// Number of documents matching the conditions, the score cutoff isn't applied
+    fn count_by_field(&mut self, index_name: &str, conditions: &[OrCondition]) -> Result<usize, IndexError> {
+        self._prepare_index_reader(index_name)?;
+        let reader = self.readers.get(index_name).unwrap().as_ref().unwrap();
+        let searcher = reader.searcher();
+        let schema = self._resolve_surfer_schema(index_name)?;
+        let expression = Expression::from(conditions);
+        let query = expression.compile(&|condition: &AndCondition| self._build_condition_query(searcher.index(), schema, condition))?;
+        let count = searcher.search(&query, &Count)?;
+        Ok(count)
     }
-}
-
-/// Get home location
-fn extract_home(builder: &SurferBuilder) -> Result<String, IndexError> {
-    let home = builder.home.as_ref();
-    let home = resolve_home(home)?;
-    Ok(home.to_str().unwrap().to_string())
-}
-
-/// Setup indexes
-fn initialized_index(home: &str, builder: &SurferBuilder) -> Result<HashMap<String, Index>, IndexError> {
-    let schemas = &builder.schemas;
-    let mut indexes = HashMap::<String, Index>::with_capacity(schemas.len());
-    for (name, schema) in schemas {
-        let index = initialize_mmap(name, &home, &schema)?;
-        indexes.insert(name.to_string(), index);
-    };
-    Ok(indexes)
-}
-
-/// Extract field information
-fn extract_fields(builder: &SurferBuilder) -> HashMap<String, Vec<Field>> {
-    let data = &builder.schemas;
-    let mut fields = HashMap::<String, Vec<Field>>::with_capacity(data.len());
-    for (data, schema) in data {
-        let key = data.clone();
-        let value: Vec<Field> = schema.fields().map(|(f, _)| f).collect();
-        fields.insert(key, value);
-    };
-    fields
-}
-
+    /// Bucketed counts over every document matching the query
+    pub fn aggregate(&mut self, name: &str, query: &str, aggregations: &[Aggregation]) -> Result<Option<Vec<Buckets>>, IndexError> {
+        {
+            let result = self._prepare_index_reader(name);
+            if result.is_err() {
+                return Ok(None);
+            };
+        }
+        let reader = self.readers.get(name).unwrap().as_ref().unwrap();
+        let searcher = reader.searcher();
+        let schema = self._resolve_surfer_schema(name)?;
+        let collector = AggregationCollector::new(schema, aggregations)?;
+        let query = self._build_full_text_query(name, query)?;
+        let fruit = searcher.search(&query, &collector)?;
+        Ok(Some(collector.resolve_buckets(fruit)))
+    }
+    /// Count, min, max, sum, mean & percentiles of a numeric field over every match
+    pub fn stats(&mut self, name: &str, query: &str, field_name: &str, percents: &[f64]) -> Result<Option<Stats>, IndexError> {
+        {
+            let result = self._prepare_index_reader(name);
+            if result.is_err() {
+                return Ok(None);
+            };
+        }
+        let reader = self.readers.get(name).unwrap().as_ref().unwrap();
+        let searcher = reader.searcher();
+        let schema = self._resolve_surfer_schema(name)?;
+        let collector = StatsCollector::new(schema, field_name, percents)?;
+        let query = self._build_full_text_query(name, query)?;
+        let summary = searcher.search(&query, &collector)?;
+        Ok(Some(collector.resolve_stats(summary)))
+    }
+    /// Stats of a numeric field over every match of the expression, the score cutoff isn't applied
+    fn stats_by_expression(&mut self, index_name: &str, expression: &Expression, field_name: &str, percents: &[f64]) -> Result<Stats, IndexError> {
+        self._prepare_index_reader(index_name)?;
+        let reader = self.readers.get(index_name).unwrap().as_ref().unwrap();
+        let searcher = reader.searcher();
+        let schema = self._resolve_surfer_schema(index_name)?;
+        let collector = StatsCollector::new(schema, field_name, percents)?;
+        let query = expression.compile(&|condition: &AndCondition| self._build_condition_query(searcher.index(), schema, condition))?;
+        let summary = searcher.search(&query, &collector)?;
+        Ok(collector.resolve_stats(summary))
+    }
+    /// Reads as struct, aggregations run over every match & not just the ones read
+    pub fn read_aggregated_structs<T: Serialize + DeserializeOwned>(&mut self, name: &str, query: &str, aggregations: &[Aggregation], limit: Option<usize>, score: Option<f32>) -> Result<Option<Aggregated<T>>, IndexError> {
+        {
+            let result = self._prepare_index_reader(name);
+            if result.is_err() {
+                return Ok(None);
+            };
+        }
+        let reader = self.readers.get(name).unwrap().as_ref().unwrap();
+        let searcher = reader.searcher();
+        let schema = self._resolve_surfer_schema(name)?;
+        let collector = AggregationCollector::new(schema, aggregations)?;
+        let query = self._build_full_text_query(name, query)?;
+        let limit = self._resolve_limit(limit);
+        let (top_docs, fruit) = searcher.search(&query, &(TopDocs::with_limit(limit), collector.clone()))?;
+        let top_docs = top_docs
+            .into_iter()
+            .filter(|(doc_score, _)| score.is_none() || *doc_score >= score.unwrap())
+            .collect();
+        let hits = self._resolve_hits::<T>(name, &searcher, top_docs)?;
+        let documents = hits.into_iter().map(Hit::into_document).collect();
+        Ok(Some(Aggregated::new(documents, collector.resolve_buckets(fruit))))
+    }
+    /// Bucketed counts over every document matching the expression, the score cutoff isn't applied
+    fn aggregate_by_expression(&mut self, index_name: &str, expression: &Expression, aggregations: &[Aggregation]) -> Result<Vec<Buckets>, IndexError> {
+        self._prepare_index_reader(index_name)?;
+        let reader = self.readers.get(index_name).unwrap().as_ref().unwrap();
+        let searcher = reader.searcher();
+        let schema = self._resolve_surfer_schema(index_name)?;
+        let collector = AggregationCollector::new(schema, aggregations)?;
+        let query = expression.compile(&|condition: &AndCondition| self._build_condition_query(searcher.index(), schema, condition))?;
+        let fruit = searcher.search(&query, &collector)?;
+        Ok(collector.resolve_buckets(fruit))
+    }
+    /// Reads as struct, also reporting the hits dropped by the score cutoff
+    pub fn read_structs_debug<T: Serialize + DeserializeOwned>(&mut self, name: &str, query: &str, limit: Option<usize>, score: Option<f32>) -> Result<Option<Debugged<T>>, IndexError> {
+        {
+            let result = self._prepare_index_reader(name);
+            if result.is_err() {
+                return Ok(None);
+            };
+        }
+        let reader = self.readers.get(name).unwrap().as_ref().unwrap();
+        let searcher = reader.searcher();
+
+        let query = self._build_full_text_query(name, query)?;
+        let limit = self._resolve_limit(limit);
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(limit))?;
+        let cutoff = score.unwrap_or(f32::MIN);
+        let (top_docs, dropped) = top_docs
+            .into_iter()
+            .partition(|(doc_score, _)| *doc_score >= cutoff);
+        self._resolve_debugged(name, &searcher, top_docs, dropped, cutoff).map(Some)
+    }
+    /// Uses term search, also reporting the hits dropped by the score cutoff
+    fn multiple_structs_debug<T: Serialize + DeserializeOwned>(&mut self, index_name: &str, conditions: &[OrCondition], limit: Option<usize>, score: Option<f32>) -> Result<Option<Debugged<T>>, IndexError> {
+        self._prepare_index_reader(index_name)?;
+        let reader = self.readers.get(index_name).unwrap().as_ref().unwrap();
+        let searcher = reader.searcher();
+        let limit = self._resolve_limit(limit);
+        let cutoff = self._resolve_score(score);
+        let schema = self._resolve_surfer_schema(index_name)?;
+        let expression = Expression::from(conditions);
+        let (top_docs, dropped) = self._search_expression_with_dropped(&searcher, schema, &expression, limit, cutoff)?;
+        self._resolve_debugged(index_name, &searcher, top_docs, dropped, cutoff).map(Some)
+    }
+    fn _resolve_debugged<T: Serialize + DeserializeOwned>(&self, name: &str, searcher: &Searcher, top_docs: ScoredAddresses, dropped: ScoredAddresses, cutoff: f32) -> Result<Debugged<T>, IndexError> {
+        let hits = self._resolve_hits::<T>(name, searcher, top_docs)?;
+        let documents = hits.into_iter().map(Hit::into_document).collect();
+        let dropped = dropped
+            .into_iter()
+            .map(|(doc_score, doc_address)| Dropped::new(doc_score, cutoff, SegmentDocRef::from_address(searcher, doc_address)))
+            .collect();
+        Ok(Debugged::new(documents, dropped))
+    }
+    /// How the query scores the referenced document, as a json tree
+    pub fn explain(&mut self, name: &str, query: &str, reference: &SegmentDocRef) -> Result<Option<serde_json::Value>, IndexError> {
+        {
+            let result = self._prepare_index_reader(name);
+            if result.is_err() {
+                return Ok(None);
+            };
+        }
+        let query = self._build_full_text_query(name, query)?;
+        self._explain(name, &query, reference)
+    }
+    /// How the conditions score the referenced document, as a json tree
+    fn explain_conditions(&mut self, index_name: &str, conditions: &[OrCondition], reference: &SegmentDocRef) -> Result<Option<serde_json::Value>, IndexError> {
+        self._prepare_index_reader(index_name)?;
+        let schema = self._resolve_surfer_schema(index_name)?;
+        let index = self.indexes.get(index_name).unwrap();
+        let expression = Expression::from(conditions);
+        let query = expression.compile(&|condition: &AndCondition| self._build_condition_query(index, schema, condition))?;
+        self._explain(index_name, &query, reference)
+    }
+    fn _explain(&self, name: &str, query: &dyn Query, reference: &SegmentDocRef) -> Result<Option<serde_json::Value>, IndexError> {
+        let reader = self.readers.get(name).unwrap().as_ref().unwrap();
+        let searcher = reader.searcher();
+        let doc_address = match reference.resolve_address(&searcher) {
+            Some(doc_address) => doc_address,
+            None => return Ok(None),
+        };
+        let explanation = query.explain(&searcher, doc_address).map_err(|e| {
+            let message = format!("Unable to explain {}", reference);
+            let reason = e.to_string();
+            IndexError::new(message, reason)
+        })?;
+        let explanation = serde_json::to_value(&explanation)?;
+        Ok(Some(explanation))
+    }
+    /// Boost for hits on a field of the index, applies to queries & conditions
+    pub fn set_field_boost(&mut self, name: &str, field_name: &str, boost: f32) -> Result<(), IndexError> {
+        self._resolve_surfer_schema(name)?;
+        self.schemas.get_mut(name).unwrap().set_field_boost(field_name, boost)
+    }
+    /// Text fields searched by queries of the index when no field is named
+    pub fn set_default_fields(&mut self, name: &str, field_names: &[String]) -> Result<(), IndexError> {
+        self._resolve_surfer_schema(name)?;
+        self.schemas.get_mut(name).unwrap().set_default_fields(field_names)
+    }
+    /// Dictionary used for "did you mean" suggestions on the index
+    ///
+    /// Kept as given, later inserts don't add their words to it.
+    pub fn set_speller(&mut self, name: &str, speller: FuzzyWord) {
+        self.spellers.insert(name.to_string(), speller);
+    }
+    /// Goes back to the dictionary of the terms in the index, built right away
+    ///
+    /// Unlike a speller set by hand, it is rebuilt on the first suggestion after a commit.
+    pub fn build_speller(&mut self, name: &str) -> Result<(), IndexError> {
+        self.spellers.remove(name);
+        self._resolve_index_speller(name)?;
+        Ok(())
+    }
+    /// Dictionary built from the index, cached until the next commit
+    fn _resolve_index_speller(&mut self, name: &str) -> Result<&FuzzyWord, IndexError> {
+        if !self.index_spellers.contains_key(name) {
+            let speller = self._build_index_speller(name)?;
+            self.index_spellers.insert(name.to_string(), speller);
+        };
+        Ok(self.index_spellers.get(name).unwrap())
+    }
+    /// Dictionary of the text terms in the index weighted by document frequency
+    fn _build_index_speller(&mut self, name: &str) -> Result<FuzzyWord, IndexError> {
+        self._prepare_index_reader(name)?;
+        let reader = self.readers.get(name).unwrap().as_ref().unwrap();
+        let searcher = reader.searcher();
+        let schema = self._resolve_surfer_schema(name)?;
+
+        let mut words = HashMap::<String, u64>::new();
+        for (field_name, field_type) in schema.resolve_mapping() {
+            if *field_type != SurferFieldTypes::String {
+                continue;
+            };
+            let field = schema.get_field(field_name).unwrap();
+            for segment_reader in searcher.segment_readers() {
+                let inverted_index = segment_reader.inverted_index(field);
+                let mut terms = inverted_index.terms().stream();
+                while terms.advance() {
+                    if let Ok(word) = std::str::from_utf8(terms.key()) {
+                        *words.entry(word.to_string()).or_insert(0) += u64::from(terms.value().doc_freq);
+                    };
+                };
+            };
+        };
+        Ok(FuzzyWord::from_words(words))
+    }
+    /// Reads structs, suggests a corrected query when there are too few hits
+    ///
+    /// Uses the speller set on the index, or a dictionary built from the index when there is none
+    pub fn read_structs_with_suggestion<T: Serialize + DeserializeOwned>(&mut self, name: &str, query: &str, suggest: &Suggest, limit: Option<usize>, score: Option<f32>) -> Result<Option<Suggested<T>>, IndexError> {
+        let docs = self.read_structs::<T>(name, query, limit, score)?;
+        if docs.is_none() {
+            return Ok(None);
+        };
+        let docs = docs.unwrap();
+        if docs.len() >= suggest.resolve_threshold() {
+            return Ok(Some(Suggested::new(docs, None, false)));
+        };
+
+        let suggestion = match self.spellers.get(name) {
+            Some(speller) => correct_query(speller, query),
+            None => correct_query(self._resolve_index_speller(name)?, query),
+        };
+        if suggestion.is_none() || !suggest.resolve_rerun() {
+            return Ok(Some(Suggested::new(docs, suggestion, false)));
+        };
+        let suggestion = suggestion.unwrap();
+        let corrected = self.read_structs::<T>(name, &suggestion, limit, score)?.unwrap_or_default();
+        if corrected.len() <= docs.len() {
+            return Ok(Some(Suggested::new(docs, Some(suggestion), false)));
+        };
+        Ok(Some(Suggested::new(corrected, Some(suggestion), true)))
+    }
+    /// Typo tolerant read, fields missing from fuzziness are not searched unless it is empty
+    pub fn read_fuzzy_structs<T: Serialize + DeserializeOwned>(&mut self, name: &str, query: &str, fuzziness: &HashMap<String, Fuzziness>, limit: Option<usize>, score: Option<f32>) -> Result<Option<Vec<T>>, IndexError> {
+        {
+            let result = self._prepare_index_reader(name);
+            if result.is_err() {
+                return Ok(None);
+            };
+        }
+        let reader = self.readers.get(name).unwrap().as_ref().unwrap();
+        let searcher = reader.searcher();
+
+        let query = self._build_fuzzy_query(name, query, fuzziness)?;
+        let limit = self._resolve_limit(limit);
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(limit))?;
+
+        let mut docs = Vec::with_capacity(top_docs.len());
+        for (doc_score, doc_address) in top_docs {
+            if score.is_some() && doc_score < score.unwrap() {
+                continue;
+            }
+            let doc = self._read_document(name, &searcher, doc_address)?;
+            let doc = self.jsonify(name, &doc)?;
+            let doc = serde_json::from_str::<T>(&doc)?;
+            docs.push(doc);
+        };
+        Ok(Some(docs))
+    }
+    /// Reads as struct along with score & document reference, best hits first
+    pub fn read_scored_structs<T: Serialize + DeserializeOwned>(&mut self, name: &str, query: &str, limit: Option<usize>, score: Option<f32>) -> Result<Option<Vec<Hit<T>>>, IndexError> {
+        {
+            let result = self._prepare_index_reader(name);
+            if result.is_err() {
+                return Ok(None);
+            };
+        }
+        let reader = self.readers.get(name).unwrap().as_ref().unwrap();
+        let searcher = reader.searcher();
+
+        let query = self._build_full_text_query(name, query)?;
+        let limit = self._resolve_limit(limit);
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(limit))?;
+        let top_docs: Vec<(f32, DocAddress)> = top_docs
+            .into_iter()
+            .filter(|(doc_score, _)| score.is_none() || *doc_score >= score.unwrap())
+            .collect();
+        self._resolve_hits(name, &searcher, top_docs).map(Some)
+    }
+    /// Reads as struct with the score of each hit, normalized as the cutoff asks
+    pub fn read_normalized_structs<T: Serialize + DeserializeOwned>(&mut self, name: &str, query: &str, limit: Option<usize>, cutoff: &Cutoff) -> Result<Option<Vec<Hit<T>>>, IndexError> {
+        cutoff.validate()?;
+        {
+            let result = self._prepare_index_reader(name);
+            if result.is_err() {
+                return Ok(None);
+            };
+        }
+        let reader = self.readers.get(name).unwrap().as_ref().unwrap();
+        let searcher = reader.searcher();
+
+        let query = self._build_full_text_query(name, query)?;
+        let limit = self._resolve_limit(limit);
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(limit))?;
+        self._resolve_hits(name, &searcher, cutoff.apply(top_docs)).map(Some)
+    }
+    /// Reads the struct behind a reference, none once it is deleted or its segment merged away
+    pub fn read_struct_by_reference<T: Serialize + DeserializeOwned>(&mut self, name: &str, reference: &SegmentDocRef) -> Result<Option<T>, IndexError> {
+        self._prepare_index_reader(name)?;
+        let reader = self.readers.get(name).unwrap().as_ref().unwrap();
+        let searcher = reader.searcher();
+        let doc_address = match reference.resolve_address(&searcher) {
+            Some(doc_address) => doc_address,
+            None => return Ok(None),
+        };
+        let doc = self._read_document(name, &searcher, doc_address)?;
+        let doc = self.jsonify(name, &doc)?;
+        let doc = serde_json::from_str::<T>(&doc)?;
+        Ok(Some(doc))
+    }
+    /// Documents similar to the referenced one, best first, the source itself left out
+    ///
+    /// None when the reference went stale.
+    pub fn more_like_this<T: Serialize + DeserializeOwned>(&mut self, name: &str, reference: &SegmentDocRef, options: &MoreLikeThis, limit: Option<usize>) -> Result<Option<Vec<Hit<T>>>, IndexError> {
+        {
+            let result = self._prepare_index_reader(name);
+            if result.is_err() {
+                return Ok(None);
+            };
+        }
+        let reader = self.readers.get(name).unwrap().as_ref().unwrap();
+        let searcher = reader.searcher();
+        let source = match reference.resolve_address(&searcher) {
+            Some(doc_address) => doc_address,
+            None => return Ok(None),
+        };
+        let document = searcher.doc(source)?;
+        let query = self._build_similar_query(name, &searcher, &document, options)?;
+        let limit = self._resolve_limit(limit);
+        let top_docs = searcher
+            .search(&query, &TopDocs::with_limit(limit + 1))?
+            .into_iter()
+            .filter(|(_, doc_address)| *doc_address != source)
+            .take(limit)
+            .collect();
+        self._resolve_hits(name, &searcher, top_docs).map(Some)
+    }
+    /// Documents similar to the given one, best first, documents equal to it left out
+    pub fn more_like_this_struct<T: Serialize + DeserializeOwned>(&mut self, name: &str, data: &T, options: &MoreLikeThis, limit: Option<usize>) -> Result<Option<Vec<Hit<T>>>, IndexError> {
+        {
+            let result = self._prepare_index_reader(name);
+            if result.is_err() {
+                return Ok(None);
+            };
+        }
+        let reader = self.readers.get(name).unwrap().as_ref().unwrap();
+        let searcher = reader.searcher();
+        let document = self._build_document(name, data)?;
+        let query = self._build_similar_query(name, &searcher, &document, options)?;
+        let limit = self._resolve_limit(limit);
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(limit + 1))?;
+        let source = serde_json::to_value(data)?;
+        let mut hits = Vec::with_capacity(limit);
+        for hit in self._resolve_hits::<T>(name, &searcher, top_docs)? {
+            if serde_json::to_value(hit.resolve_document())? != source {
+                hits.push(hit);
+            };
+        };
+        hits.truncate(limit);
+        Ok(Some(hits))
+    }
+    /// Weighted query over the most significant terms of the text fields of the document
+    fn _build_similar_query(&self, name: &str, searcher: &Searcher, document: &Document, options: &MoreLikeThis) -> Result<Box<dyn Query>, IndexError> {
+        let index = self.indexes.get(name).unwrap();
+        let schema = self._resolve_surfer_schema(name)?;
+        let mappings = schema.resolve_mapping();
+        for field_name in options.resolve_fields().into_iter().flatten() {
+            if mappings.get(field_name) != Some(&SurferFieldTypes::String) {
+                let message = "Unable to find similar documents".to_string();
+                let reason = format!("Terms are only picked from text fields: {}", field_name);
+                return Err(IndexError::new(message, reason));
+            };
+        };
+
+        let mut frequencies = HashMap::<Term, usize>::new();
+        for field_value in document.field_values() {
+            let field = field_value.field();
+            let field_name = schema.get_field_name(field);
+            if mappings.get(field_name) != Some(&SurferFieldTypes::String) || !options.includes(field_name) {
+                continue;
+            };
+            if let SchemaValue::Str(text) = field_value.value() {
+                for word in self._tokenize(index, field, text)? {
+                    *frequencies.entry(Term::from_field_text(field, &word)).or_insert(0) += 1;
+                };
+            };
+        };
+
+        let terms = options.select_terms(frequencies, |term| searcher.doc_freq(term), searcher.num_docs());
+        let mut clauses = Vec::<(Occur, Box<dyn Query>)>::with_capacity(terms.len());
+        for (term, weight) in terms {
+            let boost = schema.resolve_field_boost(schema.get_field_name(term.field())).unwrap_or(1f32);
+            let query = Box::new(TermQuery::new(term, IndexRecordOption::WithFreqs));
+            clauses.push((Occur::Should, Box::new(BoostQuery::new(query, weight * boost))));
+        };
+        Ok(Box::new(BooleanQuery::from(clauses)))
+    }
+    /// Uses term search, hits carry the combined score of the conditions normalized as the cutoff asks
+    fn multiple_normalized_structs<T: Serialize + DeserializeOwned>(&mut self, index_name: &str, expression: &Expression, limit: Option<usize>, cutoff: &Cutoff) -> Result<Option<Vec<Hit<T>>>, IndexError> {
+        cutoff.validate()?;
+        self._prepare_index_reader(index_name)?;
+        let reader = self.readers.get(index_name).unwrap().as_ref().unwrap();
+        let searcher = reader.searcher();
+        let limit = self._resolve_limit(limit);
+        let schema = self._resolve_surfer_schema(index_name)?;
+        let top_docs = match cutoff {
+            // Keeps filter only expressions clear of the raw cutoff
+            Cutoff::Raw(score) => self._search_expression(&searcher, schema, expression, limit, *score)?,
+            _ => cutoff.apply(self._search_expression(&searcher, schema, expression, limit, 0f32)?),
+        };
+        self._resolve_hits(index_name, &searcher, top_docs).map(Some)
+    }
+    /// Uses term search, hits carry the combined score of the conditions
+    fn multiple_scored_structs<T: Serialize + DeserializeOwned>(&mut self, index_name: &str, conditions: &[OrCondition], limit: Option<usize>, score: Option<f32>) -> Result<Option<Vec<Hit<T>>>, IndexError> {
+        self._prepare_index_reader(index_name)?;
+        let reader = self.readers.get(index_name).unwrap().as_ref().unwrap();
+        let searcher = reader.searcher();
+        let limit = self._resolve_limit(limit);
+        let cutoff = self._resolve_score(score);
+        let schema = self._resolve_surfer_schema(index_name)?;
+        let expression = Expression::from(conditions);
+        let top_docs = self._search_expression(&searcher, schema, &expression, limit, cutoff)?;
+        self._resolve_hits(index_name, &searcher, top_docs).map(Some)
+    }
+    fn _resolve_hits<T: Serialize + DeserializeOwned>(&self, name: &str, searcher: &Searcher, top_docs: Vec<(f32, DocAddress)>) -> Result<Vec<Hit<T>>, IndexError> {
+        let mut hits = Vec::with_capacity(top_docs.len());
+        for (doc_score, doc_address) in top_docs {
+            let doc = self._read_document(name, searcher, doc_address)?;
+            let doc = self.jsonify(name, &doc)?;
+            let doc = serde_json::from_str::<T>(&doc)?;
+            let reference = SegmentDocRef::from_address(searcher, doc_address);
+            hits.push(Hit::new(doc_score, reference, doc));
+        };
+        Ok(hits)
+    }
+    /// Uses term search, best hits first
+    fn multiple_structs_by_field<T: Serialize + DeserializeOwned>(&mut self, index_name: &str, conditions: &[OrCondition], limit: Option<usize>, score: Option<f32>) -> Result<Option<Vec<T>>, IndexError> {
+        self._prepare_index_reader(index_name)?;
+        let reader = self.readers.get(index_name).unwrap().as_ref().unwrap();
+        let searcher = reader.searcher();
+        let limit = self._resolve_limit(limit);
+        let cutoff = self._resolve_score(score);
+        let schema = self._resolve_surfer_schema(index_name)?;
+        let expression = Expression::from(conditions);
+        let top_docs = self._search_expression(&searcher, schema, &expression, limit, cutoff)?;
+
+        let mut docs = Vec::with_capacity(top_docs.len());
+        for (_, doc_address) in top_docs {
+            let doc = self._read_document(index_name, &searcher, doc_address)?;
+            let doc = self.jsonify(index_name, &doc)?;
+            let doc = serde_json::from_str::<T>(&doc)?;
+            docs.push(doc);
+        };
+        Ok(Some(docs))
+    }
+    /// Best hits first, the given boosts take over the ones of the schema for this call
+    fn multiple_boosted_structs<T: Serialize + DeserializeOwned>(&mut self, index_name: &str, conditions: &[OrCondition], boosts: &HashMap<String, f32>, limit: Option<usize>, score: Option<f32>) -> Result<Option<Vec<T>>, IndexError> {
+        self._prepare_index_reader(index_name)?;
+        let reader = self.readers.get(index_name).unwrap().as_ref().unwrap();
+        let searcher = reader.searcher();
+        let limit = self._resolve_limit(limit);
+        let cutoff = self._resolve_score(score);
+        let schema = self._resolve_surfer_schema(index_name)?.with_field_boosts(boosts)?;
+        let expression = Expression::from(conditions);
+        let top_docs = self._search_expression(&searcher, &schema, &expression, limit, cutoff)?;
+
+        let mut docs = Vec::with_capacity(top_docs.len());
+        for (_, doc_address) in top_docs {
+            let doc = self._read_document(index_name, &searcher, doc_address)?;
+            let doc = self.jsonify(index_name, &doc)?;
+            let doc = serde_json::from_str::<T>(&doc)?;
+            docs.push(doc);
+        };
+        Ok(Some(docs))
+    }
+    /// Reads structs matching the expression, best hits first
+    pub fn read_structs_by_expression<T: Serialize + DeserializeOwned>(&mut self, index_name: &str, expression: &Expression, limit: Option<usize>, score: Option<f32>) -> Result<Option<Vec<T>>, IndexError> {
+        self._prepare_index_reader(index_name)?;
+        let reader = self.readers.get(index_name).unwrap().as_ref().unwrap();
+        let searcher = reader.searcher();
+        let limit = self._resolve_limit(limit);
+        let cutoff = self._resolve_score(score);
+        let schema = self._resolve_surfer_schema(index_name)?;
+        let top_docs = self._search_expression(&searcher, schema, expression, limit, cutoff)?;
+
+        let mut docs = Vec::with_capacity(top_docs.len());
+        for (_, doc_address) in top_docs {
+            let doc = self._read_document(index_name, &searcher, doc_address)?;
+            let doc = self.jsonify(index_name, &doc)?;
+            let doc = serde_json::from_str::<T>(&doc)?;
+            docs.push(doc);
+        };
+        Ok(Some(docs))
+    }
+    /// Runs a SQL like statement, see Statement
+    ///
+    /// Without LIMIT a page of 10 is read, the cursor of the page leads on to the next one.
+    pub fn query_page(&mut self, query: &str) -> Result<Option<Page<serde_json::Value>>, IndexError> {
+        let statement = Statement::parse(query)?;
+        let index_name = statement.resolve_index_name().as_str();
+        {
+            let result = self._prepare_index_reader(index_name);
+            if result.is_err() {
+                return Ok(None);
+            };
+        }
+        let reader = self.readers.get(index_name).unwrap().as_ref().unwrap();
+        let searcher = reader.searcher();
+        let schema = self._resolve_surfer_schema(index_name)?;
+        let expression = statement.resolve_expression(searcher.index(), schema)?;
+        let sort = statement.resolve_sort(schema)?;
+        let query = expression.compile(&|condition: &AndCondition| self._build_condition_query(searcher.index(), schema, condition))?;
+        let limit = self._resolve_limit(statement.resolve_limit());
+        let paging = Paging::new(limit, statement.resolve_offset());
+
+        let (addresses, total, cursor) = match sort {
+            Some(sort) => {
+                let field = sort.resolve_field(schema, schema.resolve_mapping())?;
+                let collector = (Count, TopDocsByField::new(field, &sort, &paging, None));
+                let (total, top_docs) = searcher.search(&query, &collector)?;
+                let cursor = self._resolve_next_keyed_cursor(&searcher, &top_docs, &paging);
+                let addresses: Vec<DocAddress> = top_docs.into_iter().map(|(_, _, address)| address).collect();
+                (addresses, total, cursor)
+            }
+            None => {
+                let (top_docs, total) = self._search_page(&searcher, &query, &paging, None)?;
+                let cursor = self._resolve_next_cursor(&searcher, &top_docs, &paging);
+                let addresses: Vec<DocAddress> = top_docs.into_iter().map(|(_, address)| address).collect();
+                (addresses, total, cursor)
+            }
+        };
+
+        let fields = statement.resolve_fields();
+        let mut docs = Vec::with_capacity(addresses.len());
+        for doc_address in addresses {
+            let doc = self._read_document(index_name, &searcher, doc_address)?;
+            let doc = self._to_value(index_name, &doc, fields.as_deref())?;
+            docs.push(doc);
+        };
+        Ok(Some(Page::new(docs, total, cursor)))
+    }
+    /// Reads a page as string
+    pub fn read_string_page(&mut self, name: &str, query: &str, paging: &Paging, score: Option<f32>) -> Result<Option<Page<String>>, IndexError> {
+        {
+            let result = self._prepare_index_reader(name);
+            if result.is_err() {
+                return Ok(None);
+            };
+        }
+        let reader = self.readers.get(name).unwrap().as_ref().unwrap();
+        let searcher = reader.searcher();
+
+        let query = self._build_all_fields_query(name, query)?;
+        let (top_docs, total) = self._search_page(&searcher, &query, paging, score)?;
+        let cursor = self._resolve_next_cursor(&searcher, &top_docs, paging);
+
+        let mut docs = Vec::with_capacity(top_docs.len());
+        for (_, doc_address) in top_docs {
+            let doc = self._read_document(name, &searcher, doc_address)?;
+            let doc = self.jsonify(name, &doc)?;
+            docs.push(doc);
+        };
+        Ok(Some(Page::new(docs, total, cursor)))
+    }
+    /// Reads a page as struct
+    pub fn read_structs_page<T: Serialize + DeserializeOwned>(&mut self, name: &str, query: &str, paging: &Paging, score: Option<f32>) -> Result<Option<Page<T>>, IndexError> {
+        {
+            let result = self._prepare_index_reader(name);
+            if result.is_err() {
+                return Ok(None);
+            };
+        }
+        let reader = self.readers.get(name).unwrap().as_ref().unwrap();
+        let searcher = reader.searcher();
+
+        let query = self._build_full_text_query(name, query)?;
+        let (top_docs, total) = self._search_page(&searcher, &query, paging, score)?;
+        let cursor = self._resolve_next_cursor(&searcher, &top_docs, paging);
+
+        let mut docs = Vec::with_capacity(top_docs.len());
+        for (_, doc_address) in top_docs {
+            let doc = self._read_document(name, &searcher, doc_address)?;
+            let doc = self.jsonify(name, &doc)?;
+            let doc = serde_json::from_str::<T>(&doc)?;
+            docs.push(doc);
+        };
+        Ok(Some(Page::new(docs, total, cursor)))
+    }
+    /// Uses term search, best hits first
+    fn multiple_structs_page<T: Serialize + DeserializeOwned>(&mut self, index_name: &str, conditions: &[OrCondition], paging: &Paging, score: Option<f32>) -> Result<Option<Page<T>>, IndexError> {
+        self._prepare_index_reader(index_name)?;
+        let reader = self.readers.get(index_name).unwrap().as_ref().unwrap();
+        let searcher = reader.searcher();
+        let cutoff = self._resolve_score(score);
+        let schema = self._resolve_surfer_schema(index_name)?;
+        let expression = Expression::from(conditions);
+        let (query, cutoff) = self._compile_expression(&searcher, schema, &expression, cutoff)?;
+        let (top_docs, total) = self._search_page(&searcher, &query, paging, Some(cutoff))?;
+        let cursor = self._resolve_next_cursor(&searcher, &top_docs, paging);
+
+        let mut docs = Vec::with_capacity(top_docs.len());
+        for (_, doc_address) in top_docs {
+            let doc = self._read_document(index_name, &searcher, doc_address)?;
+            let doc = self.jsonify(index_name, &doc)?;
+            let doc = serde_json::from_str::<T>(&doc)?;
+            docs.push(doc);
+        };
+        Ok(Some(Page::new(docs, total, cursor)))
+    }
+    /// Reads a page as struct ordered by a numeric fast field, hits below the score cutoff are left out
+    pub fn read_sorted_structs<T: Serialize + DeserializeOwned>(&mut self, name: &str, query: &str, sort: &Sort, paging: &Paging, score: Option<f32>) -> Result<Option<Page<T>>, IndexError> {
+        {
+            let result = self._prepare_index_reader(name);
+            if result.is_err() {
+                return Ok(None);
+            };
+        }
+        let reader = self.readers.get(name).unwrap().as_ref().unwrap();
+        let searcher = reader.searcher();
+
+        let schema = self._resolve_surfer_schema(name)?;
+        let field = sort.resolve_field(schema, schema.resolve_mapping())?;
+        let query = self._build_full_text_query(name, query)?;
+        let collector = (Count, TopDocsByField::new(field, sort, paging, paging.resolve_position(&searcher, true)?));
+        let (total, top_docs) = match score {
+            Some(cutoff) => searcher.search(&query, &AboveScore::new(cutoff, collector))?,
+            None => searcher.search(&query, &collector)?,
+        };
+        let cursor = self._resolve_next_keyed_cursor(&searcher, &top_docs, paging);
+
+        let mut docs = Vec::with_capacity(top_docs.len());
+        for (_, _, doc_address) in top_docs {
+            let doc = self._read_document(name, &searcher, doc_address)?;
+            let doc = self.jsonify(name, &doc)?;
+            let doc = serde_json::from_str::<T>(&doc)?;
+            docs.push(doc);
+        };
+        Ok(Some(Page::new(docs, total, cursor)))
+    }
+    /// Uses term search, hits are ordered by a numeric fast field
+    fn multiple_structs_sorted<T: Serialize + DeserializeOwned>(&mut self, index_name: &str, conditions: &[OrCondition], sort: &Sort, paging: &Paging, score: Option<f32>) -> Result<Option<Page<T>>, IndexError> {
+        self._prepare_index_reader(index_name)?;
+        let reader = self.readers.get(index_name).unwrap().as_ref().unwrap();
+        let searcher = reader.searcher();
+        let cutoff = self._resolve_score(score);
+        let schema = self._resolve_surfer_schema(index_name)?;
+        let field = sort.resolve_field(schema, schema.resolve_mapping())?;
+        let expression = Expression::from(conditions);
+        let (query, cutoff) = self._compile_expression(&searcher, schema, &expression, cutoff)?;
+        let collector = (Count, TopDocsByField::new(field, sort, paging, paging.resolve_position(&searcher, true)?));
+        let (total, top_docs) = searcher.search(&query, &AboveScore::new(cutoff, collector))?;
+        let cursor = self._resolve_next_keyed_cursor(&searcher, &top_docs, paging);
+
+        let mut docs = Vec::with_capacity(top_docs.len());
+        for (_, _, doc_address) in top_docs {
+            let doc = self._read_document(index_name, &searcher, doc_address)?;
+            let doc = self.jsonify(index_name, &doc)?;
+            let doc = serde_json::from_str::<T>(&doc)?;
+            docs.push(doc);
+        };
+        Ok(Some(Page::new(docs, total, cursor)))
+    }
+    /// Reads a page across several indexes, merged by score with a global offset & limit
+    ///
+    /// Scores are computed per index, so they only compare well across indexes of similar content.
+    /// The total counts the hits above the cutoff in every index. None when any of the indexes is missing.
+    pub fn read_federated_structs<T: Serialize + DeserializeOwned>(&mut self, names: &[String], query: &str, paging: &Paging, score: Option<f32>) -> Result<Option<Page<FederatedHit<T>>>, IndexError> {
+        validate_federation(names, paging)?;
+        if self._prepare_federated_readers(names).is_err() {
+            return Ok(None);
+        };
+        let depth = std::cmp::max(paging.resolve_offset() + paging.resolve_limit(), 1);
+        let cutoff = score.unwrap_or(f32::NEG_INFINITY);
+        let page = self._read_federated(names, paging, |position, name, searcher| {
+            let query = self._build_full_text_query(name, query)?;
+            let collector = AboveScore::new(cutoff, (Count, TopDocs::with_limit(depth)));
+            let (total, top_docs) = searcher.search(&query, &collector)?;
+            let candidates = top_docs
+                .into_iter()
+                .map(|(doc_score, address)| Candidate::scored(position, doc_score, address))
+                .collect();
+            Ok((total, candidates))
+        })?;
+        Ok(Some(page))
+    }
+    /// Reads a page across several indexes, merged by a numeric fast field of the same type in each
+    ///
+    /// Hits below the score cutoff are left out. None when any of the indexes is missing.
+    pub fn read_federated_sorted_structs<T: Serialize + DeserializeOwned>(&mut self, names: &[String], query: &str, sort: &Sort, paging: &Paging, score: Option<f32>) -> Result<Option<Page<FederatedHit<T>>>, IndexError> {
+        validate_federation(names, paging)?;
+        if self._prepare_federated_readers(names).is_err() {
+            return Ok(None);
+        };
+        let fields = self._resolve_federated_sort_fields(names, sort)?;
+        let depth = Paging::new(paging.resolve_offset() + paging.resolve_limit(), 0);
+        let cutoff = score.unwrap_or(f32::NEG_INFINITY);
+        let page = self._read_federated(names, paging, |position, name, searcher| {
+            let query = self._build_full_text_query(name, query)?;
+            let collector = AboveScore::new(cutoff, (Count, TopDocsByField::new(fields[position], sort, &depth, None)));
+            let (total, top_docs) = searcher.search(&query, &collector)?;
+            let candidates = top_docs
+                .into_iter()
+                .map(|(key, tie_score, address)| Candidate::keyed(position, key, tie_score, address))
+                .collect();
+            Ok((total, candidates))
+        })?;
+        Ok(Some(page))
+    }
+    /// Uses term search across several indexes, merged by score
+    fn multiple_structs_federated<T: Serialize + DeserializeOwned>(&mut self, names: &[String], conditions: &[OrCondition], paging: &Paging, score: Option<f32>) -> Result<Page<FederatedHit<T>>, IndexError> {
+        validate_federation(names, paging)?;
+        self._prepare_federated_readers(names)?;
+        let cutoff = self._resolve_score(score);
+        let expression = Expression::from(conditions);
+        let depth = std::cmp::max(paging.resolve_offset() + paging.resolve_limit(), 1);
+        self._read_federated(names, paging, |position, name, searcher| {
+            let schema = self._resolve_surfer_schema(name)?;
+            let (query, cutoff) = self._compile_expression(searcher, schema, &expression, cutoff)?;
+            let collector = AboveScore::new(cutoff, (Count, TopDocs::with_limit(depth)));
+            let (total, top_docs) = searcher.search(&query, &collector)?;
+            let candidates = top_docs
+                .into_iter()
+                .map(|(doc_score, address)| Candidate::scored(position, doc_score, address))
+                .collect();
+            Ok((total, candidates))
+        })
+    }
+    /// Uses term search across several indexes, merged by a numeric fast field
+    fn multiple_structs_federated_sorted<T: Serialize + DeserializeOwned>(&mut self, names: &[String], conditions: &[OrCondition], sort: &Sort, paging: &Paging, score: Option<f32>) -> Result<Page<FederatedHit<T>>, IndexError> {
+        validate_federation(names, paging)?;
+        self._prepare_federated_readers(names)?;
+        let fields = self._resolve_federated_sort_fields(names, sort)?;
+        let cutoff = self._resolve_score(score);
+        let expression = Expression::from(conditions);
+        let depth = Paging::new(paging.resolve_offset() + paging.resolve_limit(), 0);
+        self._read_federated(names, paging, |position, name, searcher| {
+            let schema = self._resolve_surfer_schema(name)?;
+            let (query, cutoff) = self._compile_expression(searcher, schema, &expression, cutoff)?;
+            let collector = AboveScore::new(cutoff, (Count, TopDocsByField::new(fields[position], sort, &depth, None)));
+            let (total, top_docs) = searcher.search(&query, &collector)?;
+            let candidates = top_docs
+                .into_iter()
+                .map(|(key, tie_score, address)| Candidate::keyed(position, key, tie_score, address))
+                .collect();
+            Ok((total, candidates))
+        })
+    }
+    fn _prepare_federated_readers(&mut self, names: &[String]) -> Result<(), IndexError> {
+        for name in names {
+            self._prepare_index_reader(name)?;
+        };
+        Ok(())
+    }
+    /// Sort field of each index, keys only compare when the field type is the same everywhere
+    fn _resolve_federated_sort_fields(&self, names: &[String], sort: &Sort) -> Result<Vec<Field>, IndexError> {
+        let mut fields = Vec::with_capacity(names.len());
+        let mut field_type = None;
+        for name in names {
+            let schema = self._resolve_surfer_schema(name)?;
+            fields.push(sort.resolve_field(schema, schema.resolve_mapping())?);
+            let current = schema.resolve_mapping().get(sort.resolve_field_name());
+            if field_type.is_some() && field_type != current {
+                let message = format!("Unable to sort by: {}", sort.resolve_field_name());
+                let reason = format!("Field type differs in index: {}", name);
+                return Err(IndexError::new(message, reason));
+            };
+            field_type = current;
+        };
+        Ok(fields)
+    }
+    /// Searches every index, merges the hits & reads the documents of the page
+    fn _read_federated<T, F>(&self, names: &[String], paging: &Paging, search: F) -> Result<Page<FederatedHit<T>>, IndexError>
+        where T: Serialize + DeserializeOwned,
+              F: Fn(usize, &str, &Searcher) -> Result<(usize, Vec<Candidate>), IndexError> {
+        let mut searchers = Vec::with_capacity(names.len());
+        let mut candidates = Vec::new();
+        let mut total = 0;
+        for (position, name) in names.iter().enumerate() {
+            let searcher = self.readers.get(name).unwrap().as_ref().unwrap().searcher();
+            let (matched, hits) = search(position, name, &searcher)?;
+            total += matched;
+            candidates.extend(hits);
+            searchers.push(searcher);
+        };
+
+        let candidates = merge_candidates(candidates, paging);
+        let mut hits = Vec::with_capacity(candidates.len());
+        for candidate in candidates {
+            let name = &names[candidate.resolve_position()];
+            let searcher = &searchers[candidate.resolve_position()];
+            let doc = self._read_document(name, searcher, candidate.resolve_address())?;
+            let doc = self.jsonify(name, &doc)?;
+            let doc = serde_json::from_str::<T>(&doc)?;
+            let reference = SegmentDocRef::from_address(searcher, candidate.resolve_address());
+            hits.push(FederatedHit::new(name.clone(), candidate.resolve_score(), reference, doc));
+        };
+        Ok(Page::new(hits, total, None))
+    }
+    /// Cursor of the last sorted hit when the page is full
+    fn _resolve_next_keyed_cursor(&self, searcher: &Searcher, top_docs: &[(u64, f32, DocAddress)], paging: &Paging) -> Option<Cursor> {
+        if top_docs.is_empty() || top_docs.len() < paging.resolve_limit() {
+            return None;
+        };
+        top_docs.last().map(|(key, tie_score, address)| Cursor::keyed_with_score(*key, *tie_score, SegmentDocRef::from_address(searcher, *address)))
+    }
+    /// Ranked hits of the page & total hits of the query, both above the score cutoff when given
+    fn _search_page(&self, searcher: &Searcher, query: &dyn Query, paging: &Paging, score: Option<f32>) -> Result<(Vec<(f32, DocAddress)>, usize), IndexError> {
+        let cutoff = score.unwrap_or(f32::NEG_INFINITY);
+        let limit = paging.resolve_limit();
+        if limit == 0 {
+            let total = searcher.search(query, &AboveScore::new(cutoff, Count))?;
+            return Ok((Vec::new(), total));
+        };
+        let (total, top_docs) = match paging.resolve_position(searcher, false)? {
+            Some(position) => searcher.search(query, &AboveScore::new(cutoff, (Count, TopDocsAfter::new(limit, position))))?,
+            None => searcher.search(query, &AboveScore::new(cutoff, (Count, TopDocs::with_limit(limit).and_offset(paging.resolve_offset()))))?,
+        };
+        Ok((top_docs, total))
+    }
+    /// Cursor of the last hit when the page is full
+    fn _resolve_next_cursor(&self, searcher: &Searcher, top_docs: &[(f32, DocAddress)], paging: &Paging) -> Option<Cursor> {
+        if top_docs.is_empty() || top_docs.len() < paging.resolve_limit() {
+            return None;
+        };
+        top_docs.last().map(|(score, address)| Cursor::new(*score, SegmentDocRef::from_address(searcher, *address)))
+    }
+    /// Parses query over all the fields
+    fn _build_all_fields_query(&self, name: &str, query: &str) -> Result<Box<dyn Query>, IndexError> {
+        let index = self.indexes.get(name).unwrap();
+        let default_fields = self.fields.get(name).unwrap().clone();
+        let query_parser = QueryParser::for_index(index, default_fields);
+        let query = query_parser.parse_query(query)?;
+        Ok(query)
+    }
+    /// Parses query over the text fields
+    fn _build_full_text_query(&self, name: &str, query: &str) -> Result<Box<dyn Query>, IndexError> {
+        let index = self.indexes.get(name).unwrap();
+        let surfer_schema = self._resolve_surfer_schema(name)?;
+        self._build_boosted_query(index, surfer_schema, query)
+    }
+    /// Parses query over the default fields of the schema, boosts applied per field
+    fn _build_boosted_query(&self, index: &Index, surfer_schema: &SurferSchema, query: &str) -> Result<Box<dyn Query>, IndexError> {
+        let mappings = surfer_schema.resolve_mapping();
+        let default_fields = surfer_schema.resolve_default_fields();
+
+        let mut fields = Vec::<Field>::with_capacity(mappings.len());
+        for (f, fe) in surfer_schema.schema.fields() {
+            let name = fe.name();
+            if !mappings.contains_key(name) {
+                continue;
+            };
+            if default_fields.is_some_and(|default_fields| !default_fields.iter().any(|field_name| field_name == name)) {
+                continue;
+            };
+            let ft = mappings.get(name).unwrap();
+            if ft.is_text() {
+                fields.push(f);
+            }
+        };
+
+        let mut query_parser = QueryParser::for_index(index, fields);
+        for (field_name, boost) in surfer_schema.resolve_field_boosts() {
+            if let Some(field) = surfer_schema.get_field(field_name) {
+                query_parser.set_field_boost(field, *boost);
+            };
+        };
+        let query = query_parser.parse_query(query)?;
+        Ok(query)
+    }
+    /// Ranked hits of the expression, limit & cutoff apply to the combined score
+    fn _search_expression(&self, searcher: &Searcher, schema: &SurferSchema, expression: &Expression, limit: usize, cutoff: f32) -> Result<Vec<(f32, DocAddress)>, IndexError> {
+        let (top_docs, _) = self._search_expression_with_dropped(searcher, schema, expression, limit, cutoff)?;
+        Ok(top_docs)
+    }
+    /// Hits kept & hits below the cutoff
+    fn _search_expression_with_dropped(&self, searcher: &Searcher, schema: &SurferSchema, expression: &Expression, limit: usize, cutoff: f32) -> Result<(ScoredAddresses, ScoredAddresses), IndexError> {
+        let (query, cutoff) = self._compile_expression(searcher, schema, expression, cutoff)?;
+        let top_docs = searcher
+            .search(&query, &TopDocs::with_limit(std::cmp::max(limit, 1)))
+            .map_err(|e| {
+                let message = "Error while boolean query".to_string();
+                let reason = e.to_string();
+                IndexError::new(message, reason)
+            })?;
+        let partitioned = top_docs
+            .into_iter()
+            .partition(|(score, _)| *score >= cutoff);
+        Ok(partitioned)
+    }
+    /// Query of the expression along with the cutoff that applies to it
+    fn _compile_expression(&self, searcher: &Searcher, schema: &SurferSchema, expression: &Expression, cutoff: f32) -> Result<(Box<dyn Query>, f32), IndexError> {
+        let query = expression.compile(&|condition: &AndCondition| self._build_condition_query(searcher.index(), schema, condition))?;
+        // Range matches & match-all carry a constant score, nothing to cut off
+        let cutoff = if expression.is_scored(&|condition: &AndCondition| self._is_filter_condition(schema, condition)) {
+            cutoff
+        } else {
+            0f32
+        };
+        Ok((query, cutoff))
+    }
+    /// Term matching no live document outside the addresses, rarest first
+    fn _resolve_exclusive_term(&self, index: &Index, searcher: &Searcher, document: &Document, addresses: &HashSet<SurferDocAddress>) -> Result<Option<Term>, IndexError> {
+        let schema = index.schema();
+        let mut terms = Vec::<Term>::new();
+        for field_value in document.field_values() {
+            let field = field_value.field();
+            if !schema.get_field_entry(field).is_indexed() {
+                continue;
+            };
+            match field_value.value() {
+                SchemaValue::Str(text) => {
+                    let tokenizer = index.tokenizer_for_field(field)?;
+                    let mut stream = tokenizer.token_stream(text);
+                    stream.process(&mut |token| terms.push(Term::from_field_text(field, &token.text)));
+                }
+                SchemaValue::U64(value) => terms.push(Term::from_field_u64(field, *value)),
+                SchemaValue::I64(value) => terms.push(Term::from_field_i64(field, *value)),
+                SchemaValue::F64(value) => terms.push(Term::from_field_f64(field, *value)),
+                _ => {}
+            };
+        };
+        terms.sort_by_key(|term| searcher.doc_freq(term));
+        terms.dedup();
+        // One more than the addresses is enough to tell a term is shared
+        let limit = addresses.len() + 1;
+        for term in terms {
+            let query = self._build_term_query(term.clone(), None)?;
+            let top_docs = searcher.search(&query, &TopDocs::with_limit(limit))?;
+            let exclusive = top_docs
+                .iter()
+                .all(|(_, address)| addresses.contains(&SurferDocAddress::from(*address)));
+            if exclusive {
+                return Ok(Some(term));
+            };
+        };
+        Ok(None)
+    }
+    /// Terms deleting exactly the addresses on indexes without the hidden id
+    ///
+    /// Tantivy can only delete by term, so each document is deleted through a term no other
+    /// document has. Nothing is deleted when one of the documents has no such term.
+    fn _resolve_exclusive_terms(&self, index_name: &str, searcher: &Searcher, addresses: &HashSet<SurferDocAddress>) -> Result<Vec<Term>, IndexError> {
+        let index = self.indexes.get(index_name).unwrap();
+        let mut terms = HashSet::<Term>::with_capacity(addresses.len());
+        for address in addresses {
+            let document = searcher.doc(address.0)?;
+            let term = self._resolve_exclusive_term(index, searcher, &document, addresses)?;
+            if term.is_none() {
+                let message = format!("Unable to delete from: {}", index_name);
+                let reason = format!("Every term of {} is shared with a document left out of the match", address);
+                return Err(IndexError::new(message, reason));
+            };
+            terms.insert(term.unwrap());
+        };
+        Ok(terms.into_iter().collect())
+    }
+    /// Deletes documents by address through their hidden id, or by terms when the index has none
+    fn _delete_addresses(&mut self, index_name: &str, searcher: &Searcher, addresses: &HashSet<SurferDocAddress>) -> Result<usize, IndexError> {
+        if addresses.is_empty() {
+            return Ok(0);
+        };
+        let id_field = self._resolve_surfer_schema(index_name)?.resolve_id_field();
+        let terms = match id_field {
+            Some(id_field) => self._resolve_id_terms(index_name, searcher, id_field, addresses)?,
+            None => self._resolve_exclusive_terms(index_name, searcher, addresses)?,
+        };
+
+        self._prepare_index_writer(index_name)?;
+        let writer = self.writers.get_mut(index_name).unwrap().as_mut().unwrap();
+        for term in terms {
+            let _ = writer.delete_term(term);
+        };
+        self._commit_documents(index_name, Vec::new())?;
+        Ok(addresses.len())
+    }
+    /// Id terms of the addresses, read from the fast field
+    fn _resolve_id_terms(&self, index_name: &str, searcher: &Searcher, id_field: Field, addresses: &HashSet<SurferDocAddress>) -> Result<Vec<Term>, IndexError> {
+        let mut readers = HashMap::<u32, FastFieldReader<u64>>::new();
+        let mut terms = Vec::<Term>::with_capacity(addresses.len());
+        for address in addresses {
+            let DocAddress(segment, doc) = address.0;
+            let reader = match readers.entry(segment) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    let reader = searcher.segment_reader(segment).fast_fields().u64(id_field).ok_or_else(|| {
+                        let message = format!("Unable to delete from: {}", index_name);
+                        let reason = format!("Missing id for {}", address);
+                        IndexError::new(message, reason)
+                    })?;
+                    entry.insert(reader)
+                }
+            };
+            let id = reader.get(doc);
+            terms.push(Term::from_field_u64(id_field, id));
+        };
+        Ok(terms)
+    }
+    /// Uses full text search, returns number of documents removed
+    pub fn delete_by_query(&mut self, index_name: &str, query: &str) -> Result<usize, IndexError> {
+        self._prepare_index_reader(index_name)?;
+        let searcher = self.readers.get(index_name).unwrap().as_ref().unwrap().searcher();
+        let query = self._build_full_text_query(index_name, query)?;
+        let limit = std::cmp::max(searcher.num_docs() as usize, 1);
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(limit))?;
+        let addresses: HashSet<SurferDocAddress> = top_docs
+            .into_iter()
+            .map(|(_, address)| SurferDocAddress::from(address))
+            .collect();
+        self._delete_addresses(index_name, &searcher, &addresses)
+    }
+    /// Uses term search, returns number of documents removed
+    pub fn delete_by_conditions(&mut self, index_name: &str, conditions: &[OrCondition]) -> Result<usize, IndexError> {
+        self._prepare_index_reader(index_name)?;
+        let searcher = self.readers.get(index_name).unwrap().as_ref().unwrap().searcher();
+        let schema = self._resolve_surfer_schema(index_name)?;
+        let limit = std::cmp::max(searcher.num_docs() as usize, 1);
+        let expression = Expression::from(conditions);
+        let addresses: HashSet<SurferDocAddress> = self
+            ._search_expression(&searcher, schema, &expression, limit, 0f32)?
+            .into_iter()
+            .map(|(_, address)| SurferDocAddress::from(address))
+            .collect();
+        self._delete_addresses(index_name, &searcher, &addresses)
+    }
+}
+
+/// Panics if somethings goes wrong
+impl Surfer {
+    pub fn new(builder: SurferBuilder) -> Self {
+        Surfer::try_from(builder).unwrap()
+    }
+}
+
+/// Opens mmap dir
+fn initialize_mmap(name: &str, home: &str, schema: &Schema) -> Result<Index, IndexError> {
+    let path = resolve_index_directory_path(name, Some(home))?;
+    if path.exists() {
+        let dir = open_mmap_directory(path)?;
+        open_index(dir, None)
+    } else {
+        let dir = open_mmap_directory(path)?;
+        open_index(dir, Some(schema))
+    }
+}
+
+/// Get home location
+fn extract_home(builder: &SurferBuilder) -> Result<String, IndexError> {
+    let home = builder.home.as_ref();
+    let home = resolve_home(home)?;
+    Ok(home.to_str().unwrap().to_string())
+}
+
+/// Setup indexes
+fn initialized_index(home: &str, builder: &SurferBuilder) -> Result<HashMap<String, Index>, IndexError> {
+    let schemas = &builder.schemas;
+    let mut indexes = HashMap::<String, Index>::with_capacity(schemas.len());
+    for (name, schema) in schemas {
+        let index = initialize_mmap(name, home, schema)?;
+        indexes.insert(name.to_string(), index);
+    };
+    Ok(indexes)
+}
+
+/// Extract field information
+fn extract_fields(builder: &SurferBuilder) -> HashMap<String, Vec<Field>> {
+    let data = &builder.schemas;
+    let mut fields = HashMap::<String, Vec<Field>>::with_capacity(data.len());
+    for (data, schema) in data {
+        let key = data.clone();
+        let value = extract_schema_fields(schema);
+        fields.insert(key, value);
+    };
+    fields
+}
+
+/// Extract field information for one schema, the hidden id is left out
+fn extract_schema_fields(schema: &Schema) -> Vec<Field> {
+    schema
+        .fields()
+        .filter(|(_, entry)| entry.name() != ID_FIELD)
+        .map(|(f, _)| f)
+        .collect()
+}
+
+
+impl TryFrom<SurferBuilder> for Surfer {
+    type Error = IndexError;
+    fn try_from(builder: SurferBuilder) -> Result<Self, Self::Error> {
+        let home = extract_home(&builder)?;
+        let indexes = initialized_index(&home, &builder)?;
+        let fields = extract_fields(&builder);
+
+        let mut readers = HashMap::new();
+        let mut writers = HashMap::new();
+        for name in builder.schemas.keys() {
+            let reader: Option<IndexReader> = None;
+            let writer: Option<IndexWriter> = None;
+            writers.insert(name.to_string(), writer);
+            readers.insert(name.to_string(), reader);
+        };
+        let schemas = builder
+            .resolve_schemas()
+            .iter()
+            .map(|(name, schema)| (name.clone(), schema.aligned_with(&indexes.get(name).unwrap().schema())))
+            .collect();
+        Ok(Surfer {
+            home,
+            indexes,
+            fields,
+            readers,
+            writers,
+            schemas,
+            spellers: HashMap::new(),
+            index_spellers: HashMap::new(),
+            ids: HashMap::new(),
+        })
+    }
+}
+
+struct SurferDocAddress(DocAddress);
+
+impl SurferDocAddress {
+    fn new(address: DocAddress) -> Self {
+        Self(address)
+    }
+}
+
+impl From<DocAddress> for SurferDocAddress {
+    fn from(address: DocAddress) -> Self {
+        Self::new(address)
+    }
+}
+
+
+impl Deref for SurferDocAddress {
+    type Target = DocAddress;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for SurferDocAddress {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl Display for SurferDocAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let address = &self.0;
+        write!(f, "SegmentLocalId = {}, DocId {})", address.0, address.1)
+    }
+}
+
+impl Debug for SurferDocAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let address = &self.0;
+        write!(f, "SegmentLocalId = {}, DocId {})", address.0, address.1)
+    }
+}
+
+
+impl PartialEq for SurferDocAddress {
+    fn eq(&self, other: &SurferDocAddress) -> bool {
+        let doc_address = &self.0;
+        let other_doc_address = other.0;
+        doc_address.0 == other_doc_address.0 && doc_address.1 == other_doc_address.1
+    }
+}
+
+impl Hash for SurferDocAddress {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let address = self.0;
+        state.write_u32(address.0);
+        state.write_u32(address.1);
+    }
+}
+
+impl Eq for SurferDocAddress {}
+
+/// Document left out of a batch insert
+#[derive(Debug, Clone, Serialize)]
+pub struct RejectedDocument {
+    position: usize,
+    error: IndexError,
+}
+
+impl RejectedDocument {
+    pub fn new(position: usize, error: IndexError) -> Self {
+        Self {
+            position,
+            error,
+        }
+    }
+    /// Index of the document within the batch
+    pub fn resolve_position(&self) -> usize {
+        self.position
+    }
+    /// Why the document was rejected
+    pub fn resolve_error(&self) -> &IndexError {
+        &self.error
+    }
+}
+
+/// Container to pass through config to tantivy
+pub enum Control {
+    ControlTextOptions(TextOptions),
+    ControlIntOptions(IntOptions),
+}
+
+
+#[cfg(test)]
+mod library_tests {
+    use super::*;
+    use super::super::utils;
+    use serde::{Serialize, Deserialize};
+    use std::fmt::Debug;
+    use std::path::Path;
+    use std::fs::remove_dir_all;
+    use std::cmp::{Ord, Ordering, Eq};
+    use std::collections::HashSet;
+    use std::iter::FromIterator;
+    use std::hash::{Hash, Hasher};
+
+
+    #[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+    struct Giant {
+        a: String,
+        c: u64,
+        d: u32,
+        e: u16,
+        f: u8,
+        g: i64,
+        h: i32,
+        i: i16,
+        j: i8,
+        k: f64,
+        l: f32,
+
+    }
+
+    impl Default for Giant {
+        fn default() -> Self {
+            let a: String = "tag1".to_string();
+            let c: u64 = 10000;
+            let d: u32 = 1000;
+            let e: u16 = 100;
+            let f: u8 = 10;
+            let g: i64 = 20000;
+            let h: i32 = 2000;
+            let i: i16 = 200;
+            let j: i8 = 20;
+            let k: f64 = 10.0;
+            let l: f32 = 1.0;
+            Giant {
+                a,
+                c,
+                d,
+                e,
+                f,
+                g,
+                h,
+                i,
+                j,
+                k,
+                l,
+
+            }
+        }
+    }
+
+    #[test]
+    fn validate_giant() {
+        let name = random_string(None);
+        let home = ".validate_giant";
+        let index_path = format!("{}/{}", home, name);
+        let path = Path::new(&index_path);
+        assert!(!path.exists());
+
+        let giant = Giant::default();
+
+        let mut builder = SurferBuilder::default();
+        builder.set_home(home);
+        builder.add_struct("giant".to_string(), &giant);
+        let mut surfer = Surfer::try_from(builder).unwrap();
+        let data = vec![Giant::default()];
+        let computed = surfer.insert_structs("giant", &data);
+        assert!(computed.is_ok());
+        let a: String = "tag1".to_string();
+        let c: u64 = 10000;
+        let d: u32 = 1000;
+        let e: u16 = 100;
+        let f: u8 = 10;
+        let g: i64 = 20000;
+        let h: i32 = 2000;
+        let i: i16 = 200;
+        let j: i8 = 20;
+        let k: f64 = 10.0;
+        let l: f32 = 1.0;
+
+        let conditions = vec![
+            AndCondition::new("a".to_string(), a.to_string()),
+            AndCondition::new("c".to_string(), format!("{}", c)),
+            AndCondition::new("d".to_string(), format!("{}", d)),
+            AndCondition::new("e".to_string(), format!("{}", e)),
+            AndCondition::new("g".to_string(), format!("{}", g)),
+            AndCondition::new("f".to_string(), format!("{}", f)),
+            AndCondition::new("h".to_string(), format!("{}", h)),
+            AndCondition::new("i".to_string(), format!("{}", i)),
+            AndCondition::new("j".to_string(), format!("{}", j)),
+            AndCondition::new("k".to_string(), format!("{}", k)),
+            AndCondition::new("l".to_string(), format!("{}", l)),
+        ];
+        let conditions = vec![OrCondition::new(conditions)];
+        let computed = surfer.multiple_structs_by_field::<Giant>("giant", &conditions, None, Some(0.0));
+        assert!(computed.is_ok());
+        let computed = computed.unwrap();
+        assert!(computed.is_some());
+        let computed = computed.unwrap();
+        assert_eq!(computed, vec![Giant::default()]);
+
+        let computed = surfer.multiple_structs_by_field::<Giant>("giant", &conditions, None, None);
+        assert!(computed.is_ok());
+
+
+        let computed = surfer.delete_structs_by_field("giant", "c", "crap");
+        assert!(computed.is_err());
+
+        let computed = surfer.delete_structs_by_field("giant", "c", "crap");
+        assert!(computed.is_err());
+
+        let computed = surfer.delete_structs_by_field("giant", "g", "crap");
+        assert!(computed.is_err());
+
+        let computed = surfer.delete_structs_by_field("giant", "k", "crap");
+        assert!(computed.is_err());
+
+        let computed = surfer.delete_structs_by_field("giant", "crap", "crap");
+        assert!(computed.is_err());
+
+        let computed = surfer.delete_structs_by_field("crap", "crap", "crap");
+        assert!(computed.is_err());
+
+        // Clean-up, writers hold a lock file until dropped
+        drop(surfer);
+        let _ = remove_dir_all(index_path);
+        let _ = remove_dir_all(home);
+    }
+
+
+    #[derive(Clone, Serialize, Debug, Deserialize, PartialEq)]
+    struct OldMan {
+        title: String,
+        body: String,
+    }
+
+    impl Default for OldMan {
+        fn default() -> Self {
+            let title = "".to_string();
+            let body = "".to_string();
+            Self {
+                title,
+                body,
+            }
+        }
+    }
+
+    #[derive(Clone, Serialize, Debug, Deserialize, PartialEq, Default)]
+    struct Visit {
+        page: String,
+        load: f64,
+        at: i64,
+    }
+
+    /// Visits with the given numeric fields as fast fields
+    fn visit_control(fast_fields: &[&str]) -> HashMap<String, Control> {
+        let mut control = HashMap::new();
+        for field_name in fast_fields {
+            let options = IntOptions::default()
+                .set_indexed()
+                .set_stored()
+                .set_fast(tantivy::schema::Cardinality::SingleValue);
+            control.insert(field_name.to_string(), Control::ControlIntOptions(options));
+        };
+        control
+    }
+
+    #[test]
+    fn validate_read_existing_documents_as_structs() {
+        let name = random_string(None);
+        let home = ".validate_read_existing_documents_as_structs";
+        let index_path = format!("{}/{}", home, name);
+        let path = Path::new(&index_path);
+        assert!(!path.exists());
+
+        let data = OldMan::default();
+
+        let mut builder = SurferBuilder::default();
+        builder.set_home(home);
+        builder.add_struct(name.clone(), &data);
+
+        {
+            let title = "The Old Man and the Sea".to_string();
+            let body = "He was an old man who fished alone in a skiff in the Gulf Stream and he had gone eighty-four days now without taking a fish.".to_string();
+            let old_man_doc = OldMan {
+                title,
+                body,
+            };
+
+            let mut surfer = Surfer::new(builder.clone());
+            surfer.insert_struct(&name, &old_man_doc).unwrap();
+        }
+
+        let mut surfer = Surfer::new(builder.clone());
+        let query = "sea whale";
+        let result = surfer.read_structs::<OldMan>(&name, query, None, None);
+        assert!(result.is_ok());
+        assert!(path.exists());
+        let _ = remove_dir_all(index_path);
+        let _ = remove_dir_all(home);
+    }
+
+    #[test]
+    fn validate_read_existing_documents_as_strings() {
+        let title = "The Old Man and the Sea".to_string();
+        let body = "He was an old man who fished alone in a skiff in the Gulf Stream and he had gone eighty-four days now without taking a fish.".to_string();
+        let expected = OldMan {
+            title,
+            body,
+        };
+
+
+        let name = random_string(None);
+        let mut builder = SurferBuilder::default();
+        let data = OldMan::default();
+        let home = ".validate_read_existing_documents_as_strings";
+        let index_path = format!("{}/{}", home, name);
+        let path = Path::new(&index_path);
+        assert!(!path.exists());
+        builder.set_home(home);
+        builder.add_struct(name.to_string(), &data);
+
+        {
+            let title = "The Old Man and the Sea".to_string();
+            let body = "He was an old man who fished alone in a skiff in the Gulf Stream and he had gone eighty-four days now without taking a fish.".to_string();
+            let old_man_doc = OldMan {
+                title,
+                body,
+            };
+
+            let mut surfer = Surfer::new(builder.clone());
+            surfer.insert_struct(&name, &old_man_doc).unwrap();
+        }
+
+        let mut surfer = Surfer::new(builder.clone());
+        let query = "sea whale";
+        let result = surfer.read_string("Non-existent", query, None, None);
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert!(result.is_none());
+        let result = surfer.read_string(&name, query, None, None);
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert!(result.is_some());
+        let result = result.unwrap();
+        let mut computed = Vec::new();
+        for entry in result {
+            let data: serde_json::Result<OldMan> = serde_json::from_str(&entry);
+            let data = data.unwrap();
+            computed.push(data);
+        };
+        assert_eq!(computed, vec![expected.clone()]);
+
+        // Reading documents again
+        let result = surfer.read_string(&name, query, None, None);
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert!(result.is_some());
+        let result = result.unwrap();
+        let mut computed = Vec::new();
+        for entry in result {
+            let data: serde_json::Result<OldMan> = serde_json::from_str(&entry);
+            let data = data.unwrap();
+            computed.push(data);
+        };
+        assert_eq!(computed, vec![expected.clone()]);
+
+        let _ = remove_dir_all(&index_path);
+        let _ = remove_dir_all(home);
+    }
+
+    #[test]
+    fn validate_as_rust_structs() {
+        let name = random_string(None);
+        let home = ".validate_as_rust_structs".to_string();
+        let index_path = format!("{}/{}", home, name);
+        let path = Path::new(&index_path);
+        assert!(!path.exists());
+
+        let title = "The Old Man and the Sea".to_string();
+        let body = "He was an old man who fished alone in a skiff in the Gulf Stream and he had gone eighty-four days now without taking a fish.".to_string();
+        let old_man_doc = OldMan {
+            title,
+            body,
+        };
+
+
+        let mut builder = SurferBuilder::default();
+        builder.set_home(home.as_str());
+        builder.add_struct(name.to_string(), &old_man_doc);
+        let mut surfer = Surfer::new(builder);
+
+        surfer.insert_struct(&name, &old_man_doc).unwrap();
+        let query = "sea whale";
+
+        let result = surfer.read_structs::<OldMan>("non-existent", query, None, None);
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert!(result.is_none());
+
+        let result = surfer.read_structs::<OldMan>(&name, query, None, None).unwrap().unwrap();
+        for computed in result {
+            assert_eq!(computed, old_man_doc);
+        };
+        assert!(path.exists());
+
+        // Reading documents again
+
+        let result = surfer.read_structs::<OldMan>(&name, query, None, None).unwrap().unwrap();
+        for computed in result {
+            assert_eq!(computed, old_man_doc);
+        };
+
+
+        let _ = remove_dir_all(index_path);
+        let _ = remove_dir_all(home);
+    }
+
+    #[test]
+    fn validate_initialize_mmap() {
+        let home = ".validate_initialize_mmap";
+        let index_name = "someindex";
+        let path_to_index = ".validate_initialize_mmap/someindex";
+        let path = Path::new(path_to_index);
+        assert!(!path.exists());
+        let oldman = OldMan::default();
+        let (schema, mappings) = to_schema(&oldman, None).unwrap();
+        let schema = SurferSchema::new(schema, mappings, false, false);
+        let _ = initialize_mmap(index_name, home, &schema);
+        assert!(path.exists());
+        let _ = std::fs::remove_dir_all(path_to_index);
+
+        let _ = remove_dir_all(path_to_index);
+        let _ = remove_dir_all(index_name);
+        let _ = remove_dir_all(home);
+    }
+
+    #[test]
+    fn validate_read_existing_documents_as_structs_limit_one() {
+        let name = random_string(None);
+        let home = ".validate_read_existing_documents_as_structs_limit_one";
+        let index_path = format!("{}/{}", home, name);
+        let path = Path::new(&index_path);
+        assert!(!path.exists());
 
-impl TryFrom<SurferBuilder> for Surfer {
-    type Error = IndexError;
-    fn try_from(builder: SurferBuilder) -> Result<Self, Self::Error> {
-        let home = extract_home(&builder)?;
-        let indexes = initialized_index(&home, &builder)?;
-        let fields = extract_fields(&builder);
+        let data = OldMan::default();
 
-        let mut readers = HashMap::new();
-        let mut writers = HashMap::new();
-        for (name, _) in &builder.schemas {
-            let reader: Option<IndexReader> = None;
-            let writer: Option<IndexWriter> = None;
-            writers.insert(name.to_string(), writer);
-            readers.insert(name.to_string(), reader);
+        let mut builder = SurferBuilder::default();
+        builder.set_home(home);
+        builder.add_struct(name.clone(), &data);
+
+        let title = "The Old Man and the Sea".to_string();
+        let body = "He was an old man who fished alone in a skiff in the Gulf Stream and he had gone eighty-four days now without taking a fish.".to_string();
+        let old_man_doc = OldMan {
+            title,
+            body,
         };
-        let schemas = builder.resolve_schemas().clone();
-        Ok(Surfer {
-            home,
-            indexes,
-            fields,
-            readers,
-            writers,
-            schemas,
-        })
+
+        let mut surfer = Surfer::new(builder.clone());
+        surfer.insert_struct(&name, &old_man_doc).unwrap();
+        surfer.insert_struct(&name, &old_man_doc).unwrap();
+        surfer.insert_struct(&name, &old_man_doc).unwrap();
+        surfer.insert_struct(&name, &old_man_doc).unwrap();
+        surfer.insert_struct(&name, &old_man_doc).unwrap();
+
+        let query = "sea whale";
+        let result = surfer.read_structs::<OldMan>(&name, query, None, None);
+        assert!(result.is_ok());
+        let result = result.unwrap().unwrap();
+        assert_eq!(result.len(), 5);
+
+        let result = surfer.read_structs::<OldMan>(&name, query, Some(1), None);
+        assert!(result.is_ok());
+        let result = result.unwrap().unwrap();
+        assert_eq!(result.len(), 1);
+
+        assert!(path.exists());
+        let _ = remove_dir_all(index_path);
+        let _ = remove_dir_all(home);
     }
-}
 
-struct SurferDocAddress(DocAddress);
+    #[test]
+    fn validate_read_existing_documents_as_structs_default_ten() {
+        let name = random_string(None);
+        let home = ".validate_read_existing_documents_as_structs_default_ten";
+        let index_path = format!("{}/{}", home, name);
+        let path = Path::new(&index_path);
+        assert!(!path.exists());
+
+        let data = OldMan::default();
+
+        let mut builder = SurferBuilder::default();
+        builder.set_home(home);
+        builder.add_struct(name.clone(), &data);
+
+        let title = "The Old Man and the Sea".to_string();
+        let body = "He was an old man who fished alone in a skiff in the Gulf Stream and he had gone eighty-four days now without taking a fish.".to_string();
+        let old_man_doc = OldMan {
+            title,
+            body,
+        };
+
+        let mut surfer = Surfer::new(builder.clone());
+        for _ in 0..20 {
+            surfer.insert_struct(&name, &old_man_doc).unwrap();
+        }
+
+
+        let query = "sea whale";
+        let result = surfer.read_structs::<OldMan>(&name, query, None, None);
+        assert!(result.is_ok());
+        let result = result.unwrap().unwrap();
+        assert_eq!(result.len(), 10);
+
+        let result = surfer.read_structs::<OldMan>(&name, query, Some(20), None);
+        assert!(result.is_ok());
+        let result = result.unwrap().unwrap();
+        assert_eq!(result.len(), 20);
+
+        assert!(path.exists());
+        let _ = remove_dir_all(index_path);
+        let _ = remove_dir_all(home);
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Dummy {
+        x: String,
+        y: String,
+        z: u64,
+    }
+
+    #[test]
+    fn check_invalid_index_name() {
+        let home = random_string(None);
+        let data = Dummy {
+            x: "X".to_owned(),
+            y: "Y".to_owned(),
+            z: 100u64,
+        };
+        let mut builder = SurferBuilder::default();
+        builder.set_home(&home);
+        builder.add_struct("dummy".to_string(), &data);
+        let surfer = Surfer::try_from(builder).unwrap();
+        let computed = surfer.which_index("crap");
+        assert!(computed.is_none());
+        let home = Path::new(&home);
+        assert!(home.exists());
+        let index_path = surfer.which_index("dummy").unwrap();
+        let _ = remove_dir_all(&index_path);
+        let _ = remove_dir_all(home);
+    }
+
+    #[test]
+    fn check_invalid_index_insert() {
+        let home = random_string(None);
+        let data = Dummy {
+            x: "X".to_owned(),
+            y: "Y".to_owned(),
+            z: 100u64,
+        };
+        let mut builder = SurferBuilder::default();
+        builder.set_home(&home);
+        builder.add_struct("dummy".to_string(), &data);
+        let mut surfer = Surfer::try_from(builder).unwrap();
+        let data = vec![data];
+        let computed = surfer.insert_structs("crap", &data);
+        assert!(computed.is_ok());
+        let index_path = surfer.which_index("dummy").unwrap();
+        let _ = remove_dir_all(&index_path);
+        let _ = remove_dir_all(&home);
+    }
+
+    #[test]
+    fn check_invalid_index_lookup() {
+        let name = random_string(None);
+        let home = ".check_invalid_index_lookup";
+        let index_path = format!("{}/{}", home, name);
+        let path = Path::new(&index_path);
+        assert!(!path.exists());
+
+
+        let data = Dummy {
+            x: "X".to_owned(),
+            y: "Y".to_owned(),
+            z: 100u64,
+        };
+        let mut builder = SurferBuilder::default();
+        builder.set_home(home);
+        let mut surfer = Surfer::try_from(builder).unwrap();
+        surfer.insert_struct("dummy", &data).unwrap();
+        let computed = surfer.read_structs::<Dummy>("crap", "X", None, None);
+        assert!(computed.is_ok());
+        let computed = computed.unwrap();
+        assert!(computed.is_none());
+        let _ = remove_dir_all(path);
+        let _ = remove_dir_all(home);
+    }
+
+    #[test]
+    fn validate_surfer_schema() {
+        let data = Dummy {
+            x: "X".to_owned(),
+            y: "Y".to_owned(),
+            z: 100u64,
+        };
+
+        let data = utils::as_value(&data).unwrap();
+        let (schema, mappings) = utils::to_schema(&data, None).unwrap();
+        let surf_schema = SurferSchema::new(schema, mappings, false, false);
+
+        let mut computed1 = SurferBuilder::default();
+        computed1.add_schema("dummy".to_string(), surf_schema.clone());
+
+        let mut computed2 = SurferBuilder::default();
+        computed2.add_struct("dummy".to_string(), &data);
+
+        assert_eq!(computed1, computed2);
+
+        assert_eq!(format!("{:?}", computed1.schemas), format!("{:?}", computed2.schemas))
+    }
+
+    // Main struct
+    #[derive(Serialize, Debug, Deserialize, PartialEq, Clone)]
+    struct UserInfo {
+        first: String,
+        last: String,
+        age: u8,
+    }
+
+    impl UserInfo {
+        pub fn new(first: String, last: String, age: u8) -> Self {
+            Self {
+                first,
+                last,
+                age,
+            }
+        }
+    }
+
+    impl Default for UserInfo {
+        fn default() -> Self {
+            let first = "".to_string();
+            let last = "".to_string();
+            let age = 0u8;
+            UserInfo::new(first, last, age)
+        }
+    }
+
+    #[test]
+    fn test_user_info() {
+        // Specify home location for indexes
+        let home = ".test_user_info".to_string();
+        // Specify index name
+        let index_name = "store".to_string();
+
+        // Prepare builder
+        let mut builder = SurferBuilder::default();
+        builder.set_home(&home);
+
+        let data = UserInfo::default();
+        builder.add_struct(index_name.clone(), &data);
+
+        // Prepare Surfer
+        let mut surfer = Surfer::try_from(builder).unwrap();
+
+        // Prepare data to insert & search
+
+        // User 1: John Doe
+        let first = "John".to_string();
+        let last = "Doe".to_string();
+        let age = 20u8;
+        let john_doe = UserInfo::new(first, last, age);
+
+        // User 2: Jane Doe
+        let first = "Jane".to_string();
+        let last = "Doe".to_string();
+        let age = 18u8;
+        let jane_doe = UserInfo::new(first, last, age);
+
+        // User 3: Jonny Doe
+        let first = "Jonny".to_string();
+        let last = "Doe".to_string();
+        let age = 10u8;
+        let jonny_doe = UserInfo::new(first, last, age);
+
+        // User 4: Jinny Doe
+        let first = "Jinny".to_string();
+        let last = "Doe".to_string();
+        let age = 10u8;
+        let jinny_doe = UserInfo::new(first, last, age);
+
+        // Writing structs
 
-impl SurferDocAddress {
-    fn new(address: DocAddress) -> Self {
-        Self(address)
-    }
-}
+        // Option 1: One struct at a time
+        surfer.insert_struct(&index_name, &john_doe).unwrap();
+        surfer.insert_struct(&index_name, &jane_doe).unwrap();
 
-impl From<DocAddress> for SurferDocAddress {
-    fn from(address: DocAddress) -> Self {
-        Self::new(address)
-    }
-}
+        // Option 2: Write all structs together
+        let users = vec![jonny_doe.clone(), jinny_doe.clone()];
+        surfer.insert_structs(&index_name, &users).unwrap();
 
+        block_thread(1);
 
-impl Deref for SurferDocAddress {
-    type Target = DocAddress;
+        // Reading structs
 
-    fn deref(&self) -> &Self::Target {
-        &self.0
-    }
-}
+        // Option 1: Full text search
+        let expected = vec![john_doe.clone()];
+        let computed = surfer.read_all_structs::<UserInfo>(&index_name, "John").unwrap().unwrap();
+        assert_eq!(expected, computed);
 
-impl DerefMut for SurferDocAddress {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
-    }
-}
+        let mut expected = vec![john_doe.clone(), jane_doe.clone(), jonny_doe.clone(), jinny_doe.clone()];
+        expected.sort();
+        let mut computed = surfer.read_all_structs::<UserInfo>(&index_name, "doe").unwrap().unwrap();
+        computed.sort();
+        assert_eq!(expected, computed);
 
-impl Display for SurferDocAddress {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let address = &self.0;
-        write!(f, "SegmentLocalId = {}, DocId {})", address.0, address.1)
-    }
-}
+        // Option 2: Term search
+        let mut expected = vec![jonny_doe.clone(), jinny_doe.clone()];
+        expected.sort();
+        let mut computed = surfer.read_all_structs_by_field::<UserInfo>(&index_name, "age", "10").unwrap().unwrap();
+        computed.sort();
+        assert_eq!(expected, computed);
 
-impl Debug for SurferDocAddress {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let address = &self.0;
-        write!(f, "SegmentLocalId = {}, DocId {})", address.0, address.1)
-    }
-}
+        // Delete structs
 
+        // Option 1: Delete based on all text fields
+        // Before delete
+        let before = surfer.read_all_structs::<UserInfo>(&index_name, "doe").unwrap().unwrap();
+        let before: HashSet<UserInfo> = HashSet::from_iter(before);
 
-impl PartialEq for SurferDocAddress {
-    fn eq(&self, other: &SurferDocAddress) -> bool {
-        let doc_address = &self.0;
-        let other_doc_address = other.0;
-        doc_address.0 == other_doc_address.0 && doc_address.1 == other_doc_address.1
-    }
-}
+        // Delete any occurrence of John (Actual call to delete)
+        surfer.delete_structs(&index_name, "john").unwrap();
 
-impl Hash for SurferDocAddress {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        let address = self.0;
-        state.write_u32(address.0);
-        state.write_u32(address.1);
-        state.finish();
+        // After delete
+        let after = surfer.read_all_structs::<UserInfo>(&index_name, "doe").unwrap().unwrap();
+        let after: HashSet<UserInfo> = HashSet::from_iter(after);
+        // Check difference
+        let computed: Vec<UserInfo> = before.difference(&after).cloned().collect();
+        // Only John should be deleted
+        let expected = vec![john_doe];
+        assert_eq!(expected, computed);
+
+        // Option 2: Delete based on a specific field
+        // Before delete
+        let before = surfer.read_all_structs_by_field::<UserInfo>(&index_name, "age", "10").unwrap().unwrap();
+        let before: HashSet<UserInfo> = HashSet::from_iter(before);
+
+        // Delete any occurrence where age = 10 (Actual call to delete)
+        surfer.delete_structs_by_field(&index_name, "age", "10").unwrap();
+
+        // After delete
+        let after = surfer.read_all_structs_by_field::<UserInfo>(&index_name, "age", "10").unwrap().unwrap();
+        let after: HashSet<UserInfo> = HashSet::from_iter(after);
+        // Check difference
+        let mut computed: Vec<UserInfo> = before.difference(&after).cloned().collect();
+        computed.sort();
+        // Both Jonny & Jinny should be deleted
+        let mut expected = vec![jonny_doe, jinny_doe];
+        expected.sort();
+        assert_eq!(expected, computed);
+
+
+        // Clean-up
+        let path = surfer.which_index(&index_name).unwrap();
+        let _ = remove_dir_all(&path);
+        let _ = remove_dir_all(&home);
     }
-}
 
-impl Eq for SurferDocAddress {}
+    #[test]
+    fn test_where_clause() {
+        // Specify home location for indexes
+        let home = ".test_where_clause".to_string();
+        // Specify index name
+        let index_name = "store".to_string();
 
-/// Container to pass through config to tantivy
-pub enum Control {
-    ControlTextOptions(TextOptions),
-    ControlIntOptions(IntOptions),
-}
+        // Prepare builder
+        let mut builder = SurferBuilder::default();
+        builder.set_home(&home);
 
+        let data = UserInfo::default();
+        builder.add_struct(index_name.clone(), &data);
 
-#[cfg(test)]
-mod library_tests {
-    use super::*;
-    use super::super::utils;
-    use serde::{Serialize, Deserialize};
-    use std::fmt::Debug;
-    use std::path::Path;
-    use std::fs::remove_dir_all;
-    use std::cmp::{Ord, Ordering, Eq};
-    use std::collections::HashSet;
-    use std::iter::FromIterator;
-    use std::hash::{Hash, Hasher};
+        // Prepare Surfer
+        let surfer = Surfer::try_from(builder).unwrap();
+        let mut surf = Surf::from(surfer);
+        let surfer = &mut surf;
 
+        // Prepare data to insert & search
 
-    #[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
-    struct Giant {
-        a: String,
-        c: u64,
-        d: u32,
-        e: u16,
-        f: u8,
-        g: i64,
-        h: i32,
-        i: i16,
-        j: i8,
-        k: f64,
-        l: f32,
+        // User 1: John Doe
+        let first = "John".to_string();
+        let last = "Doe".to_string();
+        let age = 20u8;
+        let john_doe = UserInfo::new(first, last, age);
 
-    }
+        // User 2: Jane Doe
+        let first = "Jane".to_string();
+        let last = "Doe".to_string();
+        let age = 18u8;
+        let jane_doe = UserInfo::new(first, last, age);
 
-    impl Default for Giant {
-        fn default() -> Self {
-            let a: String = "tag1".to_string();
-            let c: u64 = 10000;
-            let d: u32 = 1000;
-            let e: u16 = 100;
-            let f: u8 = 10;
-            let g: i64 = 20000;
-            let h: i32 = 2000;
-            let i: i16 = 200;
-            let j: i8 = 20;
-            let k: f64 = 10.0;
-            let l: f32 = 1.0;
-            Giant {
-                a,
-                c,
-                d,
-                e,
-                f,
-                g,
-                h,
-                i,
-                j,
-                k,
-                l,
+        // User 3: Jonny Doe
+        let first = "Jonny".to_string();
+        let last = "Doe".to_string();
+        let age = 10u8;
+        let jonny_doe = UserInfo::new(first, last, age);
 
-            }
-        }
-    }
+        // User 4: Jinny Doe
+        let first = "Jinny".to_string();
+        let last = "Doe".to_string();
+        let age = 10u8;
+        let jinny_doe = UserInfo::new(first, last, age);
 
-    #[test]
-    fn validate_giant() {
-        let name = random_string(None);
-        let home = ".validate_giant";
-        let index_path = format!("{}/{}", home, name);
-        let path = Path::new(&index_path);
-        assert!(!path.exists());
+        // Writing structs
 
-        let giant = Giant::default();
+        let users = vec![john_doe.clone(), jane_doe.clone(), jonny_doe.clone(), jinny_doe.clone()];
+        surfer.insert_structs(&index_name, &users).unwrap();
+        block_thread(1);
 
-        let mut builder = SurferBuilder::default();
-        builder.set_home(home);
-        builder.add_struct("giant".to_string(), &giant);
-        let mut surfer = Surfer::try_from(builder).unwrap();
-        let data = vec![Giant::default()];
-        let computed = surfer.insert_structs("giant", &data);
-        assert!(computed.is_ok());
-        let a: String = "tag1".to_string();
-        let c: u64 = 10000;
-        let d: u32 = 1000;
-        let e: u16 = 100;
-        let f: u8 = 10;
-        let g: i64 = 20000;
-        let h: i32 = 2000;
-        let i: i16 = 200;
-        let j: i8 = 20;
-        let k: f64 = 10.0;
-        let l: f32 = 1.0;
+        let conditions = vec![OrCondition::from(("age".to_string(), "10".to_string()))];
+        let mut expected = vec![jonny_doe.clone(), jinny_doe.clone()];
+        let mut computed = surfer.select::<UserInfo>(&index_name, &conditions).unwrap().unwrap();
+        expected.sort();
+        computed.sort();
+        assert_eq!(expected, computed);
 
-        let conditions = vec![
-            AndCondition::new("a".to_string(), format!("{}", a)),
-            AndCondition::new("c".to_string(), format!("{}", c)),
-            AndCondition::new("d".to_string(), format!("{}", d)),
-            AndCondition::new("e".to_string(), format!("{}", e)),
-            AndCondition::new("g".to_string(), format!("{}", g)),
-            AndCondition::new("f".to_string(), format!("{}", f)),
-            AndCondition::new("h".to_string(), format!("{}", h)),
-            AndCondition::new("i".to_string(), format!("{}", i)),
-            AndCondition::new("j".to_string(), format!("{}", j)),
-            AndCondition::new("k".to_string(), format!("{}", k)),
-            AndCondition::new("l".to_string(), format!("{}", l)),
-        ];
-        let conditions = vec![OrCondition::new(conditions)];
-        let computed = surfer.multiple_structs_by_field::<Giant>("giant", &conditions, None, Some(0.0));
-        assert!(computed.is_ok());
-        let computed = computed.unwrap();
-        assert!(computed.is_some());
-        let computed = computed.unwrap();
-        assert_eq!(computed, vec![Giant::default()]);
+        let conditions = vec![OrCondition::from(("age".to_string(), "10".to_string())),
+                              OrCondition::from(("first".to_string(), "john".to_string()))];
+        let mut expected = vec![john_doe.clone(), jonny_doe.clone(), jinny_doe.clone()];
+        let mut computed = surfer.select::<UserInfo>(&index_name, &conditions).unwrap().unwrap();
+        expected.sort();
+        computed.sort();
+        assert_eq!(expected, computed);
 
-        let computed = surfer.multiple_structs_by_field::<Giant>("giant", &conditions, None, None);
-        assert!(computed.is_ok());
 
+        let name_condition = AndCondition::new("first".to_string(), "jinny".to_string());
+        let age_condition = AndCondition::new("age".to_string(), "10".to_string());
+        let and_conditions = vec![name_condition, age_condition];
+        let child_condition = OrCondition::new(and_conditions);
+        let parent_condition = OrCondition::from(("first".to_string(), "john".to_string()));
+        let conditions = vec![child_condition, parent_condition];
 
-        let computed = surfer.delete_structs_by_field("giant", "c", "crap");
-        assert!(computed.is_err());
+        let mut expected = vec![john_doe.clone(), jinny_doe.clone()];
+        let mut computed = surfer.select::<UserInfo>(&index_name, &conditions).unwrap().unwrap();
+        expected.sort();
+        computed.sort();
+        assert_eq!(expected, computed);
 
-        let computed = surfer.delete_structs_by_field("giant", "c", "crap");
-        assert!(computed.is_err());
+        // Clean-up
+        let path = surfer.which_index(&index_name).unwrap();
+        let _ = remove_dir_all(&path);
+        let _ = remove_dir_all(&home);
+    }
 
-        let computed = surfer.delete_structs_by_field("giant", "g", "crap");
-        assert!(computed.is_err());
+    #[test]
+    fn validate_batch_insert_with_invalid_documents() {
+        let home = ".validate_batch_insert_with_invalid_documents".to_string();
+        let index_name = "store".to_string();
 
-        let computed = surfer.delete_structs_by_field("giant", "k", "crap");
-        assert!(computed.is_err());
+        let mut builder = SurferBuilder::default();
+        builder.set_home(&home);
+        let data = UserInfo::default();
+        builder.add_struct(index_name.clone(), &data);
+        let mut surfer = Surfer::try_from(builder).unwrap();
 
-        let computed = surfer.delete_structs_by_field("giant", "crap", "crap");
-        assert!(computed.is_err());
+        let john_doe = serde_json::json!({"first": "John", "last": "Doe", "age": 20});
+        let bad_age = serde_json::json!({"first": "Jane", "last": "Doe", "age": "eighteen"});
+        let bad_field = serde_json::json!({"first": "Jonny", "last": "Doe", "height": 10});
+        let payload = vec![john_doe.clone(), bad_age, john_doe.clone(), bad_field];
 
-        let computed = surfer.delete_structs_by_field("crap", "crap", "crap");
+        // All or nothing: nothing is written
+        let computed = surfer.insert_structs(&index_name, &payload);
         assert!(computed.is_err());
+        let computed = surfer.read_all_structs::<UserInfo>(&index_name, "doe").unwrap().unwrap();
+        assert!(computed.is_empty());
+
+        // Partially: valid documents are written
+        let rejected = surfer.insert_structs_partially(&index_name, &payload).unwrap();
+        let positions: Vec<usize> = rejected.iter().map(|r| r.resolve_position()).collect();
+        assert_eq!(positions, vec![1, 3]);
+        for r in &rejected {
+            assert_eq!(r.resolve_error().message(), "Unable to parse document");
+        };
+        let computed = surfer.read_all_structs::<UserInfo>(&index_name, "doe").unwrap().unwrap();
+        let expected = UserInfo::new("John".to_string(), "Doe".to_string(), 20);
+        assert_eq!(computed, vec![expected.clone(), expected]);
 
-        let _ = remove_dir_all(index_path);
-        let _ = remove_dir_all(home);
+        // Missing index is a no-op, as with insert_struct(s)
+        let computed = surfer.insert_structs_partially("missing", &payload).unwrap();
+        assert!(computed.is_empty());
+
+        // Clean-up
+        let path = surfer.which_index(&index_name).unwrap();
+        let _ = remove_dir_all(&path);
+        let _ = remove_dir_all(&home);
     }
 
+    #[test]
+    fn validate_delete_by_query_and_conditions() {
+        let home = ".validate_delete_by_query_and_conditions".to_string();
+        let index_name = "store".to_string();
 
-    #[derive(Clone, Serialize, Debug, Deserialize, PartialEq)]
-    struct OldMan {
-        title: String,
-        body: String,
-    }
+        let mut builder = SurferBuilder::default();
+        builder.set_home(&home);
+        let data = UserInfo::default();
+        builder.add_struct(index_name.clone(), &data);
+        let mut surf = Surf::try_from(builder).unwrap();
+
+        let john_doe = UserInfo::new("John".to_string(), "Doe".to_string(), 20);
+        let jane_doe = UserInfo::new("Jane".to_string(), "Doe".to_string(), 18);
+        let jonny_doe = UserInfo::new("Jonny".to_string(), "Doe".to_string(), 10);
+        let jinny_doe = UserInfo::new("Jinny".to_string(), "Doe".to_string(), 10);
+        let jinny_roe = UserInfo::new("Jinny".to_string(), "Roe".to_string(), 12);
+        let users = vec![john_doe, jane_doe.clone(), jonny_doe.clone(), jinny_doe, jinny_roe.clone()];
+        surf.insert(&index_name, &users).unwrap();
+
+        // Full text
+        let computed = surf.delete_by_query(&index_name, "john").unwrap();
+        assert_eq!(computed, 1);
+        let computed = surf.read_all_structs::<UserInfo>(&index_name, "doe").unwrap().unwrap();
+        assert_eq!(computed.len(), 3);
+
+        // Jinny Doe shares every term with either Jonny Doe or Jinny Roe
+        let conditions = vec![OrCondition::new(vec![
+            AndCondition::new("first".to_string(), "jinny".to_string()),
+            AndCondition::new("age".to_string(), "10".to_string()),
+        ])];
+        let computed = surf.delete_where(&index_name, &conditions).unwrap();
+        assert_eq!(computed, 1);
+        let mut computed = surf.read_all_structs::<UserInfo>(&index_name, "doe roe").unwrap().unwrap();
+        computed.sort();
+        let mut expected = vec![jane_doe.clone(), jonny_doe.clone(), jinny_roe.clone()];
+        expected.sort();
+        assert_eq!(expected, computed);
 
-    impl Default for OldMan {
-        fn default() -> Self {
-            let title = "".to_string();
-            let body = "".to_string();
-            Self {
-                title,
-                body,
-            }
-        }
+        // Exact duplicates go together
+        surf.insert_struct(&index_name, &jane_doe).unwrap();
+        let conditions = vec![OrCondition::new(vec![
+            AndCondition::new("first".to_string(), "jane".to_string()),
+        ])];
+        let computed = surf.delete_where(&index_name, &conditions).unwrap();
+        assert_eq!(computed, 2);
+        let mut computed = surf.read_all_structs::<UserInfo>(&index_name, "doe roe").unwrap().unwrap();
+        computed.sort();
+        let mut expected = vec![jonny_doe, jinny_roe];
+        expected.sort();
+        assert_eq!(expected, computed);
+
+        // Nothing left to match
+        let computed = surf.delete_where(&index_name, &conditions).unwrap();
+        assert_eq!(computed, 0);
+
+        let computed = surf.delete_by_query("missing", "john");
+        assert!(computed.is_err());
+
+        // Clean-up
+        let path = surf.which_index(&index_name).unwrap();
+        let _ = remove_dir_all(&path);
+        let _ = remove_dir_all(&home);
     }
 
     #[test]
-    fn validate_read_existing_documents_as_structs() {
-        let name = random_string(None);
-        let home = ".validate_read_existing_documents_as_structs";
-        let index_path = format!("{}/{}", home, name);
-        let path = Path::new(&index_path);
-        assert!(!path.exists());
-
-        let data = OldMan::default();
+    fn validate_ids_increase_per_index() {
+        let home = ".validate_ids_increase_per_index".to_string();
+        let index_name = "ids".to_string();
+        let john_doe = UserInfo::new("John".to_string(), "Doe".to_string(), 20);
+        let jane_doe = UserInfo::new("Jane".to_string(), "Doe".to_string(), 18);
 
         let mut builder = SurferBuilder::default();
-        builder.set_home(home);
-        builder.add_struct(name.clone(), &data);
+        builder.set_home(&home);
+        builder.add_struct(index_name.clone(), &john_doe);
+        let mut surfer = Surfer::try_from(builder.clone()).unwrap();
+        surfer.insert_structs(&index_name, &[john_doe.clone(), jane_doe.clone()]).unwrap();
+        surfer.delete_by_query(&index_name, "jane").unwrap();
+        drop(surfer);
 
-        {
-            let title = "The Old Man and the Sea".to_string();
-            let body = "He was an old man who fished alone in a skiff in the Gulf Stream and he had gone eighty-four days now without taking a fish.".to_string();
-            let old_man_doc = OldMan {
-                title,
-                body,
+        // A new surfer picks up after the largest id on disk
+        let mut surfer = Surfer::try_from(builder).unwrap();
+        surfer.insert_struct(&index_name, &jane_doe).unwrap();
+        let id_field = surfer.resolve_schema(&index_name).unwrap().resolve_id_field().unwrap();
+        let searcher = surfer.readers.get(&index_name).unwrap().as_ref().unwrap().searcher();
+        let mut computed = Vec::<u64>::new();
+        for segment_reader in searcher.segment_readers() {
+            let reader = segment_reader.fast_fields().u64(id_field).unwrap();
+            for doc in 0..segment_reader.max_doc() {
+                if !segment_reader.is_deleted(doc) {
+                    computed.push(reader.get(doc));
+                };
             };
+        };
+        computed.sort_unstable();
+        assert_eq!(computed, vec![0, 2]);
 
-            let mut surfer = Surfer::new(builder.clone());
-            let _ = surfer.insert_struct(&name, &old_man_doc).unwrap();
-        }
-
-        let mut surfer = Surfer::new(builder.clone());
-        let query = "sea whale";
-        let result = surfer.read_structs::<OldMan>(&name, query, None, None);
-        assert!(result.is_ok());
-        assert!(path.exists());
-        let _ = remove_dir_all(index_path);
-        let _ = remove_dir_all(home);
+        // Clean-up
+        let path = surfer.which_index(&index_name).unwrap();
+        drop(surfer);
+        let _ = remove_dir_all(&path);
+        let _ = remove_dir_all(&home);
     }
 
     #[test]
-    fn validate_read_existing_documents_as_strings() {
-        let title = "The Old Man and the Sea".to_string();
-        let body = "He was an old man who fished alone in a skiff in the Gulf Stream and he had gone eighty-four days now without taking a fish.".to_string();
-        let expected = OldMan {
-            title,
-            body,
-        };
+    fn validate_index_written_without_id() {
+        let home = ".validate_index_written_without_id".to_string();
+        let index_name = "legacy".to_string();
+        let john_doe = UserInfo::new("John".to_string(), "Doe".to_string(), 20);
+        let jane_doe = UserInfo::new("Jane".to_string(), "Doe".to_string(), 18);
 
+        // Written the way indexes were before the hidden id
+        {
+            let (schema, _) = utils::to_schema(&john_doe, None).unwrap();
+            let path = resolve_index_directory_path(index_name.as_str(), Some(home.as_str())).unwrap();
+            let dir = open_mmap_directory(path).unwrap();
+            let index = open_index(dir, Some(&schema)).unwrap();
+            let mut writer = open_index_writer(&index).unwrap();
+            let document = schema.parse_document(&serde_json::to_string(&john_doe).unwrap()).unwrap();
+            writer.add_document(document);
+            writer.commit().unwrap();
+            writer.wait_merging_threads().unwrap();
+        }
 
-        let name = random_string(None);
         let mut builder = SurferBuilder::default();
-        let data = OldMan::default();
-        let home = ".validate_read_existing_documents_as_strings";
-        let index_path = format!("{}/{}", home, name);
-        let path = Path::new(&index_path);
-        assert!(!path.exists());
-        builder.set_home(home);
-        builder.add_struct(name.to_string(), &data);
+        builder.set_home(&home);
+        builder.add_struct(index_name.clone(), &john_doe);
+        let mut surfer = Surfer::try_from(builder).unwrap();
+        let schema = surfer.resolve_schema(&index_name).unwrap();
+        assert!(schema.resolve_id_field().is_none());
 
-        {
-            let title = "The Old Man and the Sea".to_string();
-            let body = "He was an old man who fished alone in a skiff in the Gulf Stream and he had gone eighty-four days now without taking a fish.".to_string();
-            let old_man_doc = OldMan {
-                title,
-                body,
-            };
+        // Same struct registered again is still a no-op
+        let computed = surfer.add_struct(&index_name, &john_doe);
+        assert!(computed.is_ok());
 
-            let mut surfer = Surfer::new(builder.clone());
-            let _ = surfer.insert_struct(&name, &old_man_doc).unwrap();
-        }
+        surfer.insert_struct(&index_name, &jane_doe).unwrap();
+        let mut computed = surfer.read_all_structs::<UserInfo>(&index_name, "doe").unwrap().unwrap();
+        computed.sort();
+        let mut expected = vec![john_doe.clone(), jane_doe.clone()];
+        expected.sort();
+        assert_eq!(expected, computed);
 
-        let mut surfer = Surfer::new(builder.clone());
-        let query = "sea whale";
-        let result = surfer.read_string("Non-existent", query, None, None);
-        assert!(result.is_ok());
-        let result = result.unwrap();
-        assert!(result.is_none());
-        let result = surfer.read_string(&name, query, None, None);
-        assert!(result.is_ok());
-        let result = result.unwrap();
-        assert!(result.is_some());
-        let result = result.unwrap();
-        let mut computed = Vec::new();
-        for entry in result {
-            let data: serde_json::Result<OldMan> = serde_json::from_str(&entry);
-            let data = data.unwrap();
-            computed.push(data);
-        };
-        assert_eq!(computed, vec![expected.clone()]);
+        // Deletes fall back to terms no other document has
+        let computed = surfer.delete_by_query(&index_name, "john").unwrap();
+        assert_eq!(computed, 1);
+        let computed = surfer.read_all_structs::<UserInfo>(&index_name, "doe").unwrap().unwrap();
+        assert_eq!(computed, vec![jane_doe.clone()]);
+
+        // Without ids a document sharing all its terms with unmatched ones is refused
+        let jonny_doe = UserInfo::new("Jonny".to_string(), "Doe".to_string(), 10);
+        let jinny_doe = UserInfo::new("Jinny".to_string(), "Doe".to_string(), 10);
+        let jinny_roe = UserInfo::new("Jinny".to_string(), "Roe".to_string(), 12);
+        surfer.insert_structs(&index_name, &[jonny_doe, jinny_doe, jinny_roe]).unwrap();
+        let conditions = vec![OrCondition::new(vec![
+            AndCondition::new("first".to_string(), "jinny".to_string()),
+            AndCondition::new("age".to_string(), "10".to_string()),
+        ])];
+        let computed = surfer.delete_by_conditions(&index_name, &conditions);
+        assert!(computed.is_err());
+        let computed = surfer.read_all_structs::<UserInfo>(&index_name, "doe roe").unwrap().unwrap();
+        assert_eq!(computed.len(), 4);
 
-        // Reading documents again
-        let result = surfer.read_string(&name, query, None, None);
-        assert!(result.is_ok());
-        let result = result.unwrap();
-        assert!(result.is_some());
-        let result = result.unwrap();
-        let mut computed = Vec::new();
-        for entry in result {
-            let data: serde_json::Result<OldMan> = serde_json::from_str(&entry);
-            let data = data.unwrap();
-            computed.push(data);
-        };
-        assert_eq!(computed, vec![expected.clone()]);
+        // Clean-up
+        let path = surfer.which_index(&index_name).unwrap();
+        drop(surfer);
+        let _ = remove_dir_all(&path);
+        let _ = remove_dir_all(&home);
+    }
 
-        let _ = remove_dir_all(&index_path);
-        let _ = remove_dir_all(home);
+    #[test]
+    fn validate_truncate_and_drop_index() {
+        let home = ".validate_truncate_and_drop_index".to_string();
+        let index_name = "store".to_string();
+
+        let mut builder = SurferBuilder::default();
+        builder.set_home(&home);
+        let data = UserInfo::default();
+        builder.add_struct(index_name.clone(), &data);
+        let mut surfer = Surfer::try_from(builder).unwrap();
+
+        let john_doe = UserInfo::new("John".to_string(), "Doe".to_string(), 20);
+        let jane_doe = UserInfo::new("Jane".to_string(), "Doe".to_string(), 18);
+        let users = vec![john_doe.clone(), jane_doe];
+        surfer.insert_structs(&index_name, &users).unwrap();
+        let computed = surfer.read_all_structs::<UserInfo>(&index_name, "doe").unwrap().unwrap();
+        assert_eq!(computed.len(), 2);
+
+        // Truncate keeps the index usable
+        surfer.truncate(&index_name).unwrap();
+        let computed = surfer.read_all_structs::<UserInfo>(&index_name, "doe").unwrap().unwrap();
+        assert!(computed.is_empty());
+        surfer.insert_struct(&index_name, &john_doe).unwrap();
+        let computed = surfer.read_all_structs::<UserInfo>(&index_name, "doe").unwrap().unwrap();
+        assert_eq!(computed, vec![john_doe]);
+
+        // Drop removes everything
+        let path = surfer.which_index(&index_name).unwrap();
+        assert!(Path::new(&path).exists());
+        surfer.drop_index(&index_name).unwrap();
+        assert!(!Path::new(&path).exists());
+        assert!(surfer.which_index(&index_name).is_none());
+        assert!(surfer.resolve_schema(&index_name).is_none());
+        let computed = surfer.read_all_structs::<UserInfo>(&index_name, "doe").unwrap();
+        assert!(computed.is_none());
+
+        assert!(surfer.drop_index(&index_name).is_err());
+        assert!(surfer.truncate(&index_name).is_err());
+
+        let _ = remove_dir_all(&home);
     }
 
     #[test]
-    fn validate_as_rust_structs() {
-        let name = random_string(None);
-        let home = ".validate_as_rust_structs".to_string();
-        let index_path = format!("{}/{}", home, name);
-        let path = Path::new(&index_path);
-        assert!(!path.exists());
+    fn validate_create_index_on_live_surfer() {
+        let home = ".validate_create_index_on_live_surfer".to_string();
 
-        let title = "The Old Man and the Sea".to_string();
-        let body = "He was an old man who fished alone in a skiff in the Gulf Stream and he had gone eighty-four days now without taking a fish.".to_string();
-        let old_man_doc = OldMan {
-            title,
-            body,
-        };
+        let mut builder = SurferBuilder::default();
+        builder.set_home(&home);
+        let mut surfer = Surfer::try_from(builder).unwrap();
+        let customer1 = random_string(None);
+        let customer2 = random_string(None);
+        assert!(surfer.which_index(&customer1).is_none());
 
+        let data = UserInfo::default();
+        surfer.add_struct(&customer1, &data).unwrap();
+        let john_doe = UserInfo::new("John".to_string(), "Doe".to_string(), 20);
+        surfer.insert_struct(&customer1, &john_doe).unwrap();
+        let computed = surfer.read_all_structs::<UserInfo>(&customer1, "john").unwrap().unwrap();
+        assert_eq!(computed, vec![john_doe.clone()]);
+
+        // Same schema is fine
+        assert!(surfer.add_struct(&customer1, &data).is_ok());
+
+        // Conflicting schema is not
+        let old_man = OldMan::default();
+        assert!(surfer.add_struct(&customer1, &old_man).is_err());
+        let (schema, mappings) = to_schema(&old_man, None).unwrap();
+        let schema = SurferSchema::new(schema, mappings, false, false);
+        assert!(surfer.create_index(&customer1, schema.clone()).is_err());
+        assert!(surfer.create_index(&customer2, schema).is_ok());
 
+        // Existing index on disk with a conflicting schema
         let mut builder = SurferBuilder::default();
-        builder.set_home(home.as_str());
-        builder.add_struct(name.to_string(), &old_man_doc);
-        let mut surfer = Surfer::new(builder);
+        builder.set_home(&home);
+        let mut other = Surfer::try_from(builder).unwrap();
+        assert!(other.add_struct(&customer1, &old_man).is_err());
+        assert!(other.add_struct(&customer1, &data).is_ok());
+        let computed = other.read_all_structs::<UserInfo>(&customer1, "john").unwrap().unwrap();
+        assert_eq!(computed, vec![john_doe]);
+
+        // Clean-up, writers hold a lock file until dropped
+        drop(surfer);
+        drop(other);
+        let _ = remove_dir_all(&home);
+    }
 
-        let _ = surfer.insert_struct(&name, &old_man_doc).unwrap();
-        let query = "sea whale";
+    #[test]
+    fn validate_paging_with_offset_and_cursor() {
+        let home = ".validate_paging_with_offset_and_cursor".to_string();
+        let index_name = random_string(None);
 
-        let result = surfer.read_structs::<OldMan>("non-existent", query, None, None);
-        assert!(result.is_ok());
-        let result = result.unwrap();
-        assert!(result.is_none());
+        let mut builder = SurferBuilder::default();
+        builder.set_home(&home);
+        let data = UserInfo::default();
+        builder.add_struct(index_name.clone(), &data);
+        let mut surf = Surf::try_from(builder).unwrap();
 
-        let result = surfer.read_structs::<OldMan>(&name, query, None, None).unwrap().unwrap();
-        for computed in result {
-            assert_eq!(computed, old_man_doc);
-        };
-        assert!(path.exists());
+        let mut users = Vec::new();
+        for i in 0..25u8 {
+            users.push(UserInfo::new(format!("user{}", i), "Doe".to_string(), i));
+        }
+        surf.insert(&index_name, &users).unwrap();
+
+        // Offset
+        let mut by_offset = Vec::new();
+        for offset in &[0usize, 10, 20] {
+            let paging = Paging::new(10, *offset);
+            let page = surf.read_structs_page::<UserInfo>(&index_name, "doe", &paging, None).unwrap().unwrap();
+            assert_eq!(page.resolve_total(), 25);
+            by_offset.extend(page.into_documents());
+        }
+
+        // Cursor
+        let mut by_cursor = Vec::new();
+        let mut paging = Paging::new(10, 0);
+        let mut pages = 0;
+        loop {
+            let page = surf.read_structs_page::<UserInfo>(&index_name, "doe", &paging, None).unwrap().unwrap();
+            assert_eq!(page.resolve_total(), 25);
+            pages += 1;
+            let cursor = page.resolve_cursor().cloned();
+            by_cursor.extend(page.into_documents());
+            match cursor {
+                Some(cursor) => {
+                    let token = cursor.to_token();
+                    let cursor = Cursor::try_from(token.as_str()).unwrap();
+                    paging = Paging::after(10, cursor);
+                }
+                None => break
+            }
+        }
+        assert_eq!(pages, 3);
+        assert_eq!(by_offset, by_cursor);
+        let mut computed = by_cursor.clone();
+        computed.sort();
+        let mut expected = users.clone();
+        expected.sort();
+        assert_eq!(expected, computed);
+
+        // Segment of the cursor is gone
+        let cursor = Cursor::new(1f32, SegmentDocRef::new("0123456789abcdef0123456789abcdef".to_string(), 0));
+        let computed = surf.read_structs_page::<UserInfo>(&index_name, "doe", &Paging::after(10, cursor), None);
+        assert_eq!(computed.unwrap_err().message(), "Invalid cursor");
+
+        // Strings
+        let paging = Paging::new(5, 20);
+        let page = surf.read_string_page(&index_name, "last:doe", &paging, None).unwrap().unwrap();
+        assert_eq!(page.resolve_total(), 25);
+        assert_eq!(page.resolve_documents().len(), 5);
+        assert!(page.resolve_cursor().is_some());
+
+        // Score cutoff, pages stay full & the total only counts hits above it
+        let query = "doe user3 user13 user23";
+        let hits = surf.read_scored_structs::<UserInfo>(&index_name, query, Some(25), Some(0f32)).unwrap().unwrap();
+        assert!(hits[2].resolve_score() > hits[3].resolve_score());
+        let score = Some((hits[2].resolve_score() + hits[3].resolve_score()) / 2f32);
+        let page = surf.read_structs_page::<UserInfo>(&index_name, query, &Paging::new(2, 0), score).unwrap().unwrap();
+        assert_eq!(page.resolve_total(), 3);
+        assert_eq!(page.resolve_documents().len(), 2);
+        let cursor = page.resolve_cursor().cloned().unwrap();
+        let page = surf.read_structs_page::<UserInfo>(&index_name, query, &Paging::after(2, cursor), score).unwrap().unwrap();
+        assert_eq!(page.resolve_total(), 3);
+        assert_eq!(page.resolve_documents().len(), 1);
+        let page = surf.read_string_page(&index_name, "last:doe first:user3 first:user13 first:user23", &Paging::new(0, 0), score).unwrap().unwrap();
+        assert_eq!(page.resolve_total(), 3);
+
+        // Conditions
+        let conditions = vec![OrCondition::from(("last".to_string(), "doe".to_string()))];
+        let mut by_conditions = Vec::new();
+        let mut paging = Paging::new(10, 0);
+        loop {
+            let page = surf.apply_page::<UserInfo>(&index_name, &conditions, &paging, Some(0f32)).unwrap().unwrap();
+            assert_eq!(page.resolve_total(), 25);
+            let cursor = page.resolve_cursor().cloned();
+            by_conditions.extend(page.into_documents());
+            match cursor {
+                Some(cursor) => paging = Paging::after(10, cursor),
+                None => break
+            }
+        }
+        let paging = Paging::new(10, 10);
+        let page = surf.apply_page::<UserInfo>(&index_name, &conditions, &paging, Some(0f32)).unwrap().unwrap();
+        assert_eq!(page.resolve_documents(), &by_conditions[10..20].to_vec());
+        by_conditions.sort();
+        assert_eq!(expected, by_conditions);
+
+        // Non-existent index
+        let computed = surf.read_structs_page::<UserInfo>("missing", "doe", &Paging::default(), None).unwrap();
+        assert!(computed.is_none());
+
+        // Clean-up
+        let path = surf.which_index(&index_name).unwrap();
+        let _ = remove_dir_all(&path);
+        let _ = remove_dir_all(&home);
+    }
 
-        // Reading documents again
+    #[test]
+    fn validate_sorting_by_fast_field() {
+        let home = ".validate_sorting_by_fast_field".to_string();
+        let index_name = random_string(None);
 
-        let result = surfer.read_structs::<OldMan>(&name, query, None, None).unwrap().unwrap();
-        for computed in result {
-            assert_eq!(computed, old_man_doc);
-        };
+        let mut control = HashMap::new();
+        let options = IntOptions::default()
+            .set_indexed()
+            .set_stored()
+            .set_fast(tantivy::schema::Cardinality::SingleValue);
+        control.insert("age".to_string(), Control::ControlIntOptions(options));
 
+        let mut builder = SurferBuilder::default();
+        builder.set_home(&home);
+        let data = UserInfo::default();
+        builder.add_struct_with_control(index_name.clone(), &data, &control).unwrap();
+        let mut surf = Surf::try_from(builder).unwrap();
 
-        let _ = remove_dir_all(index_path);
-        let _ = remove_dir_all(home);
+        let mut users = Vec::new();
+        for i in &[7u8, 3, 19, 11, 0, 15, 4] {
+            users.push(UserInfo::new(format!("user{}", i), "Doe".to_string(), *i));
+        }
+        surf.insert(&index_name, &users).unwrap();
+        let mut ascending: Vec<u8> = users.iter().map(|u| u.age).collect();
+        ascending.sort();
+        let mut descending = ascending.clone();
+        descending.reverse();
+
+        // Full text, ascending
+        let sort = Sort::ascending("age");
+        let page = surf.read_sorted_structs::<UserInfo>(&index_name, "doe", &sort, &Paging::new(10, 0), None).unwrap().unwrap();
+        assert_eq!(page.resolve_total(), 7);
+        assert!(page.resolve_cursor().is_none());
+        let computed: Vec<u8> = page.resolve_documents().iter().map(|u| u.age).collect();
+        assert_eq!(computed, ascending);
+
+        // Full text, descending by cursor
+        let sort = Sort::descending("age");
+        let mut computed = Vec::new();
+        let mut paging = Paging::new(3, 0);
+        loop {
+            let page = surf.read_sorted_structs::<UserInfo>(&index_name, "doe", &sort, &paging, None).unwrap().unwrap();
+            let cursor = page.resolve_cursor().cloned();
+            computed.extend(page.into_documents().into_iter().map(|u| u.age));
+            match cursor {
+                Some(cursor) => {
+                    let cursor = Cursor::try_from(cursor.to_token().as_str()).unwrap();
+                    paging = Paging::after(3, cursor);
+                }
+                None => break
+            }
+        }
+        assert_eq!(computed, descending);
+
+        // Conditions, descending with offset
+        let conditions = vec![OrCondition::from(("last".to_string(), "doe".to_string()))];
+        let page = surf.apply_sorted::<UserInfo>(&index_name, &conditions, &sort, &Paging::new(3, 2), Some(0f32)).unwrap().unwrap();
+        assert_eq!(page.resolve_total(), 7);
+        let computed: Vec<u8> = page.resolve_documents().iter().map(|u| u.age).collect();
+        assert_eq!(computed, descending[2..5].to_vec());
+        let cursor = page.resolve_cursor().cloned().unwrap();
+        let page = surf.apply_sorted::<UserInfo>(&index_name, &conditions, &sort, &Paging::after(10, cursor), Some(0f32)).unwrap().unwrap();
+        let computed: Vec<u8> = page.resolve_documents().iter().map(|u| u.age).collect();
+        assert_eq!(computed, descending[5..].to_vec());
+
+        // Cursors hold segment ids, they survive writes
+        let page = surf.read_sorted_structs::<UserInfo>(&index_name, "doe", &sort, &Paging::new(3, 0), None).unwrap().unwrap();
+        let cursor = page.resolve_cursor().cloned().unwrap();
+        surf.insert_struct(&index_name, &UserInfo::new("Jinny".to_string(), "Roe".to_string(), 12)).unwrap();
+        let page = surf.read_sorted_structs::<UserInfo>(&index_name, "doe", &sort, &Paging::after(10, cursor), None).unwrap().unwrap();
+        let computed: Vec<u8> = page.resolve_documents().iter().map(|u| u.age).collect();
+        assert_eq!(computed, descending[3..].to_vec());
+
+        // Cursor of another kind of read
+        let page = surf.read_structs_page::<UserInfo>(&index_name, "doe", &Paging::new(3, 0), None).unwrap().unwrap();
+        let scored = page.resolve_cursor().cloned().unwrap();
+        let computed = surf.read_sorted_structs::<UserInfo>(&index_name, "doe", &sort, &Paging::after(3, scored.clone()), None);
+        assert_eq!(computed.unwrap_err().message(), "Invalid cursor");
+        let computed = surf.apply_sorted::<UserInfo>(&index_name, &conditions, &sort, &Paging::after(3, scored), Some(0f32));
+        assert!(computed.is_err());
+        let page = surf.read_sorted_structs::<UserInfo>(&index_name, "doe", &sort, &Paging::new(3, 0), None).unwrap().unwrap();
+        let keyed = page.resolve_cursor().cloned().unwrap();
+        let computed = surf.read_structs_page::<UserInfo>(&index_name, "doe", &Paging::after(3, keyed.clone()), None);
+        assert_eq!(computed.unwrap_err().message(), "Invalid cursor");
+        let computed = surf.apply_page::<UserInfo>(&index_name, &conditions, &Paging::after(3, keyed), Some(0f32));
+        assert!(computed.is_err());
+
+        // Not sortable
+        assert!(surf.read_sorted_structs::<UserInfo>(&index_name, "doe", &Sort::ascending("first"), &Paging::default(), None).is_err());
+        assert!(surf.read_sorted_structs::<UserInfo>(&index_name, "doe", &Sort::ascending("missing"), &Paging::default(), None).is_err());
+
+        // Ties by doc address or by score
+        let weaker = UserInfo::new("tie".to_string(), "Roe".to_string(), 7);
+        let stronger = UserInfo::new("tie tie tie".to_string(), "Roe".to_string(), 7);
+        surf.insert(&index_name, &[weaker.clone(), stronger.clone()]).unwrap();
+        let mut sort = Sort::ascending("age");
+        let page = surf.read_sorted_structs::<UserInfo>(&index_name, "tie", &sort, &Paging::default(), None).unwrap().unwrap();
+        assert_eq!(page.into_documents(), vec![weaker.clone(), stronger.clone()]);
+        sort.set_tiebreak(Tiebreak::Score);
+        let page = surf.read_sorted_structs::<UserInfo>(&index_name, "tie", &sort, &Paging::new(1, 0), None).unwrap().unwrap();
+        assert_eq!(page.resolve_documents(), &vec![stronger.clone()]);
+        let cursor = page.resolve_cursor().cloned().unwrap();
+        let cursor = Cursor::try_from(cursor.to_token().as_str()).unwrap();
+        let page = surf.read_sorted_structs::<UserInfo>(&index_name, "tie", &sort, &Paging::after(1, cursor), None).unwrap().unwrap();
+        assert_eq!(page.resolve_documents(), &vec![weaker.clone()]);
+        let conditions = vec![OrCondition::from(("first".to_string(), "tie".to_string()))];
+        let hits = surf.apply_scored::<UserInfo>(&index_name, &conditions, None, Some(0f32)).unwrap().unwrap();
+        let expected: Vec<UserInfo> = hits.into_iter().map(|hit| hit.into_document()).collect();
+        let page = surf.apply_sorted::<UserInfo>(&index_name, &conditions, &sort, &Paging::default(), Some(0f32)).unwrap().unwrap();
+        assert_eq!(page.into_documents(), expected);
+
+        // Score cutoff
+        let hits = surf.read_scored_structs::<UserInfo>(&index_name, "tie", None, Some(0f32)).unwrap().unwrap();
+        let score = Some((hits[0].resolve_score() + hits[1].resolve_score()) / 2f32);
+        let page = surf.read_sorted_structs::<UserInfo>(&index_name, "tie", &sort, &Paging::default(), score).unwrap().unwrap();
+        assert_eq!(page.resolve_total(), 1);
+        assert_eq!(page.into_documents(), vec![stronger]);
+
+        // Clean-up
+        let path = surf.which_index(&index_name).unwrap();
+        let _ = remove_dir_all(&path);
+        let _ = remove_dir_all(&home);
     }
 
     #[test]
-    fn validate_initialize_mmap() {
-        let home = ".validate_initialize_mmap";
-        let index_name = "someindex";
-        let path_to_index = ".validate_initialize_mmap/someindex";
-        let path = Path::new(path_to_index);
-        assert!(!path.exists());
-        let oldman = OldMan::default();
-        let (schema, mappings) = to_schema(&oldman, None).unwrap();
-        let schema = SurferSchema::new(schema, mappings, false, false);
-        let _ = initialize_mmap(index_name, home, &schema);
-        assert!(path.exists());
-        let _ = std::fs::remove_dir_all(path_to_index);
+    fn validate_range_conditions() {
+        let home = ".validate_range_conditions".to_string();
+        let index_name = random_string(None);
 
-        let _ = remove_dir_all(path_to_index);
-        let _ = remove_dir_all(index_name);
-        let _ = remove_dir_all(home);
+        let mut builder = SurferBuilder::default();
+        builder.set_home(&home);
+        let data = Giant::default();
+        builder.add_struct(index_name.clone(), &data);
+        let mut surf = Surf::try_from(builder).unwrap();
+
+        let mut giants = Vec::new();
+        for i in 0..10u64 {
+            let giant = Giant {
+                a: format!("giant{}", i),
+                c: i * 10,
+                g: i as i64 - 5,
+                k: i as f64 * 0.1,
+                ..Default::default()
+            };
+            giants.push(giant);
+        }
+        surf.insert(&index_name, &giants).unwrap();
+        let select = |surf: &mut Surf, conditions: Vec<AndCondition>| -> Vec<String> {
+            let conditions = vec![OrCondition::new(conditions)];
+            let computed = surf.apply::<Giant>(&index_name, &conditions, Some(100), Some(0f32)).unwrap().unwrap();
+            let mut computed: Vec<String> = computed.into_iter().map(|g| g.a).collect();
+            computed.sort();
+            computed
+        };
+        let names = |range: std::ops::Range<usize>| -> Vec<String> {
+            range.map(|i| format!("giant{}", i)).collect()
+        };
+
+        assert_eq!(select(&mut surf, vec![AndCondition::gt("c", "70")]), names(8..10));
+        assert_eq!(select(&mut surf, vec![AndCondition::gte("c", "70")]), names(7..10));
+        assert_eq!(select(&mut surf, vec![AndCondition::lt("g", "-3")]), names(0..2));
+        assert_eq!(select(&mut surf, vec![AndCondition::lte("g", "-3")]), names(0..3));
+        assert_eq!(select(&mut surf, vec![AndCondition::between("k", "0.2", "0.5")]), names(2..6));
+        assert_eq!(select(&mut surf, vec![AndCondition::gte("c", "20"), AndCondition::lt("g", "0")]), names(2..5));
+        assert_eq!(select(&mut surf, vec![AndCondition::gt("c", "20"), AndCondition::new("a".to_string(), "giant4".to_string())]), names(4..5));
+
+        // Float equality tolerates rounding, giant3 holds 0.1 + 0.2
+        assert_ne!(0.1f64 + 0.2f64, 0.3f64);
+        assert_eq!(giants[3].k, 0.1f64 + 0.2f64);
+        assert_eq!(select(&mut surf, vec![AndCondition::new("k".to_string(), "0.3".to_string())]), names(3..4));
+        assert_eq!(select(&mut surf, vec![AndCondition::new("k".to_string(), "0.30000000000000004".to_string())]), names(3..4));
+        assert!(select(&mut surf, vec![AndCondition::new("k".to_string(), "0.31".to_string())]).is_empty());
+
+        // Display
+        let condition = OrCondition::new(vec![AndCondition::between("k", "0.2", "0.5")]);
+        assert_eq!(condition.to_string(), "k BETWEEN 0.2 AND 0.5");
+        assert_eq!(AndCondition::gte("c", "20").to_string(), "c >= 20");
+
+        // Invalid
+        let conditions = vec![OrCondition::new(vec![AndCondition::gt("a", "giant1")])];
+        assert!(surf.apply::<Giant>(&index_name, &conditions, None, None).is_err());
+        let conditions = vec![OrCondition::new(vec![AndCondition::between("c", "1", "ten")])];
+        assert!(surf.apply::<Giant>(&index_name, &conditions, None, None).is_err());
+
+        // Clean-up
+        let path = surf.which_index(&index_name).unwrap();
+        let _ = remove_dir_all(&path);
+        let _ = remove_dir_all(&home);
     }
 
     #[test]
-    fn validate_read_existing_documents_as_structs_limit_one() {
-        let name = random_string(None);
-        let home = ".validate_read_existing_documents_as_structs_limit_one";
-        let index_path = format!("{}/{}", home, name);
-        let path = Path::new(&index_path);
-        assert!(!path.exists());
-
-        let data = OldMan::default();
+    fn validate_boolean_expressions() {
+        let home = ".validate_boolean_expressions".to_string();
+        let index_name = random_string(None);
 
         let mut builder = SurferBuilder::default();
-        builder.set_home(home);
-        builder.add_struct(name.clone(), &data);
+        builder.set_home(&home);
+        let data = UserInfo::default();
+        builder.add_struct(index_name.clone(), &data);
+        let mut surf = Surf::try_from(builder).unwrap();
 
-        let title = "The Old Man and the Sea".to_string();
-        let body = "He was an old man who fished alone in a skiff in the Gulf Stream and he had gone eighty-four days now without taking a fish.".to_string();
-        let old_man_doc = OldMan {
-            title,
-            body,
+        let mut users = Vec::new();
+        for i in 0..50u8 {
+            users.push(UserInfo::new(format!("user{}", i), "Doe".to_string(), i));
+        }
+        users.push(UserInfo::new("Jane".to_string(), "Doe".to_string(), 30));
+        users.push(UserInfo::new("Jane".to_string(), "Roe".to_string(), 30));
+        surf.insert(&index_name, &users).unwrap();
+        let term = |field_name: &str, field_value: &str| -> Expression {
+            AndCondition::new(field_name.to_string(), field_value.to_string()).into()
+        };
+        let ages = |computed: Vec<UserInfo>| -> Vec<u8> {
+            let mut computed: Vec<u8> = computed.into_iter().map(|u| u.age).collect();
+            computed.sort();
+            computed
         };
 
-        let mut surfer = Surfer::new(builder.clone());
-        let _ = surfer.insert_struct(&name, &old_man_doc).unwrap();
-        let _ = surfer.insert_struct(&name, &old_man_doc).unwrap();
-        let _ = surfer.insert_struct(&name, &old_man_doc).unwrap();
-        let _ = surfer.insert_struct(&name, &old_man_doc).unwrap();
-        let _ = surfer.insert_struct(&name, &old_man_doc).unwrap();
-
-        let query = "sea whale";
-        let result = surfer.read_structs::<OldMan>(&name, query, None, None);
-        assert!(result.is_ok());
-        let result = result.unwrap().unwrap();
-        assert_eq!(result.len(), 5);
+        // Limit applies to the combined result, not to each clause
+        let expression = Expression::and(vec![term("last", "doe"), term("first", "jane")]);
+        let computed = surf.apply_expression::<UserInfo>(&index_name, &expression, Some(1), Some(0f32)).unwrap().unwrap();
+        assert_eq!(computed, vec![UserInfo::new("Jane".to_string(), "Doe".to_string(), 30)]);
+
+        // Not & nesting
+        let expression = Expression::and(vec![
+            term("first", "jane"),
+            Expression::not(term("last", "doe")),
+        ]);
+        let computed = surf.apply_expression::<UserInfo>(&index_name, &expression, None, Some(0f32)).unwrap().unwrap();
+        assert_eq!(computed, vec![UserInfo::new("Jane".to_string(), "Roe".to_string(), 30)]);
+
+        let expression = Expression::or(vec![
+            Expression::and(vec![
+                Expression::condition(AndCondition::lt("age", "3")),
+                Expression::not(Expression::or(vec![term("age", "0"), term("age", "1")])),
+            ]),
+            term("age", "40"),
+        ]);
+        let computed = surf.apply_expression::<UserInfo>(&index_name, &expression, None, Some(0f32)).unwrap().unwrap();
+        assert_eq!(ages(computed), vec![2, 40]);
+
+        // Match all
+        let computed = surf.apply_expression::<UserInfo>(&index_name, &Expression::all(), Some(100), None).unwrap().unwrap();
+        assert_eq!(computed.len(), users.len());
+        let expression = Expression::not(AndCondition::gte("age", "2").into());
+        let computed = surf.apply_expression::<UserInfo>(&index_name, &expression, Some(100), None).unwrap().unwrap();
+        assert_eq!(ages(computed), vec![0, 1]);
+        let computed = surf.apply_expression::<UserInfo>(&index_name, &Expression::or(vec![]), Some(100), None).unwrap().unwrap();
+        assert!(computed.is_empty());
+
+        // Conditions go through the same query
+        let conditions = vec![OrCondition::new(vec![
+            AndCondition::new("last".to_string(), "doe".to_string()),
+            AndCondition::new("first".to_string(), "jane".to_string()),
+        ])];
+        let computed = surf.apply::<UserInfo>(&index_name, &conditions, Some(1), Some(0f32)).unwrap().unwrap();
+        assert_eq!(computed, vec![UserInfo::new("Jane".to_string(), "Doe".to_string(), 30)]);
+
+        // Ranked by the combined score
+        let conditions = vec![
+            OrCondition::from(("first".to_string(), "jane".to_string())),
+            OrCondition::from(("last".to_string(), "roe".to_string())),
+        ];
+        let hits = surf.apply_scored::<UserInfo>(&index_name, &conditions, None, Some(0f32)).unwrap().unwrap();
+        let expected: Vec<UserInfo> = hits.into_iter().map(|hit| hit.into_document()).collect();
+        assert_eq!(expected[0], UserInfo::new("Jane".to_string(), "Roe".to_string(), 30));
+        let computed = surf.apply::<UserInfo>(&index_name, &conditions, None, Some(0f32)).unwrap().unwrap();
+        assert_eq!(computed, expected);
+        let page = surf.apply_page::<UserInfo>(&index_name, &conditions, &Paging::default(), Some(0f32)).unwrap().unwrap();
+        assert_eq!(page.into_documents(), expected);
+
+        // Stored documents not fitting the struct
+        #[derive(Serialize, Deserialize)]
+        struct Numbered {
+            first: u64,
+        }
+        assert!(surf.apply::<Numbered>(&index_name, &conditions, None, Some(0f32)).is_err());
 
-        let result = surfer.read_structs::<OldMan>(&name, query, Some(1), None);
-        assert!(result.is_ok());
-        let result = result.unwrap().unwrap();
-        assert_eq!(result.len(), 1);
+        // Invalid leaf
+        let expression = Expression::not(AndCondition::gt("first", "jane").into());
+        assert!(surf.apply_expression::<UserInfo>(&index_name, &expression, None, None).is_err());
 
-        assert!(path.exists());
-        let _ = remove_dir_all(index_path);
-        let _ = remove_dir_all(home);
+        // Clean-up
+        let path = surf.which_index(&index_name).unwrap();
+        let _ = remove_dir_all(&path);
+        let _ = remove_dir_all(&home);
     }
 
     #[test]
-    fn validate_read_existing_documents_as_structs_default_ten() {
-        let name = random_string(None);
-        let home = ".validate_read_existing_documents_as_structs_default_ten";
-        let index_path = format!("{}/{}", home, name);
-        let path = Path::new(&index_path);
-        assert!(!path.exists());
+    fn validate_sql_queries() {
+        let home = ".validate_sql_queries".to_string();
+        let index_name = format!("users{}", random_string(None));
 
-        let data = OldMan::default();
+        let mut control = HashMap::new();
+        let options = IntOptions::default()
+            .set_indexed()
+            .set_stored()
+            .set_fast(tantivy::schema::Cardinality::SingleValue);
+        control.insert("age".to_string(), Control::ControlIntOptions(options));
 
         let mut builder = SurferBuilder::default();
-        builder.set_home(home);
-        builder.add_struct(name.clone(), &data);
+        builder.set_home(&home);
+        let data = UserInfo::default();
+        builder.add_struct_with_control(index_name.clone(), &data, &control).unwrap();
+        let mut surf = Surf::try_from(builder).unwrap();
 
-        let title = "The Old Man and the Sea".to_string();
-        let body = "He was an old man who fished alone in a skiff in the Gulf Stream and he had gone eighty-four days now without taking a fish.".to_string();
-        let old_man_doc = OldMan {
-            title,
-            body,
-        };
+        let mut users = Vec::new();
+        for i in 0..60u8 {
+            let last = if i % 2 == 0 { "Doe" } else { "Roe" };
+            users.push(UserInfo::new(format!("user{}", i), last.to_string(), i));
+        }
+        surf.insert(&index_name, &users).unwrap();
+
+        let query = format!("SELECT first, age FROM {} WHERE last = 'doe' AND age > 30 ORDER BY age DESC LIMIT 10 OFFSET 2", index_name);
+        let page = surf.query(&query).unwrap().unwrap();
+        assert_eq!(page.resolve_total(), 14);
+        let computed: Vec<u64> = page.resolve_documents().iter().map(|d| d["age"].as_u64().unwrap()).collect();
+        assert_eq!(computed, vec![54, 52, 50, 48, 46, 44, 42, 40, 38, 36]);
+        let first = page.resolve_documents().first().unwrap().as_object().unwrap();
+        let mut fields: Vec<&String> = first.keys().collect();
+        fields.sort();
+        assert_eq!(fields, vec!["age", "first"]);
+
+        // Nesting, NOT & BETWEEN
+        let query = format!("select * from {} where (age between 10 and 13 or age = 40) and last != 'roe'", index_name);
+        let page = surf.query_structs::<UserInfo>(&query).unwrap().unwrap();
+        let mut computed: Vec<u8> = page.into_documents().into_iter().map(|u| u.age).collect();
+        computed.sort();
+        assert_eq!(computed, vec![10, 12, 40]);
 
-        let mut surfer = Surfer::new(builder.clone());
-        for _ in 0..20 {
-            let _ = surfer.insert_struct(&name, &old_man_doc).unwrap();
+        // Smaller struct
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Age {
+            age: u8,
         }
+        let query = format!("SELECT age FROM {} WHERE age < 3 ORDER BY age", index_name);
+        let page = surf.query_structs::<Age>(&query).unwrap().unwrap();
+        assert_eq!(page.into_documents(), vec![Age { age: 0 }, Age { age: 1 }, Age { age: 2 }]);
+
+        // Typed literals point at the column
+        let query = format!("SELECT * FROM {} WHERE age = 'ten'", index_name);
+        let error = surf.query(&query).unwrap_err();
+        assert_eq!(error.message(), format!("Invalid query at column {}", 28 + index_name.len()));
+        let query = format!("SELECT * FROM {} WHERE last = 10", index_name);
+        let error = surf.query(&query).unwrap_err();
+        assert_eq!(error.message(), format!("Invalid query at column {}", 29 + index_name.len()));
+        let query = format!("SELECT * FROM {} WHERE last > 'doe'", index_name);
+        assert!(surf.query(&query).is_err());
+
+        // Text goes through the field tokenizer
+        let query = format!("SELECT * FROM {} WHERE last = 'Doe'", index_name);
+        let page = surf.query(&query).unwrap().unwrap();
+        assert_eq!(page.resolve_total(), 30);
+        assert_eq!(page.resolve_documents().len(), 10);
+        assert!(page.resolve_cursor().is_some());
+        let query = format!("SELECT * FROM {} WHERE last = 'DOE' AND age < 4", index_name);
+        let page = surf.query_structs::<UserInfo>(&query).unwrap().unwrap();
+        assert_eq!(page.into_documents(), vec![users[0].clone(), users[2].clone()]);
+        let query = format!("SELECT * FROM {} WHERE last = 'Doe Roe'", index_name);
+        let error = surf.query(&query).unwrap_err();
+        assert_eq!(error.message(), format!("Invalid query at column {}", 29 + index_name.len()));
+        let query = format!("SELECT * FROM {} WHERE last = '...'", index_name);
+        assert!(surf.query(&query).is_err());
+        let query = format!("SELECT first, height FROM {}", index_name);
+        let error = surf.query(&query).unwrap_err();
+        assert_eq!(error.message(), "Invalid query at column 15");
+        let query = format!("SELECT * FROM {} ORDER BY first", index_name);
+        assert!(surf.query(&query).is_err());
+
+        // Non-existent index
+        assert!(surf.query("SELECT * FROM missing").unwrap().is_none());
 
+        // Clean-up
+        let path = surf.which_index(&index_name).unwrap();
+        let _ = remove_dir_all(&path);
+        let _ = remove_dir_all(&home);
+    }
 
-        let query = "sea whale";
-        let result = surfer.read_structs::<OldMan>(&name, query, None, None);
-        assert!(result.is_ok());
-        let result = result.unwrap().unwrap();
-        assert_eq!(result.len(), 10);
+    #[test]
+    fn validate_text_match_conditions() {
+        let home = ".validate_text_match_conditions".to_string();
+        let index_name = random_string(None);
 
-        let result = surfer.read_structs::<OldMan>(&name, query, Some(20), None);
-        assert!(result.is_ok());
-        let result = result.unwrap().unwrap();
-        assert_eq!(result.len(), 20);
+        let mut builder = SurferBuilder::default();
+        builder.set_home(&home);
+        let data = OldMan::default();
+        builder.add_struct(index_name.clone(), &data);
+        let mut surf = Surf::try_from(builder).unwrap();
 
-        assert!(path.exists());
-        let _ = remove_dir_all(index_path);
-        let _ = remove_dir_all(home);
-    }
+        let bodies = [
+            ("a", "the old man and the sea"),
+            ("b", "the old grey man"),
+            ("c", "the man was old"),
+            ("d", "Jonathan joined the sea scouts"),
+        ];
+        let data: Vec<OldMan> = bodies
+            .iter()
+            .map(|(title, body)| OldMan { title: title.to_string(), body: body.to_string() })
+            .collect();
+        surf.insert(&index_name, &data).unwrap();
+        let titles = |surf: &mut Surf, condition: AndCondition, score: Option<f32>| -> Vec<String> {
+            let conditions = vec![OrCondition::new(vec![condition])];
+            let computed = surf.apply::<OldMan>(&index_name, &conditions, None, score).unwrap().unwrap();
+            let mut computed: Vec<String> = computed.into_iter().map(|d| d.title).collect();
+            computed.sort();
+            computed
+        };
 
-    #[derive(Serialize, Deserialize)]
-    struct Dummy {
-        x: String,
-        y: String,
-        z: u64,
+        // Phrase
+        assert_eq!(titles(&mut surf, AndCondition::phrase("body", "old man", 0), Some(0f32)), vec!["a"]);
+        assert_eq!(titles(&mut surf, AndCondition::phrase("body", "Old Man", 1), Some(0f32)), vec!["a", "b"]);
+        assert_eq!(titles(&mut surf, AndCondition::phrase("body", "the sea", 2), Some(0f32)), vec!["a", "d"]);
+        assert_eq!(titles(&mut surf, AndCondition::phrase("body", "scouts", 0), Some(0f32)), vec!["d"]);
+
+        // Prefix, wildcard & regex are filters, the default cutoff does not apply
+        assert_eq!(titles(&mut surf, AndCondition::prefix("body", "Jo"), None), vec!["d"]);
+        assert_eq!(titles(&mut surf, AndCondition::prefix("body", "gr*"), None), vec!["b"]);
+        assert_eq!(titles(&mut surf, AndCondition::wildcard("body", "w?s"), None), vec!["c"]);
+        assert_eq!(titles(&mut surf, AndCondition::wildcard("body", "*ath*"), None), vec!["d"]);
+        assert_eq!(titles(&mut surf, AndCondition::regex("body", "se[a-z]"), None), vec!["a", "d"]);
+        assert_eq!(titles(&mut surf, AndCondition::regex("body", "s"), None), Vec::<String>::new());
+        assert_eq!(titles(&mut surf, AndCondition::regex("body", "Jo.*"), None), vec!["d"]);
+        assert_eq!(titles(&mut surf, AndCondition::regex("body", "JO\\w+ED"), None), vec!["d"]);
+        assert_eq!(titles(&mut surf, AndCondition::regex("body", "(?:SEA|MAN)"), None), vec!["a", "b", "c", "d"]);
+        let expression = Expression::and(vec![
+            AndCondition::prefix("body", "old").into(),
+            Expression::not(AndCondition::phrase("body", "old man", 1).into()),
+        ]);
+        let computed = surf.apply_expression::<OldMan>(&index_name, &expression, None, None).unwrap().unwrap();
+        assert_eq!(computed, vec![data[2].clone()]);
+
+        // Display
+        assert_eq!(AndCondition::phrase("body", "old man", 1).to_string(), "body PHRASE \"old man\"~1");
+        assert_eq!(AndCondition::prefix("body", "jo*").to_string(), "body LIKE jo*");
+
+        // Invalid
+        let conditions = vec![OrCondition::new(vec![AndCondition::regex("body", "(")])];
+        assert!(surf.apply::<OldMan>(&index_name, &conditions, None, None).is_err());
+        let conditions = vec![OrCondition::new(vec![AndCondition::phrase("body", " ", 0)])];
+        assert!(surf.apply::<OldMan>(&index_name, &conditions, None, None).is_err());
+        let conditions = vec![OrCondition::new(vec![AndCondition::phrase("body", "a b c d e f g", 20)])];
+        assert!(surf.apply::<OldMan>(&index_name, &conditions, None, None).is_err());
+        let conditions = vec![OrCondition::new(vec![AndCondition::prefix("missing", "jo")])];
+        assert!(surf.apply::<OldMan>(&index_name, &conditions, None, None).is_err());
+
+        // Clean-up
+        let path = surf.which_index(&index_name).unwrap();
+        let _ = remove_dir_all(&path);
+        let _ = remove_dir_all(&home);
     }
 
     #[test]
-    fn check_invalid_index_name() {
-        let home = random_string(None);
-        let data = Dummy {
-            x: "X".to_owned(),
-            y: "Y".to_owned(),
-            z: 100u64,
-        };
+    fn validate_fuzzy_search() {
+        let home = ".validate_fuzzy_search".to_string();
+        let index_name = random_string(None);
+
         let mut builder = SurferBuilder::default();
         builder.set_home(&home);
-        builder.add_struct("dummy".to_string(), &data);
-        let surfer = Surfer::try_from(builder).unwrap();
-        let computed = surfer.which_index("crap");
-        assert!(computed.is_none());
-        let home = Path::new(&home);
-        assert!(home.exists());
-        let index_path = surfer.which_index("dummy").unwrap();
-        let _ = remove_dir_all(&index_path);
+        let data = UserInfo::default();
+        builder.add_struct(index_name.clone(), &data);
+        let mut surf = Surf::try_from(builder).unwrap();
+
+        let john_doe = UserInfo::new("John".to_string(), "Doe".to_string(), 20);
+        let jane_doe = UserInfo::new("Jane".to_string(), "Doe".to_string(), 18);
+        let jim_smith = UserInfo::new("Jim".to_string(), "Smith".to_string(), 40);
+        surf.insert(&index_name, &[john_doe.clone(), jane_doe.clone(), jim_smith.clone()]).unwrap();
+
+        // Plain search misses the typo
+        let computed = surf.read_structs::<UserInfo>(&index_name, "deo", None, None).unwrap().unwrap();
+        assert!(computed.is_empty());
+
+        // All text fields
+        let mut computed = surf.read_fuzzy_structs::<UserInfo>(&index_name, "deo", &HashMap::new(), None, None).unwrap().unwrap();
+        computed.sort();
+        assert_eq!(computed, vec![jane_doe.clone(), john_doe.clone()]);
+
+        // Per field fuzziness, a swap is two edits without transposition
+        let mut fuzziness = HashMap::new();
+        fuzziness.insert("last".to_string(), Fuzziness::new(1, false));
+        let computed = surf.read_fuzzy_structs::<UserInfo>(&index_name, "deo", &fuzziness, None, None).unwrap().unwrap();
+        assert!(computed.is_empty());
+        fuzziness.insert("last".to_string(), Fuzziness::new(2, false));
+        let computed = surf.read_fuzzy_structs::<UserInfo>(&index_name, "deo", &fuzziness, None, None).unwrap().unwrap();
+        assert_eq!(computed.len(), 2);
+        fuzziness.insert("last".to_string(), Fuzziness::new(1, true));
+        let computed = surf.read_fuzzy_structs::<UserInfo>(&index_name, "smtih", &fuzziness, None, None).unwrap().unwrap();
+        assert_eq!(computed, vec![jim_smith.clone()]);
+        let computed = surf.read_fuzzy_structs::<UserInfo>(&index_name, "jhon", &fuzziness, None, None).unwrap().unwrap();
+        assert!(computed.is_empty());
+
+        // Conditions
+        let conditions = vec![OrCondition::new(vec![
+            AndCondition::fuzzy("first", "jhon", Fuzziness::default()),
+            AndCondition::fuzzy("last", "deo", Fuzziness::default()),
+        ])];
+        let computed = surf.apply::<UserInfo>(&index_name, &conditions, None, None).unwrap().unwrap();
+        assert_eq!(computed, vec![john_doe.clone()]);
+        let conditions = vec![OrCondition::new(vec![AndCondition::fuzzy("first", "jhon", Fuzziness::new(1, false))])];
+        let computed = surf.apply::<UserInfo>(&index_name, &conditions, None, None).unwrap().unwrap();
+        assert!(computed.is_empty());
+        assert_eq!(AndCondition::fuzzy("last", "deo", Fuzziness::default()).to_string(), "last ~ deo~1");
+
+        // Invalid
+        let mut fuzziness = HashMap::new();
+        fuzziness.insert("age".to_string(), Fuzziness::default());
+        assert!(surf.read_fuzzy_structs::<UserInfo>(&index_name, "deo", &fuzziness, None, None).is_err());
+        let conditions = vec![OrCondition::new(vec![AndCondition::fuzzy("last", "deo", Fuzziness::new(3, true))])];
+        assert!(surf.apply::<UserInfo>(&index_name, &conditions, None, None).is_err());
+
+        // Clean-up
+        let path = surf.which_index(&index_name).unwrap();
+        let _ = remove_dir_all(&path);
         let _ = remove_dir_all(&home);
     }
 
     #[test]
-    fn check_invalid_index_insert() {
-        let home = random_string(None);
-        let data = Dummy {
-            x: "X".to_owned(),
-            y: "Y".to_owned(),
-            z: 100u64,
-        };
+    fn validate_did_you_mean() {
+        let home = ".validate_did_you_mean".to_string();
+        let index_name = random_string(None);
+
         let mut builder = SurferBuilder::default();
         builder.set_home(&home);
-        builder.add_struct("dummy".to_string(), &data);
-        let mut surfer = Surfer::try_from(builder).unwrap();
-        let data = vec![data];
-        let computed = surfer.insert_structs("crap", &data);
-        assert!(computed.is_ok());
-        let index_path = surfer.which_index("dummy").unwrap();
-        let _ = remove_dir_all(&index_path);
+        let data = UserInfo::default();
+        builder.add_struct(index_name.clone(), &data);
+        let mut surf = Surf::try_from(builder).unwrap();
+
+        let john_doe = UserInfo::new("John".to_string(), "Doe".to_string(), 20);
+        let jane_doe = UserInfo::new("Jane".to_string(), "Doe".to_string(), 18);
+        surf.insert(&index_name, &[john_doe.clone(), jane_doe.clone()]).unwrap();
+
+        // Dictionary built from the index, re-run with the suggestion
+        let suggest = Suggest::default();
+        let computed = surf.read_structs_with_suggestion::<UserInfo>(&index_name, "deo", &suggest, None, None).unwrap().unwrap();
+        assert_eq!(computed.resolve_suggestion(), Some(&"doe".to_string()));
+        assert!(computed.resolve_rerun());
+        let mut computed = computed.into_documents();
+        computed.sort();
+        assert_eq!(computed, vec![jane_doe.clone(), john_doe.clone()]);
+
+        // Suggest only
+        let suggest = Suggest::new(1, false);
+        let computed = surf.read_structs_with_suggestion::<UserInfo>(&index_name, "last:deo", &suggest, None, None).unwrap().unwrap();
+        assert_eq!(computed.resolve_suggestion(), Some(&"last:doe".to_string()));
+        assert!(!computed.resolve_rerun());
+        assert!(computed.resolve_documents().is_empty());
+
+        // Enough hits, nothing to suggest
+        let computed = surf.read_structs_with_suggestion::<UserInfo>(&index_name, "doe", &suggest, None, None).unwrap().unwrap();
+        assert_eq!(computed.resolve_suggestion(), None);
+        assert_eq!(computed.resolve_documents().len(), 2);
+        let suggest = Suggest::new(5, true);
+        let computed = surf.read_structs_with_suggestion::<UserInfo>(&index_name, "doe", &suggest, None, None).unwrap().unwrap();
+        assert_eq!(computed.resolve_suggestion(), None);
+        assert!(!computed.resolve_rerun());
+
+        // Custom dictionary
+        surf.set_speller(&index_name, FuzzyWord::from_words(vec![("doe".to_string(), 1)]));
+        let computed = surf.read_structs_with_suggestion::<UserInfo>(&index_name, "jhon", &suggest, None, None).unwrap().unwrap();
+        assert_eq!(computed.resolve_suggestion(), None);
+        surf.build_speller(&index_name).unwrap();
+        let computed = surf.read_structs_with_suggestion::<UserInfo>(&index_name, "jhon", &suggest, None, None).unwrap().unwrap();
+        assert_eq!(computed.resolve_suggestion(), Some(&"john".to_string()));
+        assert_eq!(computed.into_documents(), vec![john_doe.clone()]);
+
+        // Words inserted later are picked up
+        let computed = surf.read_structs_with_suggestion::<UserInfo>(&index_name, "jenny", &suggest, None, None).unwrap().unwrap();
+        assert_eq!(computed.resolve_suggestion(), None);
+        let jenny_doe = UserInfo::new("Jenny".to_string(), "Doe".to_string(), 30);
+        surf.insert_struct(&index_name, &jenny_doe).unwrap();
+        let computed = surf.read_structs_with_suggestion::<UserInfo>(&index_name, "jennyy", &suggest, None, None).unwrap().unwrap();
+        assert_eq!(computed.resolve_suggestion(), Some(&"jenny".to_string()));
+        assert_eq!(computed.into_documents(), vec![jenny_doe]);
+
+        // Non-existent index
+        let computed = surf.read_structs_with_suggestion::<UserInfo>("missing", "deo", &suggest, None, None).unwrap();
+        assert!(computed.is_none());
+
+        // Clean-up
+        let path = surf.which_index(&index_name).unwrap();
+        let _ = remove_dir_all(&path);
         let _ = remove_dir_all(&home);
     }
 
     #[test]
-    fn check_invalid_index_lookup() {
-        let name = random_string(None);
-        let home = ".check_invalid_index_lookup";
-        let index_path = format!("{}/{}", home, name);
-        let path = Path::new(&index_path);
-        assert!(!path.exists());
+    fn validate_highlighted_snippets() {
+        let home = ".validate_highlighted_snippets".to_string();
+        let index_name = random_string(None);
 
-
-        let data = Dummy {
-            x: "X".to_owned(),
-            y: "Y".to_owned(),
-            z: 100u64,
-        };
         let mut builder = SurferBuilder::default();
         builder.set_home(&home);
-        let mut surfer = Surfer::try_from(builder).unwrap();
-        let _ = surfer.insert_struct("dummy", &data).unwrap();
-        let computed = surfer.read_structs::<Dummy>("crap", "X", None, None);
-        assert!(computed.is_ok());
-        let computed = computed.unwrap();
+        let data = OldMan::default();
+        builder.add_struct(index_name.clone(), &data);
+        let mut surf = Surf::try_from(builder).unwrap();
+
+        let sea = OldMan {
+            title: "The Old Man and the Sea".to_string(),
+            body: "He was an old man who fished alone in a skiff in the Gulf Stream & he had gone eighty-four days now without taking a fish.".to_string(),
+        };
+        let river = OldMan {
+            title: "Across the River".to_string(),
+            body: "They started two hours before daylight".to_string(),
+        };
+        surf.insert(&index_name, &[sea.clone(), river.clone()]).unwrap();
+
+        // Default, bold html
+        let computed = surf.read_highlighted_structs::<OldMan>(&index_name, "fish", &Highlight::default(), None, None).unwrap().unwrap();
+        assert_eq!(computed.len(), 1);
+        assert_eq!(computed[0].resolve_document(), &sea);
+        let snippets = computed[0].resolve_snippets();
+        assert_eq!(snippets.len(), 1);
+        let body = snippets.get("body").unwrap();
+        assert!(body.contains("<b>fish</b>"));
+        assert!(body.contains("&amp;"));
+
+        // Tags, fragment length & fields
+        let highlight = Highlight::new(Some(vec!["title".to_string()]), "[", "]", 20, false);
+        let computed = surf.read_highlighted_structs::<OldMan>(&index_name, "old", &highlight, None, None).unwrap().unwrap();
+        let snippets = computed[0].resolve_snippets();
+        assert_eq!(snippets.keys().collect::<Vec<_>>(), vec!["title"]);
+        assert_eq!(snippets.get("title").unwrap(), "The [Old] Man and the");
+        let highlight = Highlight::new(None, "[", "]", 30, false);
+        let computed = surf.read_highlighted_structs::<OldMan>(&index_name, "old", &highlight, None, None).unwrap().unwrap();
+        let body = computed[0].resolve_snippets().get("body").unwrap();
+        assert!(body.contains("[old]"));
+        assert!(body.chars().count() <= 32);
+
+        // Strings
+        let computed = surf.read_highlighted_string(&index_name, "title:river", &Highlight::default(), None, None).unwrap().unwrap();
+        assert_eq!(computed.len(), 1);
+        let document: OldMan = serde_json::from_str(computed[0].resolve_document()).unwrap();
+        assert_eq!(document, river);
+        assert_eq!(computed[0].resolve_snippets().get("title").unwrap(), "Across the <b>River</b>");
+
+        // Invalid
+        let highlight = Highlight::new(Some(vec!["missing".to_string()]), "[", "]", 20, false);
+        assert!(surf.read_highlighted_structs::<OldMan>(&index_name, "old", &highlight, None, None).is_err());
+        let computed = surf.read_highlighted_structs::<OldMan>("missing", "old", &Highlight::default(), None, None).unwrap();
         assert!(computed.is_none());
+
+        // Clean-up
+        let path = surf.which_index(&index_name).unwrap();
         let _ = remove_dir_all(&path);
         let _ = remove_dir_all(&home);
     }
 
     #[test]
-    fn validate_surfer_schema() {
-        let data = Dummy {
-            x: "X".to_owned(),
-            y: "Y".to_owned(),
-            z: 100u64,
-        };
-
-        let data = utils::as_value(&data).unwrap();
-        let (schema, mappings) = utils::to_schema(&data, None).unwrap();
-        let surf_schema = SurferSchema::new(schema, mappings, false, false);
+    fn validate_scored_hits() {
+        let home = ".validate_scored_hits".to_string();
+        let index_name = random_string(None);
 
-        let mut computed1 = SurferBuilder::default();
-        computed1.add_schema("dummy".to_string(), surf_schema.clone());
+        let mut builder = SurferBuilder::default();
+        builder.set_home(&home);
+        let data = OldMan::default();
+        builder.add_struct(index_name.clone(), &data);
+        let mut surf = Surf::try_from(builder).unwrap();
 
-        let mut computed2 = SurferBuilder::default();
-        computed2.add_struct("dummy".to_string(), &data);
+        let sea = OldMan {
+            title: "The Old Man and the Sea".to_string(),
+            body: "He was an old man who fished alone".to_string(),
+        };
+        let river = OldMan {
+            title: "Across the River".to_string(),
+            body: "An old soldier".to_string(),
+        };
+        surf.insert(&index_name, &[sea.clone(), river.clone()]).unwrap();
+
+        // Full text, best hit first
+        let computed = surf.read_scored_structs::<OldMan>(&index_name, "old", None, None).unwrap().unwrap();
+        assert_eq!(computed.len(), 2);
+        assert_eq!(computed[0].resolve_document(), &sea);
+        assert!(computed[0].resolve_score() >= computed[1].resolve_score());
+        assert_ne!(computed[0].resolve_reference(), computed[1].resolve_reference());
+
+        // References resolve back to the document
+        let reference = computed[1].resolve_reference().to_string();
+        let reference = SegmentDocRef::try_from(reference.as_str()).unwrap();
+        let top = computed[0].resolve_score();
+        let computed = surf.read_struct_by_reference::<OldMan>(&index_name, &reference).unwrap();
+        assert_eq!(computed, Some(river.clone()));
+        let stale = SegmentDocRef::new(reference.resolve_segment().clone(), 99);
+        let computed = surf.read_struct_by_reference::<OldMan>(&index_name, &stale).unwrap();
+        assert!(computed.is_none());
 
-        assert_eq!(computed1, computed2);
+        // Cutoff
+        let computed = surf.read_scored_structs::<OldMan>(&index_name, "old", None, Some(top)).unwrap().unwrap();
+        assert_eq!(computed.len(), 1);
 
-        assert_eq!(format!("{:?}", computed1.schemas), format!("{:?}", computed2.schemas))
-    }
+        // Conditions
+        let conditions = vec![OrCondition::new(vec![AndCondition::new("title".to_string(), "river".to_string())])];
+        let computed = surf.apply_scored::<OldMan>(&index_name, &conditions, None, Some(0f32)).unwrap().unwrap();
+        assert_eq!(computed.len(), 1);
+        assert_eq!(computed[0].resolve_document(), &river);
+        assert!(computed[0].resolve_score() > 0f32);
 
-    // Main struct
-    #[derive(Serialize, Debug, Deserialize, PartialEq, PartialOrd, Clone)]
-    struct UserInfo {
-        first: String,
-        last: String,
-        age: u8,
-    }
+        // Deleted documents don't resolve
+        surf.delete_structs_by_field(&index_name, "title", "river").unwrap();
+        let computed = surf.read_struct_by_reference::<OldMan>(&index_name, &reference).unwrap();
+        assert!(computed.is_none());
+        assert!(surf.explain(&index_name, "old", &reference).unwrap().is_none());
+        let computed = surf.more_like_this::<OldMan>(&index_name, &reference, &MoreLikeThis::default(), None).unwrap();
+        assert!(computed.is_none());
 
-    impl UserInfo {
-        pub fn new(first: String, last: String, age: u8) -> Self {
-            Self {
-                first,
-                last,
-                age,
-            }
-        }
-    }
+        // Missing
+        let computed = surf.read_scored_structs::<OldMan>("missing", "old", None, None).unwrap();
+        assert!(computed.is_none());
 
-    impl Default for UserInfo {
-        fn default() -> Self {
-            let first = "".to_string();
-            let last = "".to_string();
-            let age = 0u8;
-            UserInfo::new(first, last, age)
-        }
+        // Clean-up
+        let path = surf.which_index(&index_name).unwrap();
+        let _ = remove_dir_all(&path);
+        let _ = remove_dir_all(&home);
     }
 
     #[test]
-    fn test_user_info() {
-        // Specify home location for indexes
-        let home = ".test_user_info".to_string();
-        // Specify index name
-        let index_name = "store".to_string();
+    fn validate_field_boosts() {
+        let home = ".validate_field_boosts".to_string();
+        let index_name = random_string(None);
 
-        // Prepare builder
         let mut builder = SurferBuilder::default();
         builder.set_home(&home);
-
-        let data = UserInfo::default();
+        let data = OldMan::default();
         builder.add_struct(index_name.clone(), &data);
+        let mut surf = Surf::try_from(builder).unwrap();
 
-        // Prepare Surfer
-        let mut surfer = Surfer::try_from(builder).unwrap();
+        let in_title = OldMan {
+            title: "rust".to_string(),
+            body: "pasta".to_string(),
+        };
+        let in_body = OldMan {
+            title: "pasta".to_string(),
+            body: "rust".to_string(),
+        };
+        surf.insert(&index_name, &[in_title.clone(), in_body.clone()]).unwrap();
+
+        // Schema boost
+        surf.set_field_boost(&index_name, "body", 5f32).unwrap();
+        let computed = surf.read_scored_structs::<OldMan>(&index_name, "rust", None, None).unwrap().unwrap();
+        assert_eq!(computed.len(), 2);
+        assert_eq!(computed[0].resolve_document(), &in_body);
+        assert!(computed[0].resolve_score() > computed[1].resolve_score());
+
+        // Per call boosts take over
+        let mut boosts = HashMap::new();
+        boosts.insert("title".to_string(), 10f32);
+        let computed = surf.read_boosted_structs::<OldMan>(&index_name, "rust", &boosts, None, None).unwrap().unwrap();
+        assert_eq!(computed, vec![in_title.clone(), in_body.clone()]);
+
+        // Conditions
+        let conditions = vec![
+            OrCondition::new(vec![AndCondition::new("title".to_string(), "rust".to_string())]),
+            OrCondition::new(vec![AndCondition::new("body".to_string(), "rust".to_string())]),
+        ];
+        let computed = surf.apply_scored::<OldMan>(&index_name, &conditions, None, Some(0f32)).unwrap().unwrap();
+        assert_eq!(computed[0].resolve_document(), &in_body);
+        let computed = surf.apply_boosted::<OldMan>(&index_name, &conditions, &boosts, None, Some(0f32)).unwrap().unwrap();
+        assert_eq!(computed, vec![in_title.clone(), in_body.clone()]);
+
+        // Default fields
+        surf.set_default_fields(&index_name, &["title".to_string()]).unwrap();
+        let computed = surf.read_structs::<OldMan>(&index_name, "rust", None, None).unwrap().unwrap();
+        assert_eq!(computed, vec![in_title.clone()]);
+        let computed = surf.read_structs::<OldMan>(&index_name, "body:rust", None, None).unwrap().unwrap();
+        assert_eq!(computed, vec![in_body.clone()]);
+
+        // Invalid
+        assert!(surf.set_field_boost(&index_name, "missing", 2f32).is_err());
+        assert!(surf.set_field_boost(&index_name, "title", -1f32).is_err());
+        assert!(surf.set_default_fields(&index_name, &[]).is_err());
+        assert!(surf.set_default_fields(&index_name, &["missing".to_string()]).is_err());
+        assert!(surf.set_field_boost("missing", "title", 2f32).is_err());
+        boosts.insert("missing".to_string(), 2f32);
+        assert!(surf.read_boosted_structs::<OldMan>(&index_name, "rust", &boosts, None, None).is_err());
 
-        // Prepare data to insert & search
+        // Clean-up
+        let path = surf.which_index(&index_name).unwrap();
+        let _ = remove_dir_all(&path);
+        let _ = remove_dir_all(&home);
+    }
 
-        // User 1: John Doe
-        let first = "John".to_string();
-        let last = "Doe".to_string();
-        let age = 20u8;
-        let john_doe = UserInfo::new(first, last, age);
+    #[test]
+    fn validate_federated_reads() {
+        let home = ".validate_federated_reads".to_string();
+        let east = random_string(None);
+        let west = random_string(None);
+        let slow = random_string(None);
 
-        // User 2: Jane Doe
-        let first = "Jane".to_string();
-        let last = "Doe".to_string();
-        let age = 18u8;
-        let jane_doe = UserInfo::new(first, last, age);
+        let mut builder = SurferBuilder::default();
+        builder.set_home(&home);
+        let data = Visit::default();
+        builder.add_struct_with_control(east.clone(), &data, &visit_control(&["at"])).unwrap();
+        builder.add_struct_with_control(west.clone(), &data, &visit_control(&["at"])).unwrap();
+        builder.add_struct(slow.clone(), &data);
+        let mut surf = Surf::try_from(builder).unwrap();
+
+        let first = Visit { page: "home".to_string(), load: 1.0, at: 40 };
+        let second = Visit { page: "home".to_string(), load: 1.0, at: 30 };
+        let third = Visit { page: "home".to_string(), load: 1.0, at: 10 };
+        let about = Visit { page: "about".to_string(), load: 1.0, at: 20 };
+        surf.insert(&east, &[first.clone(), third.clone(), about.clone()]).unwrap();
+        surf.insert(&west, &[second.clone(), about.clone()]).unwrap();
+        let names = vec![east.clone(), west.clone()];
+
+        // By score
+        let page = surf.read_federated_structs::<Visit>(&names, "home", &Paging::default(), None).unwrap().unwrap();
+        assert_eq!(page.resolve_total(), 3);
+        let hits = page.resolve_documents();
+        assert_eq!(hits.len(), 3);
+        assert!(hits.windows(2).all(|pair| pair[0].resolve_score() >= pair[1].resolve_score()));
+        let tags: Vec<&String> = hits.iter().map(|hit| hit.resolve_index_name()).collect();
+        assert_eq!(tags.iter().filter(|tag| **tag == &east).count(), 2);
+        assert_eq!(tags.iter().filter(|tag| **tag == &west).count(), 1);
+        let page = surf.read_federated_structs::<Visit>(&names, "home", &Paging::new(1, 1), None).unwrap().unwrap();
+        assert_eq!(page.resolve_total(), 3);
+        assert_eq!(page.resolve_documents().len(), 1);
+        assert!(page.resolve_cursor().is_none());
+        let json = serde_json::to_value(&page.resolve_documents()[0]).unwrap();
+        assert!(json["index_name"].is_string());
+
+        // By sort key
+        let sort = Sort::descending("at");
+        let page = surf.read_federated_sorted_structs::<Visit>(&names, "home", &sort, &Paging::new(2, 1), None).unwrap().unwrap();
+        assert_eq!(page.resolve_total(), 3);
+        let computed: Vec<(&String, &Visit)> = page.resolve_documents().iter().map(|hit| (hit.resolve_index_name(), hit.resolve_document())).collect();
+        assert_eq!(computed, vec![(&west, &second), (&east, &third)]);
+        let page = surf.read_federated_sorted_structs::<Visit>(&names, "home", &sort, &Paging::default(), Some(0f32)).unwrap().unwrap();
+        assert_eq!(page.resolve_total(), 3);
+        let page = surf.read_federated_sorted_structs::<Visit>(&names, "home", &sort, &Paging::default(), Some(f32::MAX)).unwrap().unwrap();
+        assert_eq!(page.resolve_total(), 0);
+        assert!(page.resolve_documents().is_empty());
+
+        // Conditions
+        let conditions = vec![OrCondition::new(vec![AndCondition::new("page".to_string(), "home".to_string())])];
+        let page = surf.apply_federated::<Visit>(&names, &conditions, &Paging::default(), Some(0f32)).unwrap();
+        assert_eq!(page.resolve_total(), 3);
+        assert_eq!(page.resolve_documents().len(), 3);
+        let page = surf.apply_federated_sorted::<Visit>(&names, &conditions, &Sort::ascending("at"), &Paging::default(), Some(0f32)).unwrap();
+        let computed: Vec<Visit> = page.into_documents().into_iter().map(|hit| hit.into_document()).collect();
+        assert_eq!(computed, vec![third, second, first]);
+
+        // Invalid
+        let missing = vec![east.clone(), "missing".to_string()];
+        assert!(surf.read_federated_structs::<Visit>(&missing, "home", &Paging::default(), None).unwrap().is_none());
+        assert!(surf.apply_federated::<Visit>(&missing, &conditions, &Paging::default(), Some(0f32)).is_err());
+        let twice = vec![east.clone(), east.clone()];
+        assert!(surf.read_federated_structs::<Visit>(&twice, "home", &Paging::default(), None).is_err());
+        let cursor = Paging::after(10, Cursor::new(1f32, SegmentDocRef::new("0123456789abcdef0123456789abcdef".to_string(), 0)));
+        assert!(surf.read_federated_structs::<Visit>(&names, "home", &cursor, None).is_err());
+        let unsorted = vec![east.clone(), slow.clone()];
+        assert!(surf.read_federated_sorted_structs::<Visit>(&unsorted, "home", &sort, &Paging::default(), None).is_err());
 
-        // User 3: Jonny Doe
-        let first = "Jonny".to_string();
-        let last = "Doe".to_string();
-        let age = 10u8;
-        let jonny_doe = UserInfo::new(first, last, age);
+        // Clean-up
+        for name in &[east, west, slow] {
+            if let Some(path) = surf.which_index(name) {
+                let _ = remove_dir_all(&path);
+            };
+        };
+        let _ = remove_dir_all(&home);
+    }
 
-        // User 4: Jinny Doe
-        let first = "Jinny".to_string();
-        let last = "Doe".to_string();
-        let age = 10u8;
-        let jinny_doe = UserInfo::new(first, last, age);
+    #[test]
+    fn validate_more_like_this() {
+        let home = ".validate_more_like_this".to_string();
+        let index_name = random_string(None);
 
-        // Writing structs
+        let mut builder = SurferBuilder::default();
+        builder.set_home(&home);
+        let data = OldMan::default();
+        builder.add_struct(index_name.clone(), &data);
+        let mut surf = Surf::try_from(builder).unwrap();
 
-        // Option 1: One struct at a time
-        let _ = surfer.insert_struct(&index_name, &john_doe).unwrap();
-        let _ = surfer.insert_struct(&index_name, &jane_doe).unwrap();
+        let jam = OldMan {
+            title: "Printer jams paper".to_string(),
+            body: "The office printer keeps jamming paper".to_string(),
+        };
+        let toner = OldMan {
+            title: "Printer out of toner".to_string(),
+            body: "Office printer toner is empty".to_string(),
+        };
+        let password = OldMan {
+            title: "Password reset".to_string(),
+            body: "Cannot reset my email password".to_string(),
+        };
+        let sync = OldMan {
+            title: "Email not syncing".to_string(),
+            body: "Email on the phone is not syncing".to_string(),
+        };
+        surf.insert(&index_name, &[jam.clone(), toner.clone(), password.clone(), sync.clone()]).unwrap();
+
+        // By reference, source left out
+        let source = surf.read_scored_structs::<OldMan>(&index_name, "jams", None, None).unwrap().unwrap();
+        let reference = source[0].resolve_reference().clone();
+        let options = MoreLikeThis::default();
+        let computed = surf.more_like_this::<OldMan>(&index_name, &reference, &options, None).unwrap().unwrap();
+        assert!(!computed.is_empty());
+        assert_eq!(computed[0].resolve_document(), &toner);
+        assert!(computed.iter().all(|hit| hit.resolve_reference() != &reference));
+        assert!(computed.windows(2).all(|pair| pair[0].resolve_score() >= pair[1].resolve_score()));
+        let computed = surf.more_like_this::<OldMan>(&index_name, &reference, &options, Some(1)).unwrap().unwrap();
+        assert_eq!(computed.len(), 1);
+
+        // By struct
+        let computed = surf.more_like_this_struct(&index_name, &password, &options, None).unwrap().unwrap();
+        assert_eq!(computed[0].resolve_document(), &sync);
+        assert!(computed.iter().all(|hit| hit.resolve_document() != &password));
+        let ticket = OldMan {
+            title: "Toner".to_string(),
+            body: "Where do I order toner".to_string(),
+        };
+        let computed = surf.more_like_this_struct(&index_name, &ticket, &options, None).unwrap().unwrap();
+        assert_eq!(computed[0].resolve_document(), &toner);
+
+        // Options
+        let titles = MoreLikeThis::new(Some(vec!["title".to_string()]), 25, 1, 1, 2);
+        let computed = surf.more_like_this_struct(&index_name, &password, &titles, None).unwrap().unwrap();
+        assert!(computed.is_empty());
+        let frequent = MoreLikeThis::new(None, 25, 1, 2, 2);
+        let computed = surf.more_like_this_struct(&index_name, &ticket, &frequent, None).unwrap().unwrap();
+        assert!(computed.is_empty());
+
+        // Invalid
+        let invalid = MoreLikeThis::new(Some(vec!["missing".to_string()]), 25, 1, 1, 2);
+        assert!(surf.more_like_this::<OldMan>(&index_name, &reference, &invalid, None).is_err());
+        let stale = SegmentDocRef::new(reference.resolve_segment().clone(), 99);
+        assert!(surf.more_like_this::<OldMan>(&index_name, &stale, &options, None).unwrap().is_none());
+        assert!(surf.more_like_this::<OldMan>("missing", &reference, &options, None).unwrap().is_none());
+        assert!(surf.more_like_this_struct("missing", &ticket, &options, None).unwrap().is_none());
 
-        // Option 2: Write all structs together
-        let users = vec![jonny_doe.clone(), jinny_doe.clone()];
-        let _ = surfer.insert_structs(&index_name, &users).unwrap();
+        // Clean-up
+        let path = surf.which_index(&index_name).unwrap();
+        let _ = remove_dir_all(&path);
+        let _ = remove_dir_all(&home);
+    }
 
-        block_thread(1);
+    #[test]
+    fn validate_normalized_scores() {
+        let home = ".validate_normalized_scores".to_string();
+        let index_name = random_string(None);
 
-        // Reading structs
+        let mut builder = SurferBuilder::default();
+        builder.set_home(&home);
+        let data = OldMan::default();
+        builder.add_struct(index_name.clone(), &data);
+        let mut surf = Surf::try_from(builder).unwrap();
 
-        // Option 1: Full text search
-        let expected = vec![john_doe.clone()];
-        let computed = surfer.read_all_structs::<UserInfo>(&index_name, "John").unwrap().unwrap();
-        assert_eq!(expected, computed);
+        let sea = OldMan {
+            title: "The Old Man and the Sea".to_string(),
+            body: "An old man".to_string(),
+        };
+        let river = OldMan {
+            title: "Across the River".to_string(),
+            body: "An old soldier remembers the war".to_string(),
+        };
+        let bell = OldMan {
+            title: "For Whom the Bell Tolls".to_string(),
+            body: "An old guerrilla fighter and a young dynamiter in the mountains of Spain".to_string(),
+        };
+        surf.insert(&index_name, &[sea.clone(), river, bell]).unwrap();
+
+        // Raw scores as before
+        let raw = surf.read_scored_structs::<OldMan>(&index_name, "old", None, None).unwrap().unwrap();
+        assert_eq!(raw.len(), 3);
+        let computed = surf.read_normalized_structs::<OldMan>(&index_name, "old", None, &Cutoff::Raw(0f32)).unwrap().unwrap();
+        let scores: Vec<f32> = computed.iter().map(|hit| hit.resolve_score()).collect();
+        assert_eq!(scores, raw.iter().map(|hit| hit.resolve_score()).collect::<Vec<f32>>());
+
+        // Relative to the top hit
+        let computed = surf.read_normalized_structs::<OldMan>(&index_name, "old", None, &Cutoff::Top(0f32)).unwrap().unwrap();
+        assert_eq!(computed.len(), 3);
+        assert_eq!(computed[0].resolve_score(), 1f32);
+        assert_eq!(computed[0].resolve_document(), &sea);
+        assert!(computed.iter().all(|hit| hit.resolve_score() > 0f32 && hit.resolve_score() <= 1f32));
+        let computed = surf.read_normalized_structs::<OldMan>(&index_name, "old", None, &Cutoff::Top(1f32)).unwrap().unwrap();
+        assert_eq!(computed.len(), 1);
+
+        // Min-max
+        let computed = surf.read_normalized_structs::<OldMan>(&index_name, "old", None, &Cutoff::MinMax(0f32)).unwrap().unwrap();
+        assert_eq!(computed[0].resolve_score(), 1f32);
+        assert_eq!(computed[2].resolve_score(), 0f32);
+        let middle = computed[1].resolve_score();
+        let computed = surf.read_normalized_structs::<OldMan>(&index_name, "old", None, &Cutoff::MinMax(middle)).unwrap().unwrap();
+        assert_eq!(computed.len(), 2);
+
+        // Conditions
+        let conditions = vec![OrCondition::new(vec![AndCondition::new("body".to_string(), "old".to_string())])];
+        let computed = surf.apply_normalized::<OldMan>(&index_name, &conditions, None, &Cutoff::Top(1f32)).unwrap().unwrap();
+        assert_eq!(computed.len(), 1);
+        assert_eq!(computed[0].resolve_document(), &sea);
+        let computed = surf.apply_normalized::<OldMan>(&index_name, &conditions, None, &Cutoff::Raw(90f32)).unwrap().unwrap();
+        assert!(computed.is_empty());
+        let expression = Expression::from(conditions.as_slice());
+        let computed = surf.apply_expression_normalized::<OldMan>(&index_name, &expression, None, &Cutoff::MinMax(0f32)).unwrap().unwrap();
+        assert_eq!(computed.len(), 3);
+
+        // Invalid
+        assert!(surf.read_normalized_structs::<OldMan>(&index_name, "old", None, &Cutoff::Top(90f32)).is_err());
+        assert!(surf.apply_normalized::<OldMan>(&index_name, &conditions, None, &Cutoff::MinMax(-1f32)).is_err());
+        assert!(surf.read_normalized_structs::<OldMan>("missing", "old", None, &Cutoff::Top(0.5)).unwrap().is_none());
 
-        let mut expected = vec![john_doe.clone(), jane_doe.clone(), jonny_doe.clone(), jinny_doe.clone()];
-        expected.sort();
-        let mut computed = surfer.read_all_structs::<UserInfo>(&index_name, "doe").unwrap().unwrap();
-        computed.sort();
-        assert_eq!(expected, computed);
+        // Clean-up
+        let path = surf.which_index(&index_name).unwrap();
+        let _ = remove_dir_all(&path);
+        let _ = remove_dir_all(&home);
+    }
 
-        // Option 2: Term search
-        let mut expected = vec![jonny_doe.clone(), jinny_doe.clone()];
-        expected.sort();
-        let mut computed = surfer.read_all_structs_by_field::<UserInfo>(&index_name, "age", "10").unwrap().unwrap();
-        computed.sort();
-        assert_eq!(expected, computed);
+    #[test]
+    fn validate_counts() {
+        let home = ".validate_counts".to_string();
+        let index_name = random_string(None);
 
-        // Delete structs
+        let mut builder = SurferBuilder::default();
+        builder.set_home(&home);
+        let data = Giant::default();
+        builder.add_struct(index_name.clone(), &data);
+        let mut surf = Surf::try_from(builder).unwrap();
+
+        let giants: Vec<Giant> = (0..25u64)
+            .map(|i| Giant {
+                a: if i % 5 == 0 { "fee".to_string() } else { "fum".to_string() },
+                c: i,
+                ..Giant::default()
+            })
+            .collect();
+        surf.insert(&index_name, &giants).unwrap();
+
+        // Beyond the default limit of 10
+        assert_eq!(surf.count(&index_name, "fum").unwrap(), Some(20));
+        assert_eq!(surf.count(&index_name, "a:fee").unwrap(), Some(5));
+        assert_eq!(surf.count(&index_name, "giant").unwrap(), Some(0));
+        assert_eq!(surf.count("missing", "fum").unwrap(), None);
+
+        // Conditions
+        let conditions = vec![OrCondition::new(vec![AndCondition::new("a".to_string(), "fum".to_string())])];
+        assert_eq!(surf.count_where(&index_name, &conditions).unwrap(), 20);
+        let conditions = vec![
+            OrCondition::new(vec![AndCondition::gte("c", "20")]),
+            OrCondition::new(vec![AndCondition::new("a".to_string(), "fee".to_string()), AndCondition::lt("c", "10")]),
+        ];
+        assert_eq!(surf.count_where(&index_name, &conditions).unwrap(), 7);
+        assert!(surf.count_where("missing", &conditions).is_err());
 
-        // Option 1: Delete based on all text fields
-        // Before delete
-        let before = surfer.read_all_structs::<UserInfo>(&index_name, "doe").unwrap().unwrap();
-        let before: HashSet<UserInfo> = HashSet::from_iter(before.into_iter());
+        // Clean-up
+        let path = surf.which_index(&index_name).unwrap();
+        let _ = remove_dir_all(&path);
+        let _ = remove_dir_all(&home);
+    }
 
-        // Delete any occurrence of John (Actual call to delete)
-        surfer.delete_structs(&index_name, "john").unwrap();
+    #[test]
+    fn validate_aggregations() {
+        let home = ".validate_aggregations".to_string();
+        let index_name = random_string(None);
 
-        // After delete
-        let after = surfer.read_all_structs::<UserInfo>(&index_name, "doe").unwrap().unwrap();
-        let after: HashSet<UserInfo> = HashSet::from_iter(after.into_iter());
-        // Check difference
-        let computed: Vec<UserInfo> = before.difference(&after).map(|e| e.clone()).collect();
-        // Only John should be deleted
-        let expected = vec![john_doe];
-        assert_eq!(expected, computed);
+        let mut builder = SurferBuilder::default();
+        builder.set_home(&home);
+        let data = Visit::default();
+        builder.add_struct_with_control(index_name.clone(), &data, &visit_control(&["load", "at"])).unwrap();
+        let mut surf = Surf::try_from(builder).unwrap();
+
+        let hour = 60 * 60;
+        let day = 24 * hour;
+        let visits = vec![
+            Visit { page: "home".to_string(), load: 0.5, at: day + 10 },
+            Visit { page: "home".to_string(), load: 1.5, at: day + hour + 10 },
+            Visit { page: "about".to_string(), load: 2.5, at: 2 * day },
+            Visit { page: "home".to_string(), load: 1.2, at: 3 * day + 5 },
+            Visit { page: "contact".to_string(), load: 0.1, at: -10 },
+        ];
+        surf.insert(&index_name, &visits).unwrap();
 
-        // Option 2: Delete based on a specific field
-        // Before delete
-        let before = surfer.read_all_structs_by_field::<UserInfo>(&index_name, "age", "10").unwrap().unwrap();
-        let before: HashSet<UserInfo> = HashSet::from_iter(before.into_iter());
+        let aggregations = [
+            Aggregation::terms("page", 2),
+            Aggregation::histogram("load", 1.0),
+            Aggregation::date_histogram("at", DateInterval::Day),
+        ];
+        let computed = surf.aggregate_expression(&index_name, &Expression::all(), &aggregations).unwrap();
+        assert_eq!(computed.len(), 3);
+
+        // Top terms by count, then term
+        let terms = computed[0].resolve_buckets();
+        assert_eq!(computed[0].resolve_field_name(), "page");
+        assert_eq!(terms, &vec![
+            Bucket::new(BucketKey::Text("home".to_string()), 3),
+            Bucket::new(BucketKey::Text("about".to_string()), 1),
+        ]);
+
+        // Fixed width buckets, empty ones left out
+        assert_eq!(computed[1].resolve_buckets(), &vec![
+            Bucket::new(BucketKey::F64(0.0), 2),
+            Bucket::new(BucketKey::F64(1.0), 2),
+            Bucket::new(BucketKey::F64(2.0), 1),
+        ]);
+
+        // Days, before the epoch too
+        assert_eq!(computed[2].resolve_buckets(), &vec![
+            Bucket::new(BucketKey::I64(-day), 1),
+            Bucket::new(BucketKey::I64(day), 2),
+            Bucket::new(BucketKey::I64(2 * day), 1),
+            Bucket::new(BucketKey::I64(3 * day), 1),
+        ]);
+        let json = serde_json::to_value(&computed[2]).unwrap();
+        assert_eq!(json["buckets"][0]["key"], -day);
+        assert_eq!(json["buckets"][0]["count"], 1);
+
+        // Alongside a query
+        let aggregations = [Aggregation::date_histogram("at", DateInterval::Hour), Aggregation::terms("load", 10)];
+        let computed = surf.aggregate(&index_name, "page:home", &aggregations).unwrap().unwrap();
+        assert_eq!(computed[0].resolve_buckets().len(), 3);
+        assert_eq!(computed[0].resolve_buckets()[1], Bucket::new(BucketKey::I64(day + hour), 1));
+        assert_eq!(computed[1].resolve_buckets(), &vec![
+            Bucket::new(BucketKey::F64(0.5), 1),
+            Bucket::new(BucketKey::F64(1.2), 1),
+            Bucket::new(BucketKey::F64(1.5), 1),
+        ]);
+        let computed = surf.read_aggregated_structs::<Visit>(&index_name, "page:home", &[Aggregation::terms("page", 10)], Some(1), None).unwrap().unwrap();
+        assert_eq!(computed.resolve_documents().len(), 1);
+        assert_eq!(computed.resolve_aggregations()[0].resolve_buckets(), &vec![Bucket::new(BucketKey::Text("home".to_string()), 3)]);
+
+        // Invalid
+        assert!(surf.aggregate(&index_name, "home", &[Aggregation::histogram("page", 1.0)]).is_err());
+        assert!(surf.aggregate(&index_name, "home", &[Aggregation::histogram("load", 0.0)]).is_err());
+        assert!(surf.aggregate(&index_name, "home", &[Aggregation::date_histogram("load", DateInterval::Day)]).is_err());
+        assert!(surf.aggregate(&index_name, "home", &[Aggregation::terms("missing", 1)]).is_err());
+        assert!(surf.aggregate("missing", "home", &[Aggregation::terms("page", 1)]).unwrap().is_none());
 
-        // Delete any occurrence where age = 10 (Actual call to delete)
-        surfer.delete_structs_by_field(&index_name, "age", "10").unwrap();
+        // Clean-up
+        let path = surf.which_index(&index_name).unwrap();
+        let _ = remove_dir_all(&path);
+        let _ = remove_dir_all(&home);
+    }
 
-        // After delete
-        let after = surfer.read_all_structs_by_field::<UserInfo>(&index_name, "age", "10").unwrap().unwrap();
-        let after: HashSet<UserInfo> = HashSet::from_iter(after.into_iter());
-        // Check difference
-        let mut computed: Vec<UserInfo> = before.difference(&after).map(|e| e.clone()).collect();
-        computed.sort();
-        // Both Jonny & Jinny should be deleted
-        let mut expected = vec![jonny_doe, jinny_doe];
-        expected.sort();
-        assert_eq!(expected, computed);
+    #[test]
+    fn validate_stats() {
+        let home = ".validate_stats".to_string();
+        let index_name = random_string(None);
 
+        let mut builder = SurferBuilder::default();
+        builder.set_home(&home);
+        let data = Visit::default();
+        builder.add_struct_with_control(index_name.clone(), &data, &visit_control(&["load"])).unwrap();
+        let mut surf = Surf::try_from(builder).unwrap();
+
+        let visits = vec![
+            Visit { page: "home".to_string(), load: 0.5, at: 40 },
+            Visit { page: "home".to_string(), load: 1.5, at: 10 },
+            Visit { page: "home".to_string(), load: 1.0, at: 30 },
+            Visit { page: "home".to_string(), load: 1.0, at: 20 },
+            Visit { page: "about".to_string(), load: 9.0, at: -10 },
+        ];
+        surf.insert(&index_name, &visits).unwrap();
+
+        // Fast field
+        let computed = surf.stats(&index_name, "page:home", "load", &[50.0, 100.0]).unwrap().unwrap();
+        assert_eq!(computed.resolve_field_name(), "load");
+        assert_eq!(computed.resolve_count(), 4);
+        assert_eq!(computed.resolve_min(), Some(0.5));
+        assert_eq!(computed.resolve_max(), Some(1.5));
+        assert_eq!(computed.resolve_sum(), 4.0);
+        assert_eq!(computed.resolve_mean(), Some(1.0));
+        assert_eq!(computed.resolve_percentiles(), &vec![Percentile::new(50.0, 1.0), Percentile::new(100.0, 1.5)]);
+        let json = serde_json::to_value(&computed).unwrap();
+        assert_eq!(json["mean"], 1.0);
+        assert_eq!(json["percentiles"][1]["value"], 1.5);
+
+        // Term dictionary of a field without a fast field
+        let conditions = vec![OrCondition::new(vec![AndCondition::new("page".to_string(), "home".to_string())])];
+        let expression = Expression::from(conditions.as_slice());
+        let computed = surf.stats_expression(&index_name, &expression, "at", &[25.0]).unwrap();
+        assert_eq!(computed.resolve_count(), 4);
+        assert_eq!(computed.resolve_min(), Some(10.0));
+        assert_eq!(computed.resolve_max(), Some(40.0));
+        assert_eq!(computed.resolve_mean(), Some(25.0));
+        assert_eq!(computed.resolve_percentiles(), &vec![Percentile::new(25.0, 17.5)]);
+
+        // Nothing matched
+        let computed = surf.stats(&index_name, "page:missing", "load", &[50.0]).unwrap().unwrap();
+        assert_eq!(computed.resolve_count(), 0);
+        assert_eq!(computed.resolve_mean(), None);
+        assert!(computed.resolve_percentiles().is_empty());
+
+        // Invalid
+        assert!(surf.stats(&index_name, "home", "page", &[]).is_err());
+        assert!(surf.stats(&index_name, "home", "load", &[101.0]).is_err());
+        assert!(surf.stats(&index_name, "home", "missing", &[]).is_err());
+        assert!(surf.stats("missing", "home", "load", &[]).unwrap().is_none());
 
         // Clean-up
-        let path = surfer.which_index(&index_name).unwrap();
+        let path = surf.which_index(&index_name).unwrap();
         let _ = remove_dir_all(&path);
         let _ = remove_dir_all(&home);
     }
 
     #[test]
-    fn test_where_clause() {
-        // Specify home location for indexes
-        let home = ".test_where_clause".to_string();
-        // Specify index name
-        let index_name = "store".to_string();
+    fn validate_projection() {
+        let home = ".validate_projection".to_string();
+        let index_name = random_string(None);
 
-        // Prepare builder
         let mut builder = SurferBuilder::default();
         builder.set_home(&home);
-
-        let data = UserInfo::default();
+        let data = Visit::default();
         builder.add_struct(index_name.clone(), &data);
+        let mut surf = Surf::try_from(builder).unwrap();
 
-        // Prepare Surfer
-        let surfer = Surfer::try_from(builder).unwrap();
-        let mut surf = Surf::from(surfer);
-        let surfer = &mut surf;
+        let visit = Visit { page: "home".to_string(), load: 0.5, at: 40 };
+        surf.insert(&index_name, &[visit]).unwrap();
 
-        // Prepare data to insert & search
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Page {
+            page: String,
+        }
 
-        // User 1: John Doe
-        let first = "John".to_string();
-        let last = "Doe".to_string();
-        let age = 20u8;
-        let john_doe = UserInfo::new(first, last, age);
+        // Json
+        let fields = vec!["page".to_string(), "at".to_string()];
+        let computed = surf.read_projected_structs::<serde_json::Value>(&index_name, "home", &fields, None, None).unwrap().unwrap();
+        assert_eq!(computed, vec![serde_json::json!({"page": "home", "at": 40})]);
+
+        // Smaller struct
+        let fields = vec!["page".to_string()];
+        let computed = surf.read_projected_structs::<Page>(&index_name, "home", &fields, None, None).unwrap().unwrap();
+        assert_eq!(computed, vec![Page { page: "home".to_string() }]);
+        let computed = surf.read_projected_structs::<serde_json::Value>(&index_name, "home", &[], None, None).unwrap().unwrap();
+        assert_eq!(computed, vec![serde_json::json!({})]);
+
+        // Score cutoff
+        let other = Visit { page: "home about".to_string(), load: 0.5, at: 41 };
+        surf.insert(&index_name, &[other]).unwrap();
+        let hits = surf.read_scored_structs::<serde_json::Value>(&index_name, "home", None, Some(0f32)).unwrap().unwrap();
+        let score = Some((hits[0].resolve_score() + hits[1].resolve_score()) / 2f32);
+        let fields = vec!["at".to_string()];
+        let computed = surf.read_projected_structs::<serde_json::Value>(&index_name, "home", &fields, Some(2), score).unwrap().unwrap();
+        assert_eq!(computed, vec![serde_json::json!({"at": 40})]);
+
+        // Invalid
+        let fields = vec!["missing".to_string()];
+        assert!(surf.read_projected_structs::<serde_json::Value>(&index_name, "home", &fields, None, None).is_err());
+        assert!(surf.read_projected_structs::<serde_json::Value>("missing", "home", &fields, None, None).unwrap().is_none());
 
-        // User 2: Jane Doe
-        let first = "Jane".to_string();
-        let last = "Doe".to_string();
-        let age = 18u8;
-        let jane_doe = UserInfo::new(first, last, age);
+        // Clean-up
+        let path = surf.which_index(&index_name).unwrap();
+        let _ = remove_dir_all(&path);
+        let _ = remove_dir_all(&home);
+    }
 
-        // User 3: Jonny Doe
-        let first = "Jonny".to_string();
-        let last = "Doe".to_string();
-        let age = 10u8;
-        let jonny_doe = UserInfo::new(first, last, age);
+    #[test]
+    fn validate_schemaless_reads() {
+        let home = ".validate_schemaless_reads".to_string();
+        let index_name = format!("accounts{}", random_string(None));
+
+        #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+        struct Account {
+            name: String,
+            active: bool,
+            balance: i64,
+            rate: f64,
+            tag: Vec<u8>,
+        }
 
-        // User 4: Jinny Doe
-        let first = "Jinny".to_string();
-        let last = "Doe".to_string();
-        let age = 10u8;
-        let jinny_doe = UserInfo::new(first, last, age);
+        let mut builder = SurferBuilder::default();
+        builder.set_home(&home);
+        let data = Account::default();
+        builder.add_struct(index_name.clone(), &data);
+        let mut surf = Surf::try_from(builder).unwrap();
+        let mappings = surf.resolve_schema(&index_name).unwrap().resolve_mapping();
+        assert!(mappings.get("active") == Some(&SurferFieldTypes::Bool));
 
-        // Writing structs
+        let alice = Account { name: "Alice".to_string(), active: true, balance: -20, rate: 1.5, tag: vec![1, 2, 3] };
+        let bob = Account { name: "Bob".to_string(), active: false, balance: 10, rate: 0.0, tag: vec![] };
+        surf.insert(&index_name, &[alice.clone(), bob.clone()]).unwrap();
 
-        let users = vec![john_doe.clone(), jane_doe.clone(), jonny_doe.clone(), jinny_doe.clone()];
-        let _ = surfer.insert_structs(&index_name, &users).unwrap();
-        block_thread(1);
+        // Typed json
+        let expected = serde_json::json!({"name": "Alice", "active": true, "balance": -20, "rate": 1.5, "tag": [1, 2, 3]});
+        let computed = surf.read_structs::<serde_json::Value>(&index_name, "alice", None, None).unwrap().unwrap();
+        assert_eq!(computed, vec![expected.clone()]);
+        let computed = surf.read_structs_by_field::<serde_json::Value>(&index_name, "active", "true", None, Some(0f32)).unwrap().unwrap();
+        assert_eq!(computed, vec![expected.clone()]);
 
-        let conditions = vec![OrCondition::from(("age".to_string(), "10".to_string()))];
-        let mut expected = vec![jonny_doe.clone(), jinny_doe.clone()];
-        let mut computed = surfer.select::<UserInfo>(&index_name, &conditions).unwrap().unwrap();
-        expected.sort();
-        computed.sort();
-        assert_eq!(expected, computed);
+        // Conditions
+        let conditions = vec![OrCondition::new(vec![AndCondition::new("active".to_string(), "false".to_string())])];
+        let computed = surf.apply::<serde_json::Value>(&index_name, &conditions, None, Some(0f32)).unwrap().unwrap();
+        assert_eq!(computed[0]["active"], false);
+        assert_eq!(computed[0]["tag"], serde_json::json!([]));
+        let conditions = vec![OrCondition::new(vec![AndCondition::lt("balance", "0")])];
+        let computed = surf.apply_expression::<serde_json::Value>(&index_name, &Expression::from(conditions.as_slice()), None, None).unwrap().unwrap();
+        assert_eq!(computed, vec![expected]);
+
+        // Structs round trip booleans & byte arrays
+        let computed = surf.read_structs::<Account>(&index_name, "bob", None, None).unwrap().unwrap();
+        assert_eq!(computed, vec![bob]);
+        let computed = surf.query_structs::<Account>(&format!("SELECT * FROM {} WHERE active = 'true'", index_name)).unwrap().unwrap();
+        assert_eq!(computed.resolve_documents(), &vec![alice.clone()]);
+
+        // Projected byte arrays
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Tag {
+            tag: Vec<u8>,
+        }
+        let fields = vec!["tag".to_string()];
+        let computed = surf.read_projected_structs::<Tag>(&index_name, "alice", &fields, None, None).unwrap().unwrap();
+        assert_eq!(computed, vec![Tag { tag: vec![1, 2, 3] }]);
+
+        // Invalid
+        let conditions = vec![OrCondition::new(vec![AndCondition::new("active".to_string(), "yes".to_string())])];
+        assert!(surf.apply::<serde_json::Value>(&index_name, &conditions, None, None).is_err());
+
+        // Full text reads & deletes search booleans as text
+        let computed = surf.read_structs::<Account>(&index_name, "true", None, None).unwrap().unwrap();
+        assert_eq!(computed, vec![alice.clone()]);
+        surf.delete_structs(&index_name, "yes").unwrap();
+        surf.delete_structs(&index_name, "false").unwrap();
+        let computed = surf.read_structs::<Account>(&index_name, "alice bob", None, None).unwrap().unwrap();
+        assert_eq!(computed, vec![alice]);
 
-        let conditions = vec![OrCondition::from(("age".to_string(), "10".to_string())),
-                              OrCondition::from(("first".to_string(), "john".to_string()))];
-        let mut expected = vec![john_doe.clone(), jonny_doe.clone(), jinny_doe.clone()];
-        let mut computed = surfer.select::<UserInfo>(&index_name, &conditions).unwrap().unwrap();
-        expected.sort();
-        computed.sort();
-        assert_eq!(expected, computed);
+        // Clean-up
+        let path = surf.which_index(&index_name).unwrap();
+        let _ = remove_dir_all(&path);
+        let _ = remove_dir_all(&home);
+    }
 
+    #[test]
+    fn validate_scan() {
+        let home = ".validate_scan".to_string();
+        let index_name = random_string(None);
 
-        let name_condition = AndCondition::new("first".to_string(), "jinny".to_string());
-        let age_condition = AndCondition::new("age".to_string(), "10".to_string());
-        let and_conditions = vec![name_condition, age_condition];
-        let child_condition = OrCondition::new(and_conditions);
-        let parent_condition = OrCondition::from(("first".to_string(), "john".to_string()));
-        let conditions = vec![child_condition, parent_condition];
+        let mut builder = SurferBuilder::default();
+        builder.set_home(&home);
+        let data = Visit::default();
+        builder.add_struct(index_name.clone(), &data);
+        let mut surf = Surf::try_from(builder).unwrap();
+
+        // More than the default limit, over a few segments
+        for batch in 0..3 {
+            let visits: Vec<Visit> = (0..5)
+                .map(|i| Visit { page: format!("page{}", i), load: 1.0, at: batch * 5 + i })
+                .collect();
+            surf.insert(&index_name, &visits).unwrap();
+        };
+        surf.delete(&index_name, "at", "7").unwrap();
+
+        let computed: Vec<Visit> = surf.scan::<Visit>(&index_name).unwrap().unwrap().map(|visit| visit.unwrap()).collect();
+        let mut at: Vec<i64> = computed.iter().map(|visit| visit.at).collect();
+        at.sort_unstable();
+        let expected: Vec<i64> = (0..15).filter(|at| *at != 7).collect();
+        assert_eq!(at, expected);
+
+        // Lazy, other reads go on meanwhile
+        let mut scan = surf.scan::<serde_json::Value>(&index_name).unwrap().unwrap();
+        let first = scan.next().unwrap().unwrap();
+        assert!(first["page"].is_string());
+        assert_eq!(surf.count(&index_name, "page1").unwrap(), Some(3));
+
+        // Commits made meanwhile aren't seen by the scan
+        surf.insert_struct(&index_name, &Visit { page: "page2".to_string(), load: 1.0, at: 7 }).unwrap();
+        surf.delete(&index_name, "at", "3").unwrap();
+        let computed: Vec<i64> = scan.map(|visit| visit.unwrap()["at"].as_i64().unwrap()).collect();
+        assert_eq!(computed.len(), 13);
+        assert!(computed.contains(&3));
+        assert!(!computed.contains(&7));
+
+        // NDJSON
+        let mut buffer = Vec::new();
+        let written = surf.scan::<serde_json::Value>(&index_name).unwrap().unwrap().write_ndjson(&mut buffer).unwrap();
+        assert_eq!(written, 14);
+        let buffer = String::from_utf8(buffer).unwrap();
+        let lines: Vec<&str> = buffer.lines().collect();
+        assert_eq!(lines.len(), 14);
+        let visit: Visit = serde_json::from_str(lines[0]).unwrap();
+        assert_ne!(visit.at, 3);
+
+        // Missing
+        assert!(surf.scan::<Visit>("missing").unwrap().is_none());
 
-        let mut expected = vec![john_doe.clone(), jinny_doe.clone()];
-        let mut computed = surfer.select::<UserInfo>(&index_name, &conditions).unwrap().unwrap();
-        expected.sort();
-        computed.sort();
-        assert_eq!(expected, computed);
+        // Clean-up
+        let path = surf.which_index(&index_name).unwrap();
+        let _ = remove_dir_all(&path);
+        let _ = remove_dir_all(&home);
+    }
+
+    #[test]
+    fn validate_explain_and_debug() {
+        let home = ".validate_explain_and_debug".to_string();
+        let index_name = random_string(None);
+
+        let mut builder = SurferBuilder::default();
+        builder.set_home(&home);
+        let data = OldMan::default();
+        builder.add_struct(index_name.clone(), &data);
+        let mut surf = Surf::try_from(builder).unwrap();
+
+        let sea = OldMan {
+            title: "The Old Man and the Sea".to_string(),
+            body: "He was an old man who fished alone".to_string(),
+        };
+        let river = OldMan {
+            title: "Across the River".to_string(),
+            body: "An old soldier".to_string(),
+        };
+        surf.insert(&index_name, &[sea.clone(), river.clone()]).unwrap();
+
+        // Explanation tree adds up to the score
+        let hits = surf.read_scored_structs::<OldMan>(&index_name, "old", None, None).unwrap().unwrap();
+        let reference = hits[0].resolve_reference();
+        let computed = surf.explain(&index_name, "old", reference).unwrap().unwrap();
+        let value = computed["value"].as_f64().unwrap() as f32;
+        assert!((value - hits[0].resolve_score()).abs() < 1e-4);
+        assert!(computed["description"].is_string());
+        assert!(computed["details"].is_array());
+
+        // Not a match
+        let river_ref = hits[1].resolve_reference();
+        assert!(surf.explain(&index_name, "title:sea", river_ref).is_err());
+
+        // Stale reference & missing index
+        let stale = SegmentDocRef::new(reference.resolve_segment().clone(), 99);
+        assert!(surf.explain(&index_name, "old", &stale).unwrap().is_none());
+        assert!(surf.explain("missing", "old", reference).unwrap().is_none());
+
+        // Dropped by the cutoff
+        let top = hits[0].resolve_score();
+        let computed = surf.read_structs_debug::<OldMan>(&index_name, "old", None, Some(top)).unwrap().unwrap();
+        assert_eq!(computed.resolve_documents(), &vec![sea.clone()]);
+        let dropped = computed.resolve_dropped();
+        assert_eq!(dropped.len(), 1);
+        assert_eq!(dropped[0].resolve_reference(), river_ref);
+        assert!((dropped[0].resolve_margin() - (top - hits[1].resolve_score())).abs() < 1e-4);
+        let computed = surf.read_structs_debug::<OldMan>(&index_name, "old", None, None).unwrap().unwrap();
+        assert_eq!(computed.resolve_documents().len(), 2);
+        assert!(computed.resolve_dropped().is_empty());
+
+        // Default cutoff of conditions
+        let conditions = vec![OrCondition::new(vec![AndCondition::new("title".to_string(), "river".to_string())])];
+        let computed = surf.apply_debug::<OldMan>(&index_name, &conditions, None, None).unwrap().unwrap();
+        assert!(computed.resolve_documents().is_empty());
+        let dropped = computed.resolve_dropped();
+        assert_eq!(dropped.len(), 1);
+        assert_eq!(dropped[0].resolve_cutoff(), 90f32);
+        let computed = surf.explain_apply(&index_name, &conditions, dropped[0].resolve_reference()).unwrap().unwrap();
+        let value = computed["value"].as_f64().unwrap() as f32;
+        assert!((value - dropped[0].resolve_score()).abs() < 1e-4);
 
         // Clean-up
-        let path = surfer.which_index(&index_name).unwrap();
+        let path = surf.which_index(&index_name).unwrap();
         let _ = remove_dir_all(&path);
         let _ = remove_dir_all(&home);
     }
@@ -1655,6 +5557,13 @@ mod library_tests {
         }
     }
 
+    /// Convenience method for sorting & likely not required in user code
+    impl PartialOrd for UserInfo {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
     /// Convenience method for sorting & likely not required in user code
     impl Eq for UserInfo {}
 
@@ -1668,7 +5577,6 @@ mod library_tests {
                 state.write_u8(*i);
             }
             state.write_u8(self.age);
-            state.finish();
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,97 @@
+use tantivy::Score;
+
+use crate::prelude::*;
+
+/// Score cutoff, on raw scores or on scores normalized to 0-1
+///
+/// Raw BM25 scores grow with corpus size & query length, normalized ones don't.
+/// Hits carry the score the cutoff was applied to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Cutoff {
+    /// Raw score as computed by tantivy, same as the score of the other reads
+    Raw(Score),
+    /// Fraction of the score of the top hit
+    Top(f32),
+    /// Fraction of the way from the lowest to the highest score of the hits
+    MinMax(f32),
+}
+
+impl Cutoff {
+    pub fn resolve_cutoff(&self) -> f32 {
+        match self {
+            Cutoff::Raw(cutoff) | Cutoff::Top(cutoff) | Cutoff::MinMax(cutoff) => *cutoff,
+        }
+    }
+    /// Checks fractions are between 0 & 1
+    pub(crate) fn validate(&self) -> Result<(), IndexError> {
+        let cutoff = self.resolve_cutoff();
+        let message = "Invalid score cutoff".to_string();
+        match self {
+            Cutoff::Raw(_) if !cutoff.is_finite() => {
+                let reason = format!("Expected a number: {}", cutoff);
+                Err(IndexError::new(message, reason))
+            }
+            Cutoff::Top(_) | Cutoff::MinMax(_) if !(0f32..=1f32).contains(&cutoff) => {
+                let reason = format!("Expected a fraction between 0 and 1: {}", cutoff);
+                Err(IndexError::new(message, reason))
+            }
+            _ => Ok(()),
+        }
+    }
+    /// Scores normalized & hits below the cutoff left out, best hits first in & out
+    pub(crate) fn apply<T>(&self, top_docs: Vec<(Score, T)>) -> Vec<(Score, T)> {
+        let highest = top_docs.first().map(|(score, _)| *score).unwrap_or(0f32);
+        let lowest = top_docs.last().map(|(score, _)| *score).unwrap_or(0f32);
+        let normalize = |score: Score| -> Score {
+            let (floor, range) = match self {
+                Cutoff::Raw(_) => return score,
+                Cutoff::Top(_) => (0f32, highest),
+                Cutoff::MinMax(_) => (lowest, highest - lowest),
+            };
+            // Every hit scored the same
+            if range <= 0f32 {
+                return 1f32;
+            };
+            (score - floor) / range
+        };
+        let cutoff = self.resolve_cutoff();
+        top_docs
+            .into_iter()
+            .map(|(score, doc)| (normalize(score), doc))
+            .filter(|(score, _)| *score >= cutoff)
+            .collect()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_normalized_cutoffs() {
+        let top_docs = vec![(8f32, 'a'), (4f32, 'b'), (2f32, 'c')];
+        let computed = Cutoff::Raw(3f32).apply(top_docs.clone());
+        assert_eq!(computed, vec![(8f32, 'a'), (4f32, 'b')]);
+        let computed = Cutoff::Top(0.5).apply(top_docs.clone());
+        assert_eq!(computed, vec![(1f32, 'a'), (0.5, 'b')]);
+        let computed = Cutoff::MinMax(0f32).apply(top_docs.clone());
+        assert_eq!(computed, vec![(1f32, 'a'), (1f32 / 3f32, 'b'), (0f32, 'c')]);
+        let computed = Cutoff::MinMax(0.5).apply(top_docs);
+        assert_eq!(computed, vec![(1f32, 'a')]);
+
+        // Ties & single hits
+        let computed = Cutoff::MinMax(1f32).apply(vec![(3f32, 'a'), (3f32, 'b')]);
+        assert_eq!(computed, vec![(1f32, 'a'), (1f32, 'b')]);
+        assert!(Cutoff::Top(1f32).apply(Vec::<(Score, char)>::new()).is_empty());
+    }
+
+    #[test]
+    fn invalid_cutoff() {
+        assert!(Cutoff::Raw(90f32).validate().is_ok());
+        assert!(Cutoff::Top(0.8).validate().is_ok());
+        assert!(Cutoff::Top(1.5).validate().is_err());
+        assert!(Cutoff::MinMax(-0.1).validate().is_err());
+        assert!(Cutoff::Raw(f32::NAN).validate().is_err());
+    }
+}
@@ -0,0 +1,80 @@
+use std::io::Write;
+use std::marker::PhantomData;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use tantivy::{DocAddress, DocId, LeasedItem, Searcher};
+
+use crate::prelude::*;
+use crate::registry::{read_document, to_value};
+
+/// Lazy walk over every live document of an index, one segment after the other
+///
+/// Holds a searcher of its own, which keeps the segments it started with alive
+/// & hides later commits. Other reads don't wait on it.
+pub struct Scan<T> {
+    name: String,
+    schema: SurferSchema,
+    searcher: LeasedItem<Searcher>,
+    segment: usize,
+    doc: DocId,
+    marker: PhantomData<T>,
+}
+
+impl<T> Scan<T> {
+    pub(crate) fn new(name: &str, schema: SurferSchema, searcher: LeasedItem<Searcher>) -> Self {
+        Self {
+            name: name.to_string(),
+            schema,
+            searcher,
+            segment: 0,
+            doc: 0,
+            marker: PhantomData,
+        }
+    }
+    /// Next live document, deleted ones skipped
+    fn next_doc(&mut self) -> Option<DocAddress> {
+        loop {
+            let reader = self.searcher.segment_readers().get(self.segment)?;
+            if self.doc >= reader.max_doc() {
+                self.segment += 1;
+                self.doc = 0;
+                continue;
+            };
+            let doc = self.doc;
+            self.doc += 1;
+            if !reader.is_deleted(doc) {
+                return Some(DocAddress(self.segment as u32, doc));
+            };
+        }
+    }
+    fn read(&self, doc_address: DocAddress) -> Result<serde_json::Value, IndexError> {
+        let document = read_document(&self.schema, &self.searcher, doc_address)?;
+        to_value(&self.name, &self.schema, &document, None)
+    }
+}
+
+impl<T: DeserializeOwned> Iterator for Scan<T> {
+    type Item = Result<T, IndexError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let doc_address = self.next_doc()?;
+        let document = self.read(doc_address);
+        Some(document.and_then(|document| serde_json::from_value::<T>(document).map_err(IndexError::from)))
+    }
+}
+
+impl<T: Serialize + DeserializeOwned> Scan<T> {
+    /// Writes one json document per line, returns how many were written
+    pub fn write_ndjson<W: Write>(self, writer: &mut W) -> Result<usize, IndexError> {
+        let to_error = |e: std::io::Error| IndexError::new("Unable to write ndjson".to_string(), e.to_string());
+        let mut written = 0;
+        for document in self {
+            serde_json::to_writer(&mut *writer, &document?)?;
+            writer.write_all(b"\n").map_err(to_error)?;
+            written += 1;
+        };
+        writer.flush().map_err(to_error)?;
+        Ok(written)
+    }
+}
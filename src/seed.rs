@@ -15,7 +15,7 @@ pub(crate) fn resolve_home<T: AsRef<str>>(home: Option<T>) -> Result<PathBuf, In
         None => "indexes"
     };
     let home = Path::new(home);
-    let _ = create_dir_all(home)?;
+    create_dir_all(home)?;
     Ok(home.to_owned())
 }
 
@@ -30,7 +30,7 @@ pub(crate) fn resolve_index_directory_path<T: AsRef<str>>(name: T, home: Option<
 /// Create a MMap dir
 pub(crate) fn open_mmap_directory(path: PathBuf) -> Result<MmapDirectory, IndexError> {
     if !path.exists() {
-        let _ = create_dir_all(&path)?;
+        create_dir_all(&path)?;
     }
     let dir = MmapDirectory::open(path)?;
     Ok(dir)
@@ -42,7 +42,7 @@ pub(crate) fn open_index(dir: MmapDirectory, schema: Option<&Schema>) -> Result<
     let index = if Index::exists(&dir) {
         Index::open(dir)
     } else {
-        if let None = schema {
+        if schema.is_none() {
             let error = IndexError::new(
                 "Unable to create index",
                 "Schema is required for new index",
@@ -87,6 +87,22 @@ pub(crate) fn open_index_reader(index: &Index) -> Result<IndexReader, IndexError
     Ok(index_reader)
 }
 
+/// Reader with a single searcher of its own, kept out of the shared pool by long lived reads
+pub(crate) fn open_snapshot_reader(index: &Index) -> Result<IndexReader, IndexError> {
+    let index_reader = index
+        .reader_builder()
+        .reload_policy(ReloadPolicy::Manual)
+        .num_searchers(1)
+        .try_into().map_err(|e| {
+        let reason = e.to_string();
+        IndexError::new(
+            "Unable to create index reader",
+            reason.as_str(),
+        )
+    })?;
+    Ok(index_reader)
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -129,7 +145,7 @@ mod tests {
         let path = open_mmap_directory(path);
         assert!(path.is_ok());
         assert!(p.exists());
-        let _ = remove_dir_all(&p);
+        let _ = remove_dir_all(p);
     }
 
     #[test]
@@ -149,7 +165,7 @@ mod tests {
         let result = open_index(path, Some(&schema));
         assert!(result.is_ok());
         assert!(p.exists());
-        let _ = remove_dir_all(&p);
+        let _ = remove_dir_all(p);
     }
 
     #[test]
@@ -0,0 +1,131 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use tantivy::{Score, Term};
+
+/// How terms are picked from the source document of a more like this search
+/// * `fields` - Text fields to pick terms from, all of them when none
+/// * `max_query_terms` - Most significant terms kept in the query
+/// * `min_term_frequency` - Times a term must appear in the source document
+/// * `min_doc_frequency` - Documents of the index a term must appear in
+/// * `min_word_length` - Shorter words are left out
+#[derive(Clone, Debug, PartialEq)]
+pub struct MoreLikeThis {
+    fields: Option<Vec<String>>,
+    max_query_terms: usize,
+    min_term_frequency: usize,
+    min_doc_frequency: u64,
+    min_word_length: usize,
+}
+
+impl MoreLikeThis {
+    pub fn new(fields: Option<Vec<String>>, max_query_terms: usize, min_term_frequency: usize, min_doc_frequency: u64, min_word_length: usize) -> Self {
+        Self {
+            fields,
+            max_query_terms,
+            min_term_frequency,
+            min_doc_frequency,
+            min_word_length,
+        }
+    }
+    pub fn resolve_fields(&self) -> Option<&Vec<String>> {
+        self.fields.as_ref()
+    }
+    pub fn resolve_max_query_terms(&self) -> usize {
+        self.max_query_terms
+    }
+    pub fn resolve_min_term_frequency(&self) -> usize {
+        self.min_term_frequency
+    }
+    pub fn resolve_min_doc_frequency(&self) -> u64 {
+        self.min_doc_frequency
+    }
+    pub fn resolve_min_word_length(&self) -> usize {
+        self.min_word_length
+    }
+    /// Are terms of the field picked
+    pub(crate) fn includes(&self, field_name: &str) -> bool {
+        match &self.fields {
+            Some(fields) => fields.iter().any(|f| f == field_name),
+            None => true,
+        }
+    }
+    /// Terms weighted by tf-idf, most significant first
+    pub(crate) fn select_terms<F: Fn(&Term) -> u64>(&self, frequencies: HashMap<Term, usize>, doc_freq: F, num_docs: u64) -> Vec<(Term, Score)> {
+        let mut terms = Vec::with_capacity(frequencies.len());
+        for (term, term_frequency) in frequencies {
+            if term_frequency < self.min_term_frequency || term.text().chars().count() < self.min_word_length {
+                continue;
+            };
+            let doc_frequency = doc_freq(&term);
+            if doc_frequency < self.min_doc_frequency {
+                continue;
+            };
+            let weight = term_frequency as f32 * idf(doc_frequency, num_docs);
+            terms.push((term, weight));
+        };
+        terms.sort_by(|(term, weight), (other_term, other_weight)| {
+            other_weight
+                .partial_cmp(weight)
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| term.cmp(other_term))
+        });
+        terms.truncate(self.max_query_terms);
+        terms
+    }
+}
+
+/// Every text field, up to 25 terms of at least 2 characters
+impl Default for MoreLikeThis {
+    fn default() -> Self {
+        Self::new(None, 25, 1, 1, 2)
+    }
+}
+
+/// Same inverse document frequency as BM25, rare terms weigh more
+fn idf(doc_frequency: u64, num_docs: u64) -> Score {
+    let doc_frequency = doc_frequency as f32;
+    let num_docs = std::cmp::max(num_docs, 1) as f32;
+    (1f32 + (num_docs - doc_frequency + 0.5) / (doc_frequency + 0.5)).ln()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tantivy::schema::Field;
+
+    #[test]
+    fn validate_select_terms() {
+        let field = Field::from_field_id(0);
+        let rare = Term::from_field_text(field, "printer");
+        let common = Term::from_field_text(field, "issue");
+        let short = Term::from_field_text(field, "a");
+        let mut frequencies = HashMap::new();
+        frequencies.insert(rare.clone(), 1);
+        frequencies.insert(common.clone(), 2);
+        frequencies.insert(short, 5);
+        let doc_freq = |term: &Term| if term == &rare { 1 } else { 90 };
+
+        let options = MoreLikeThis::default();
+        let computed = options.select_terms(frequencies.clone(), doc_freq, 100);
+        let computed: Vec<Term> = computed.into_iter().map(|(term, _)| term).collect();
+        assert_eq!(computed, vec![rare.clone(), common.clone()]);
+
+        let options = MoreLikeThis::new(None, 1, 2, 1, 2);
+        let computed = options.select_terms(frequencies.clone(), doc_freq, 100);
+        assert_eq!(computed.len(), 1);
+        assert_eq!(computed[0].0, common);
+
+        let options = MoreLikeThis::new(None, 25, 1, 2, 2);
+        let computed = options.select_terms(frequencies, doc_freq, 100);
+        assert_eq!(computed.len(), 1);
+        assert_eq!(computed[0].0, common);
+    }
+
+    #[test]
+    fn validate_idf() {
+        assert!(idf(1, 100) > idf(50, 100));
+        assert!(idf(100, 100) > 0f32);
+    }
+}
@@ -0,0 +1,313 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+
+use tantivy::collector::{Collector, SegmentCollector};
+use tantivy::fastfield::{FastFieldReader, FastValue};
+use tantivy::schema::{Field, Schema};
+use tantivy::{DocAddress, DocId, Score, SegmentLocalId, SegmentReader};
+
+use crate::prelude::*;
+use crate::registry::SurferFieldTypes;
+use crate::paging::Position;
+
+/// Direction of a sort
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+/// How hits with the same sort key are ordered
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Tiebreak {
+    /// Doc address, the order documents were indexed in within a segment
+    Address,
+    /// Best scored first, then doc address
+    Score,
+}
+
+/// Sort by a numeric fast field, ties are broken by doc address unless told otherwise
+///
+/// Surfer maps no date fields, dates sort when they are stored as numeric timestamps.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Sort {
+    field_name: String,
+    order: SortOrder,
+    tiebreak: Tiebreak,
+}
+
+impl Sort {
+    pub fn new(field_name: String, order: SortOrder) -> Self {
+        Self {
+            field_name,
+            order,
+            tiebreak: Tiebreak::Address,
+        }
+    }
+    /// Smallest first e.g. cheapest first
+    pub fn ascending(field_name: &str) -> Self {
+        Self::new(field_name.to_string(), SortOrder::Ascending)
+    }
+    /// Largest first e.g. newest first
+    pub fn descending(field_name: &str) -> Self {
+        Self::new(field_name.to_string(), SortOrder::Descending)
+    }
+    pub fn resolve_field_name(&self) -> &String {
+        &self.field_name
+    }
+    pub fn resolve_order(&self) -> SortOrder {
+        self.order
+    }
+    pub fn set_tiebreak(&mut self, tiebreak: Tiebreak) {
+        self.tiebreak = tiebreak;
+    }
+    pub fn resolve_tiebreak(&self) -> Tiebreak {
+        self.tiebreak
+    }
+    /// Checks the field can be sorted on
+    pub(crate) fn resolve_field(&self, schema: &Schema, mappings: &HashMap<String, SurferFieldTypes>) -> Result<Field, IndexError> {
+        let message = format!("Unable to sort by: {}", self.field_name);
+        let field_type = mappings.get(&self.field_name);
+        let field = schema.get_field(&self.field_name);
+        if field_type.is_none() || field.is_none() {
+            let reason = format!("Missing field: {}", self.field_name);
+            return Err(IndexError::new(message, reason));
+        };
+        match field_type.unwrap() {
+            SurferFieldTypes::U64 | SurferFieldTypes::I64 | SurferFieldTypes::F64 => {}
+            _ => {
+                let reason = format!("Not a numeric field: {}", self.field_name);
+                return Err(IndexError::new(message, reason));
+            }
+        };
+        let field = field.unwrap();
+        if !schema.get_field_entry(field).is_int_fast() {
+            let reason = format!("Not a fast field: {}", self.field_name);
+            return Err(IndexError::new(message, reason));
+        };
+        Ok(field)
+    }
+    /// Key that sorts ascending in the requested order
+    pub(crate) fn key(&self, value: u64) -> u64 {
+        match self.order {
+            SortOrder::Ascending => value,
+            SortOrder::Descending => !value,
+        }
+    }
+    /// Score ranking ties, zero when ties are broken by doc address
+    pub(crate) fn tie_score(&self, score: Score) -> Score {
+        match self.tiebreak {
+            Tiebreak::Address => 0f32,
+            Tiebreak::Score => score,
+        }
+    }
+}
+
+/// Sort key, then tie score best first, then doc address
+pub(crate) fn compare_keyed(keyed: &(u64, Score, DocAddress), other: &(u64, Score, DocAddress)) -> Ordering {
+    keyed.0
+        .cmp(&other.0)
+        .then_with(|| other.1.partial_cmp(&keyed.1).unwrap_or(Ordering::Equal))
+        .then_with(|| keyed.2.cmp(&other.2))
+}
+
+/// Sorted hit, greater means sorted later
+#[derive(PartialEq)]
+struct Keyed(u64, Score, DocAddress);
+
+impl Eq for Keyed {}
+
+impl Ord for Keyed {
+    fn cmp(&self, other: &Self) -> Ordering {
+        compare_keyed(&(self.0, self.1, self.2), &(other.0, other.1, other.2))
+    }
+}
+
+impl PartialOrd for Keyed {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Numeric fast field of one segment
+pub(crate) enum FastFieldValues {
+    U64(FastFieldReader<u64>),
+    I64(FastFieldReader<i64>),
+    F64(FastFieldReader<f64>),
+}
+
+impl FastFieldValues {
+    pub(crate) fn open(reader: &SegmentReader, field: Field) -> Option<Self> {
+        let fast_fields = reader.fast_fields();
+        if let Some(values) = fast_fields.u64(field) {
+            return Some(FastFieldValues::U64(values));
+        };
+        if let Some(values) = fast_fields.i64(field) {
+            return Some(FastFieldValues::I64(values));
+        };
+        fast_fields.f64(field).map(FastFieldValues::F64)
+    }
+    /// Order preserving u64 mapping of the value
+    pub(crate) fn key(&self, doc: DocId) -> u64 {
+        match self {
+            FastFieldValues::U64(values) => values.get(doc),
+            FastFieldValues::I64(values) => values.get(doc).to_u64(),
+            FastFieldValues::F64(values) => values.get(doc).to_u64(),
+        }
+    }
+    pub(crate) fn as_f64(&self, doc: DocId) -> f64 {
+        match self {
+            FastFieldValues::U64(values) => values.get(doc) as f64,
+            FastFieldValues::I64(values) => values.get(doc) as f64,
+            FastFieldValues::F64(values) => values.get(doc),
+        }
+    }
+    /// Floats are truncated
+    pub(crate) fn as_i64(&self, doc: DocId) -> i64 {
+        match self {
+            FastFieldValues::U64(values) => values.get(doc) as i64,
+            FastFieldValues::I64(values) => values.get(doc),
+            FastFieldValues::F64(values) => values.get(doc) as i64,
+        }
+    }
+}
+
+/// Top documents by sort key, then tie score & doc address
+pub(crate) struct TopDocsByField {
+    field: Field,
+    sort: Sort,
+    limit: usize,
+    offset: usize,
+    cursor: Option<Position>,
+}
+
+impl TopDocsByField {
+    /// The cursor of the paging has to be placed in the searcher beforehand
+    pub(crate) fn new(field: Field, sort: &Sort, paging: &Paging, cursor: Option<Position>) -> Self {
+        Self {
+            field,
+            sort: sort.clone(),
+            limit: paging.resolve_limit(),
+            offset: paging.resolve_offset(),
+            cursor,
+        }
+    }
+    fn capacity(&self) -> usize {
+        self.limit + self.offset
+    }
+}
+
+pub(crate) struct TopDocsByFieldSegment {
+    values: FastFieldValues,
+    sort: Sort,
+    capacity: usize,
+    cursor: Option<Position>,
+    segment: SegmentLocalId,
+    heap: BinaryHeap<Keyed>,
+}
+
+/// Keeps the smallest capacity keys
+fn push_keyed(heap: &mut BinaryHeap<Keyed>, capacity: usize, keyed: Keyed) {
+    if heap.len() < capacity {
+        heap.push(keyed);
+    } else if let Some(mut largest) = heap.peek_mut() {
+        if keyed < *largest {
+            *largest = keyed;
+        }
+    }
+}
+
+fn into_sorted(heap: BinaryHeap<Keyed>) -> Vec<(u64, Score, DocAddress)> {
+    heap.into_sorted_vec()
+        .into_iter()
+        .map(|Keyed(key, score, address)| (key, score, address))
+        .collect()
+}
+
+impl Collector for TopDocsByField {
+    type Fruit = Vec<(u64, Score, DocAddress)>;
+    type Child = TopDocsByFieldSegment;
+
+    fn for_segment(&self, segment_local_id: SegmentLocalId, reader: &SegmentReader) -> tantivy::Result<Self::Child> {
+        let values = FastFieldValues::open(reader, self.field);
+        if values.is_none() {
+            let error = format!("Field: {} is not a numeric fast field", self.sort.field_name);
+            return Err(tantivy::TantivyError::SchemaError(error));
+        };
+        Ok(TopDocsByFieldSegment {
+            values: values.unwrap(),
+            sort: self.sort.clone(),
+            capacity: self.capacity(),
+            cursor: self.cursor,
+            segment: segment_local_id,
+            heap: BinaryHeap::with_capacity(self.capacity()),
+        })
+    }
+
+    fn requires_scoring(&self) -> bool {
+        self.sort.tiebreak == Tiebreak::Score
+    }
+
+    fn merge_fruits(&self, segment_fruits: Vec<Self::Fruit>) -> tantivy::Result<Self::Fruit> {
+        let mut heap = BinaryHeap::with_capacity(self.capacity());
+        for fruit in segment_fruits {
+            for (key, score, address) in fruit {
+                push_keyed(&mut heap, self.capacity(), Keyed(key, score, address));
+            }
+        };
+        Ok(into_sorted(heap).into_iter().skip(self.offset).collect())
+    }
+}
+
+impl SegmentCollector for TopDocsByFieldSegment {
+    type Fruit = Vec<(u64, Score, DocAddress)>;
+
+    fn collect(&mut self, doc: DocId, score: Score) {
+        let key = self.sort.key(self.values.key(doc));
+        let score = self.sort.tie_score(score);
+        let address = DocAddress(self.segment, doc);
+        if let Some(cursor) = &self.cursor {
+            if !cursor.is_before_key(key, score, address) {
+                return;
+            };
+        };
+        push_keyed(&mut self.heap, self.capacity, Keyed(key, score, address));
+    }
+
+    fn harvest(self) -> Self::Fruit {
+        into_sorted(self.heap)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_sort_key() {
+        let ascending = Sort::ascending("age");
+        let descending = Sort::descending("age");
+        assert!(ascending.key(1) < ascending.key(2));
+        assert!(descending.key(1) > descending.key(2));
+        assert!(descending.key((-1i64).to_u64()) > descending.key(1i64.to_u64()));
+        assert!(ascending.key((-1.5f64).to_u64()) < ascending.key(0.5f64.to_u64()));
+    }
+
+    #[test]
+    fn validate_keyed_heap() {
+        let mut heap = BinaryHeap::new();
+        push_keyed(&mut heap, 2, Keyed(5, 0f32, DocAddress(0, 1)));
+        push_keyed(&mut heap, 2, Keyed(3, 0f32, DocAddress(0, 2)));
+        push_keyed(&mut heap, 2, Keyed(3, 0f32, DocAddress(0, 0)));
+        push_keyed(&mut heap, 2, Keyed(9, 0f32, DocAddress(0, 3)));
+        assert_eq!(into_sorted(heap), vec![(3, 0f32, DocAddress(0, 0)), (3, 0f32, DocAddress(0, 2))]);
+
+        // Ties broken by score
+        let mut heap = BinaryHeap::new();
+        push_keyed(&mut heap, 2, Keyed(3, 1f32, DocAddress(0, 0)));
+        push_keyed(&mut heap, 2, Keyed(3, 2f32, DocAddress(0, 2)));
+        push_keyed(&mut heap, 2, Keyed(3, 2f32, DocAddress(0, 1)));
+        assert_eq!(into_sorted(heap), vec![(3, 2f32, DocAddress(0, 1)), (3, 2f32, DocAddress(0, 2))]);
+    }
+}
@@ -0,0 +1,541 @@
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+use tantivy::Index;
+
+use crate::prelude::*;
+use crate::registry::SurferFieldTypes;
+
+/// Token along with the column (1 based) it starts at
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Text(String),
+    Number(String),
+    Symbol(&'static str),
+    End,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Located<T> {
+    value: T,
+    column: usize,
+}
+
+fn syntax_error(column: usize, reason: String) -> IndexError {
+    let message = format!("Invalid query at column {}", column);
+    IndexError::new(message, reason)
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Word(word) => format!("'{}'", word),
+        Token::Text(text) => format!("'{}'", text),
+        Token::Number(number) => number.to_string(),
+        Token::Symbol(symbol) => format!("'{}'", symbol),
+        Token::End => "end of query".to_string(),
+    }
+}
+
+fn tokenize(query: &str) -> Result<Vec<Located<Token>>, IndexError> {
+    let mut tokens = Vec::new();
+    let mut chars: Peekable<CharIndices> = query.char_indices().peekable();
+    let column_of = |offset: usize| query[..offset].chars().count() + 1;
+    while let Some(&(offset, c)) = chars.peek() {
+        let column = column_of(offset);
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        };
+        let token = if c.is_alphabetic() || c == '_' {
+            let mut word = String::new();
+            while let Some(&(_, c)) = chars.peek() {
+                if !(c.is_alphanumeric() || c == '_') {
+                    break;
+                };
+                word.push(c);
+                chars.next();
+            };
+            Token::Word(word)
+        } else if c.is_ascii_digit() || c == '-' || c == '.' {
+            let mut number = String::new();
+            number.push(c);
+            chars.next();
+            while let Some(&(_, c)) = chars.peek() {
+                if !(c.is_ascii_alphanumeric() || c == '.') {
+                    break;
+                };
+                number.push(c);
+                chars.next();
+            };
+            Token::Number(number)
+        } else if c == '\'' {
+            chars.next();
+            let mut text = String::new();
+            loop {
+                match chars.next() {
+                    Some((_, '\'')) => {
+                        // Quotes are escaped by doubling them
+                        if let Some(&(_, '\'')) = chars.peek() {
+                            text.push('\'');
+                            chars.next();
+                        } else {
+                            break;
+                        }
+                    }
+                    Some((_, c)) => text.push(c),
+                    None => return Err(syntax_error(column, "Unterminated string".to_string())),
+                };
+            };
+            Token::Text(text)
+        } else {
+            chars.next();
+            let next = chars.peek().map(|&(_, c)| c);
+            let symbol = match (c, next) {
+                ('!', Some('=')) => "!=",
+                ('<', Some('>')) => "<>",
+                ('<', Some('=')) => "<=",
+                ('>', Some('=')) => ">=",
+                ('<', _) => "<",
+                ('>', _) => ">",
+                ('=', _) => "=",
+                (',', _) => ",",
+                ('(', _) => "(",
+                (')', _) => ")",
+                ('*', _) => "*",
+                (';', _) => ";",
+                _ => return Err(syntax_error(column, format!("Unexpected character: '{}'", c))),
+            };
+            if symbol.len() == 2 {
+                chars.next();
+            };
+            Token::Symbol(symbol)
+        };
+        tokens.push(Located { value: token, column });
+    };
+    let column = query.chars().count() + 1;
+    tokens.push(Located { value: Token::End, column });
+    Ok(tokens)
+}
+
+/// Literal as written in the query
+#[derive(Debug, Clone, PartialEq)]
+enum Literal {
+    Text(String),
+    Number(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Comparison {
+    Operator(Operator),
+    NotEqual,
+}
+
+/// WHERE clause before it is checked against a schema
+#[derive(Debug, Clone, PartialEq)]
+enum Filter {
+    Predicate {
+        field: Located<String>,
+        comparison: Comparison,
+        values: Vec<Located<Literal>>,
+    },
+    And(Vec<Filter>),
+    Or(Vec<Filter>),
+    Not(Box<Filter>),
+}
+
+/// Parsed SELECT statement
+///
+/// SELECT first, age FROM users WHERE last = 'doe' AND age > 30 ORDER BY age DESC LIMIT 10 OFFSET 20
+#[derive(Debug, Clone, PartialEq)]
+pub struct Statement {
+    fields: Option<Vec<Located<String>>>,
+    index_name: String,
+    filter: Option<Filter>,
+    order: Option<(Located<String>, SortOrder)>,
+    limit: Option<usize>,
+    offset: usize,
+}
+
+struct Parser {
+    tokens: Vec<Located<Token>>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Located<Token> {
+        &self.tokens[self.position]
+    }
+    fn next(&mut self) -> Located<Token> {
+        let token = self.tokens[self.position].clone();
+        if token.value != Token::End {
+            self.position += 1;
+        };
+        token
+    }
+    fn unexpected(&self, expected: &str) -> IndexError {
+        let token = self.peek();
+        let reason = format!("Expected {}, found {}", expected, describe(&token.value));
+        syntax_error(token.column, reason)
+    }
+    fn is_keyword(&self, keyword: &str) -> bool {
+        match &self.peek().value {
+            Token::Word(word) => word.eq_ignore_ascii_case(keyword),
+            _ => false,
+        }
+    }
+    fn accept_keyword(&mut self, keyword: &str) -> bool {
+        if self.is_keyword(keyword) {
+            self.next();
+            return true;
+        };
+        false
+    }
+    fn accept_symbol(&mut self, symbol: &str) -> bool {
+        if let Token::Symbol(s) = self.peek().value {
+            if s == symbol {
+                self.next();
+                return true;
+            };
+        };
+        false
+    }
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), IndexError> {
+        if self.accept_keyword(keyword) {
+            return Ok(());
+        };
+        Err(self.unexpected(keyword))
+    }
+    fn expect_symbol(&mut self, symbol: &str) -> Result<(), IndexError> {
+        if self.accept_symbol(symbol) {
+            return Ok(());
+        };
+        Err(self.unexpected(&format!("'{}'", symbol)))
+    }
+    fn identifier(&mut self) -> Result<Located<String>, IndexError> {
+        const RESERVED: [&str; 13] = ["select", "from", "where", "and", "or", "not", "order", "by", "asc", "desc", "limit", "offset", "between"];
+        if let Token::Word(word) = &self.peek().value {
+            if !RESERVED.iter().any(|r| word.eq_ignore_ascii_case(r)) {
+                let word = word.clone();
+                let column = self.next().column;
+                return Ok(Located { value: word, column });
+            };
+        };
+        Err(self.unexpected("a field name"))
+    }
+    fn count(&mut self, what: &str) -> Result<usize, IndexError> {
+        let token = self.peek().clone();
+        if let Token::Number(number) = &token.value {
+            let count = number.parse::<usize>().map_err(|_| {
+                syntax_error(token.column, format!("Expected a non negative integer for {}, found {}", what, number))
+            })?;
+            self.next();
+            return Ok(count);
+        };
+        Err(self.unexpected(&format!("a number for {}", what)))
+    }
+    fn literal(&mut self) -> Result<Located<Literal>, IndexError> {
+        let token = self.peek().clone();
+        let literal = match token.value {
+            Token::Text(text) => Literal::Text(text),
+            Token::Number(number) => Literal::Number(number),
+            _ => return Err(self.unexpected("a quoted string or a number")),
+        };
+        self.next();
+        Ok(Located { value: literal, column: token.column })
+    }
+
+    fn statement(&mut self) -> Result<Statement, IndexError> {
+        self.expect_keyword("select")?;
+        let fields = if self.accept_symbol("*") {
+            None
+        } else {
+            let mut fields = vec![self.identifier()?];
+            while self.accept_symbol(",") {
+                fields.push(self.identifier()?);
+            };
+            Some(fields)
+        };
+        self.expect_keyword("from")?;
+        let index_name = self.identifier()?.value;
+        let filter = if self.accept_keyword("where") {
+            Some(self.or()?)
+        } else {
+            None
+        };
+        let order = if self.accept_keyword("order") {
+            self.expect_keyword("by")?;
+            let field = self.identifier()?;
+            let order = if self.accept_keyword("desc") {
+                SortOrder::Descending
+            } else {
+                self.accept_keyword("asc");
+                SortOrder::Ascending
+            };
+            Some((field, order))
+        } else {
+            None
+        };
+        let limit = if self.accept_keyword("limit") {
+            Some(self.count("LIMIT")?)
+        } else {
+            None
+        };
+        let offset = if self.accept_keyword("offset") {
+            self.count("OFFSET")?
+        } else {
+            0
+        };
+        self.accept_symbol(";");
+        if self.peek().value != Token::End {
+            return Err(self.unexpected("end of query"));
+        };
+        Ok(Statement {
+            fields,
+            index_name,
+            filter,
+            order,
+            limit,
+            offset,
+        })
+    }
+    fn or(&mut self) -> Result<Filter, IndexError> {
+        let mut filters = vec![self.and()?];
+        while self.accept_keyword("or") {
+            filters.push(self.and()?);
+        };
+        if filters.len() == 1 {
+            return Ok(filters.remove(0));
+        };
+        Ok(Filter::Or(filters))
+    }
+    fn and(&mut self) -> Result<Filter, IndexError> {
+        let mut filters = vec![self.unary()?];
+        while self.accept_keyword("and") {
+            filters.push(self.unary()?);
+        };
+        if filters.len() == 1 {
+            return Ok(filters.remove(0));
+        };
+        Ok(Filter::And(filters))
+    }
+    fn unary(&mut self) -> Result<Filter, IndexError> {
+        if self.accept_keyword("not") {
+            return Ok(Filter::Not(Box::new(self.unary()?)));
+        };
+        if self.accept_symbol("(") {
+            let filter = self.or()?;
+            self.expect_symbol(")")?;
+            return Ok(filter);
+        };
+        self.predicate()
+    }
+    fn predicate(&mut self) -> Result<Filter, IndexError> {
+        let field = self.identifier()?;
+        if self.accept_keyword("between") {
+            let lower = self.literal()?;
+            self.expect_keyword("and")?;
+            let upper = self.literal()?;
+            let comparison = Comparison::Operator(Operator::Between(String::new()));
+            return Ok(Filter::Predicate { field, comparison, values: vec![lower, upper] });
+        };
+        let comparison = match &self.peek().value {
+            Token::Symbol("=") => Comparison::Operator(Operator::Equal),
+            Token::Symbol("!=") | Token::Symbol("<>") => Comparison::NotEqual,
+            Token::Symbol(">") => Comparison::Operator(Operator::GreaterThan),
+            Token::Symbol(">=") => Comparison::Operator(Operator::GreaterOrEqual),
+            Token::Symbol("<") => Comparison::Operator(Operator::LessThan),
+            Token::Symbol("<=") => Comparison::Operator(Operator::LessOrEqual),
+            _ => return Err(self.unexpected("a comparison")),
+        };
+        self.next();
+        let value = self.literal()?;
+        Ok(Filter::Predicate { field, comparison, values: vec![value] })
+    }
+}
+
+/// Checks the literal against the field mapping
+fn check_literal(field_name: &str, field_type: &SurferFieldTypes, literal: &Located<Literal>) -> Result<String, IndexError> {
+    let column = literal.column;
+    let mismatch = |expected: &str, found: &str| {
+        syntax_error(column, format!("Expected {} for field: {}, found {}", expected, field_name, found))
+    };
+    match (field_type, &literal.value) {
+        (SurferFieldTypes::String, Literal::Text(text)) => Ok(text.clone()),
+        (SurferFieldTypes::String, Literal::Number(number)) => Err(mismatch("a quoted string", number)),
+        (SurferFieldTypes::U64, Literal::Number(number)) if number.parse::<u64>().is_ok() => Ok(number.clone()),
+        (SurferFieldTypes::I64, Literal::Number(number)) if number.parse::<i64>().is_ok() => Ok(number.clone()),
+        (SurferFieldTypes::F64, Literal::Number(number)) if number.parse::<f64>().is_ok() => Ok(number.clone()),
+        (SurferFieldTypes::U64, literal) => Err(mismatch("an unsigned integer", &describe_literal(literal))),
+        (SurferFieldTypes::I64, literal) => Err(mismatch("an integer", &describe_literal(literal))),
+        (SurferFieldTypes::F64, literal) => Err(mismatch("a number", &describe_literal(literal))),
+        (SurferFieldTypes::Bool, Literal::Text(text)) if text.parse::<bool>().is_ok() => Ok(text.clone()),
+        (SurferFieldTypes::Bool, literal) => Err(mismatch("'true' or 'false'", &describe_literal(literal))),
+        (SurferFieldTypes::Bytes, _) => Err(syntax_error(column, format!("Cant search on bytes: {}", field_name))),
+    }
+}
+
+/// Runs a text literal through the field tokenizer, it has to come out as a single term
+fn analyze_text(index: &Index, schema: &SurferSchema, field_name: &str, text: &str, column: usize) -> Result<String, IndexError> {
+    let field = schema.get_field(field_name).ok_or_else(|| {
+        syntax_error(column, format!("Missing field: {}", field_name))
+    })?;
+    let tokenizer = index.tokenizer_for_field(field)?;
+    let mut terms = Vec::<String>::new();
+    let mut stream = tokenizer.token_stream(text);
+    stream.process(&mut |token| terms.push(token.text.clone()));
+    match terms.len() {
+        1 => Ok(terms.remove(0)),
+        0 => Err(syntax_error(column, format!("No searchable word for field: {}, found '{}'", field_name, text))),
+        _ => Err(syntax_error(column, format!("Expected a single word for field: {}, found '{}'", field_name, text))),
+    }
+}
+
+fn describe_literal(literal: &Literal) -> String {
+    match literal {
+        Literal::Text(text) => format!("'{}'", text),
+        Literal::Number(number) => number.to_string(),
+    }
+}
+
+fn check_field<'a>(schema: &'a SurferSchema, field: &Located<String>) -> Result<&'a SurferFieldTypes, IndexError> {
+    schema.resolve_mapping().get(&field.value).ok_or_else(|| {
+        syntax_error(field.column, format!("Missing field: {}", field.value))
+    })
+}
+
+impl Filter {
+    fn compile(&self, index: &Index, schema: &SurferSchema) -> Result<Expression, IndexError> {
+        let expression = match self {
+            Filter::And(filters) => Expression::and(Self::compile_all(filters, index, schema)?),
+            Filter::Or(filters) => Expression::or(Self::compile_all(filters, index, schema)?),
+            Filter::Not(filter) => Expression::not(filter.compile(index, schema)?),
+            Filter::Predicate { field, comparison, values } => {
+                let field_type = check_field(schema, field)?;
+                let numeric = matches!(field_type, SurferFieldTypes::U64 | SurferFieldTypes::I64 | SurferFieldTypes::F64);
+                let is_range = !matches!(comparison, Comparison::Operator(Operator::Equal) | Comparison::NotEqual);
+                if is_range && !numeric {
+                    let column = values.first().map(|v| v.column).unwrap_or(field.column);
+                    let reason = format!("Comparisons need a numeric field: {}", field.value);
+                    return Err(syntax_error(column, reason));
+                };
+                let mut checked = Vec::with_capacity(values.len());
+                for value in values {
+                    checked.push(check_literal(&field.value, field_type, value)?);
+                };
+                let mut field_value = checked.remove(0);
+                if let SurferFieldTypes::String = field_type {
+                    field_value = analyze_text(index, schema, &field.value, &field_value, values[0].column)?;
+                };
+                let operator = match comparison {
+                    Comparison::Operator(Operator::Between(_)) => Operator::Between(checked.remove(0)),
+                    Comparison::Operator(operator) => operator.clone(),
+                    Comparison::NotEqual => Operator::Equal,
+                };
+                let condition = AndCondition::with_operator(field.value.clone(), field_value, operator);
+                match comparison {
+                    Comparison::NotEqual => Expression::not(condition.into()),
+                    _ => condition.into(),
+                }
+            }
+        };
+        Ok(expression)
+    }
+    fn compile_all(filters: &[Filter], index: &Index, schema: &SurferSchema) -> Result<Vec<Expression>, IndexError> {
+        let mut expressions = Vec::with_capacity(filters.len());
+        for filter in filters {
+            expressions.push(filter.compile(index, schema)?);
+        };
+        Ok(expressions)
+    }
+}
+
+impl Statement {
+    pub fn parse(query: &str) -> Result<Self, IndexError> {
+        let tokens = tokenize(query)?;
+        let mut parser = Parser { tokens, position: 0 };
+        parser.statement()
+    }
+    pub fn resolve_index_name(&self) -> &String {
+        &self.index_name
+    }
+    /// Selected fields, none for SELECT *
+    pub fn resolve_fields(&self) -> Option<Vec<String>> {
+        self.fields.as_ref().map(|fields| fields.iter().map(|f| f.value.clone()).collect())
+    }
+    /// None without LIMIT, reads then fall back to their default page size
+    pub fn resolve_limit(&self) -> Option<usize> {
+        self.limit
+    }
+    pub fn resolve_offset(&self) -> usize {
+        self.offset
+    }
+    /// WHERE clause as an expression, typed against the schema & text run through the field tokenizers
+    pub fn resolve_expression(&self, index: &Index, schema: &SurferSchema) -> Result<Expression, IndexError> {
+        if let Some(fields) = &self.fields {
+            for field in fields {
+                check_field(schema, field)?;
+            };
+        };
+        match &self.filter {
+            Some(filter) => filter.compile(index, schema),
+            None => Ok(Expression::all()),
+        }
+    }
+    /// ORDER BY clause, checked against the schema
+    pub fn resolve_sort(&self, schema: &SurferSchema) -> Result<Option<Sort>, IndexError> {
+        match &self.order {
+            Some((field, order)) => {
+                let sort = Sort::new(field.value.clone(), *order);
+                sort.resolve_field(schema, schema.resolve_mapping())
+                    .map_err(|e| syntax_error(field.column, e.reason().to_string()))?;
+                Ok(Some(sort))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_statement_parsing() {
+        let query = "SELECT first, age FROM users WHERE last = 'doe' AND age > 30 ORDER BY age DESC LIMIT 10 OFFSET 20";
+        let statement = Statement::parse(query).unwrap();
+        assert_eq!(statement.resolve_index_name(), "users");
+        assert_eq!(statement.resolve_fields(), Some(vec!["first".to_string(), "age".to_string()]));
+        assert_eq!(statement.resolve_limit(), Some(10));
+        assert_eq!(statement.resolve_offset(), 20);
+        let (field, order) = statement.order.clone().unwrap();
+        assert_eq!((field.value.as_str(), order), ("age", SortOrder::Descending));
+
+        let statement = Statement::parse("select * from users where not (a = 'x' or b != 'it''s');").unwrap();
+        assert_eq!(statement.resolve_fields(), None);
+        assert_eq!(statement.resolve_limit(), None);
+        let filter = statement.filter.unwrap();
+        match filter {
+            Filter::Not(filter) => match *filter {
+                Filter::Or(filters) => assert_eq!(filters.len(), 2),
+                _ => panic!("Expected OR"),
+            },
+            _ => panic!("Expected NOT"),
+        };
+    }
+
+    #[test]
+    fn validate_statement_errors() {
+        let error = Statement::parse("SELECT first age FROM users").unwrap_err();
+        assert_eq!(error.message(), "Invalid query at column 14");
+        let error = Statement::parse("SELECT * FROM users WHERE last = 'doe").unwrap_err();
+        assert_eq!(error.message(), "Invalid query at column 34");
+        let error = Statement::parse("SELECT * FROM users WHERE age >").unwrap_err();
+        assert_eq!(error.message(), "Invalid query at column 32");
+        let error = Statement::parse("SELECT * FROM users LIMIT -1").unwrap_err();
+        assert_eq!(error.message(), "Invalid query at column 27");
+        let error = Statement::parse("SELECT * FROM users WHERE a = 1 b").unwrap_err();
+        assert_eq!(error.message(), "Invalid query at column 33");
+    }
+}
@@ -0,0 +1,384 @@
+use std::convert::TryInto;
+use std::sync::Arc;
+
+use serde::Serialize;
+use tantivy::collector::{Collector, SegmentCollector};
+use tantivy::fastfield::FastValue;
+use tantivy::schema::{Field, IndexRecordOption};
+use tantivy::{DocId, DocSet, InvertedIndexReader, Score, SegmentLocalId, SegmentReader, TERMINATED};
+
+use crate::prelude::*;
+use crate::registry::SurferFieldTypes;
+use crate::sorting::FastFieldValues;
+
+/// Distinct values kept before the digest starts merging them
+const DIGEST_CAPACITY: usize = 1000;
+
+/// Value below which percent of the matching values fall
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Percentile {
+    percent: f64,
+    value: f64,
+}
+
+impl Percentile {
+    pub fn new(percent: f64, value: f64) -> Self {
+        Self {
+            percent,
+            value,
+        }
+    }
+    pub fn resolve_percent(&self) -> f64 {
+        self.percent
+    }
+    pub fn resolve_value(&self) -> f64 {
+        self.value
+    }
+}
+
+/// Summary of a numeric field over the documents matching a query
+///
+/// Min, max & mean are none when nothing matched.
+/// Percentiles are exact up to 1000 distinct values & approximate beyond.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Stats {
+    field_name: String,
+    count: u64,
+    min: Option<f64>,
+    max: Option<f64>,
+    sum: f64,
+    mean: Option<f64>,
+    percentiles: Vec<Percentile>,
+}
+
+impl Stats {
+    pub fn resolve_field_name(&self) -> &String {
+        &self.field_name
+    }
+    pub fn resolve_count(&self) -> u64 {
+        self.count
+    }
+    pub fn resolve_min(&self) -> Option<f64> {
+        self.min
+    }
+    pub fn resolve_max(&self) -> Option<f64> {
+        self.max
+    }
+    pub fn resolve_sum(&self) -> f64 {
+        self.sum
+    }
+    pub fn resolve_mean(&self) -> Option<f64> {
+        self.mean
+    }
+    /// In the order the percents were asked for
+    pub fn resolve_percentiles(&self) -> &Vec<Percentile> {
+        &self.percentiles
+    }
+}
+
+/// Mergeable sketch of a distribution as weighted centroids
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Digest {
+    centroids: Vec<(f64, u64)>,
+}
+
+impl Digest {
+    pub(crate) fn add(&mut self, value: f64, weight: u64) {
+        self.centroids.push((value, weight));
+        if self.centroids.len() > 2 * DIGEST_CAPACITY {
+            self.compress();
+        };
+    }
+    pub(crate) fn merge(&mut self, other: Digest) {
+        self.centroids.extend(other.centroids);
+        if self.centroids.len() > 2 * DIGEST_CAPACITY {
+            self.compress();
+        };
+    }
+    /// Sorts, folds equal values & then neighbours until within capacity
+    fn compress(&mut self) {
+        self.centroids.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        let mut folded: Vec<(f64, u64)> = Vec::with_capacity(self.centroids.len());
+        for (value, weight) in self.centroids.drain(..) {
+            match folded.last_mut() {
+                Some(last) if last.0 == value => last.1 += weight,
+                _ => folded.push((value, weight)),
+            };
+        };
+        if folded.len() > DIGEST_CAPACITY {
+            let total: u64 = folded.iter().map(|(_, weight)| weight).sum();
+            let target = (total as f64 / DIGEST_CAPACITY as f64).ceil() as u64;
+            let mut merged: Vec<(f64, u64)> = Vec::with_capacity(DIGEST_CAPACITY);
+            for (value, weight) in folded {
+                match merged.last_mut() {
+                    Some(last) if last.1 + weight <= target => {
+                        let combined = last.1 + weight;
+                        last.0 = (last.0 * last.1 as f64 + value * weight as f64) / combined as f64;
+                        last.1 = combined;
+                    }
+                    _ => merged.push((value, weight)),
+                };
+            };
+            folded = merged;
+        };
+        self.centroids = folded;
+    }
+    /// Interpolates between the closest ranks
+    pub(crate) fn percentile(&mut self, percent: f64) -> Option<f64> {
+        self.compress();
+        let total: u64 = self.centroids.iter().map(|(_, weight)| weight).sum();
+        if total == 0 {
+            return None;
+        };
+        let rank = percent / 100f64 * (total - 1) as f64;
+        let lower = self.value_at(rank.floor() as u64);
+        let upper = self.value_at(rank.ceil() as u64);
+        Some(lower + (upper - lower) * rank.fract())
+    }
+    fn value_at(&self, rank: u64) -> f64 {
+        let mut seen = 0u64;
+        for (value, weight) in &self.centroids {
+            seen += weight;
+            if rank < seen {
+                return *value;
+            };
+        };
+        self.centroids.last().map(|(value, _)| *value).unwrap_or_default()
+    }
+}
+
+/// Running totals of one segment or of all of them
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Summary {
+    count: u64,
+    min: Option<f64>,
+    max: Option<f64>,
+    sum: f64,
+    digest: Digest,
+}
+
+impl Summary {
+    fn add(&mut self, value: f64, weight: u64) {
+        self.count += weight;
+        self.sum += value * weight as f64;
+        self.min = Some(self.min.map_or(value, |min| min.min(value)));
+        self.max = Some(self.max.map_or(value, |max| max.max(value)));
+        self.digest.add(value, weight);
+    }
+    fn merge(&mut self, other: Summary) {
+        self.count += other.count;
+        self.sum += other.sum;
+        self.min = match (self.min, other.min) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        self.max = match (self.max, other.max) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+        self.digest.merge(other.digest);
+    }
+}
+
+/// Computes stats of a numeric field alongside a query
+///
+/// Reads fast fields when the field has one, the term dictionary otherwise.
+#[derive(Clone)]
+pub(crate) struct StatsCollector {
+    field_name: String,
+    field: Field,
+    field_type: SurferFieldTypes,
+    is_fast: bool,
+    percents: Vec<f64>,
+}
+
+impl StatsCollector {
+    pub(crate) fn new(schema: &SurferSchema, field_name: &str, percents: &[f64]) -> Result<Self, IndexError> {
+        let message = format!("Unable to compute stats of: {}", field_name);
+        let field_type = schema.resolve_mapping().get(field_name);
+        let field = schema.get_field(field_name);
+        if field_type.is_none() || field.is_none() {
+            let reason = format!("Missing field: {}", field_name);
+            return Err(IndexError::new(message, reason));
+        };
+        let field_type = field_type.unwrap().clone();
+        let field = field.unwrap();
+        match field_type {
+            SurferFieldTypes::U64 | SurferFieldTypes::I64 | SurferFieldTypes::F64 => {}
+            _ => {
+                let reason = format!("Not a numeric field: {}", field_name);
+                return Err(IndexError::new(message, reason));
+            }
+        };
+        let field_entry = schema.get_field_entry(field);
+        let is_fast = field_entry.is_int_fast();
+        if !is_fast && !field_entry.is_indexed() {
+            let reason = format!("Neither indexed nor a fast field: {}", field_name);
+            return Err(IndexError::new(message, reason));
+        };
+        if let Some(percent) = percents.iter().find(|percent| !(0f64..=100f64).contains(*percent)) {
+            let reason = format!("Percent out of range: {}", percent);
+            return Err(IndexError::new(message, reason));
+        };
+        Ok(Self {
+            field_name: field_name.to_string(),
+            field,
+            field_type,
+            is_fast,
+            percents: percents.to_vec(),
+        })
+    }
+    pub(crate) fn resolve_stats(&self, summary: Summary) -> Stats {
+        let mut digest = summary.digest;
+        let percentiles = self.percents
+            .iter()
+            .filter_map(|percent| digest.percentile(*percent).map(|value| Percentile::new(*percent, value)))
+            .collect();
+        let mean = if summary.count == 0 {
+            None
+        } else {
+            Some(summary.sum / summary.count as f64)
+        };
+        Stats {
+            field_name: self.field_name.clone(),
+            count: summary.count,
+            min: summary.min,
+            max: summary.max,
+            sum: summary.sum,
+            mean,
+            percentiles,
+        }
+    }
+}
+
+/// Term dictionary keys hold numbers as big endian u64
+fn decode_term(field_type: &SurferFieldTypes, term: &[u8]) -> Option<f64> {
+    let value = u64::from_be_bytes(term.try_into().ok()?);
+    match field_type {
+        SurferFieldTypes::U64 => Some(value as f64),
+        SurferFieldTypes::I64 => Some(i64::from_u64(value) as f64),
+        SurferFieldTypes::F64 => Some(f64::from_u64(value)),
+        _ => None,
+    }
+}
+
+enum StatsSource {
+    Fast(FastFieldValues),
+    Terms { inverted_index: Arc<InvertedIndexReader>, field_type: SurferFieldTypes, matched: Vec<bool> },
+}
+
+pub(crate) struct StatsSegmentCollector {
+    source: StatsSource,
+    summary: Summary,
+}
+
+impl Collector for StatsCollector {
+    type Fruit = Summary;
+    type Child = StatsSegmentCollector;
+
+    fn for_segment(&self, _: SegmentLocalId, reader: &SegmentReader) -> tantivy::Result<Self::Child> {
+        let source = if self.is_fast {
+            let values = FastFieldValues::open(reader, self.field).ok_or_else(|| {
+                let error = format!("Field: {} is not a numeric fast field", self.field_name);
+                tantivy::TantivyError::SchemaError(error)
+            })?;
+            StatsSource::Fast(values)
+        } else {
+            StatsSource::Terms {
+                inverted_index: reader.inverted_index(self.field),
+                field_type: self.field_type.clone(),
+                matched: vec![false; reader.max_doc() as usize],
+            }
+        };
+        Ok(StatsSegmentCollector {
+            source,
+            summary: Summary::default(),
+        })
+    }
+
+    fn requires_scoring(&self) -> bool {
+        false
+    }
+
+    fn merge_fruits(&self, segment_fruits: Vec<Self::Fruit>) -> tantivy::Result<Self::Fruit> {
+        let mut merged = Summary::default();
+        for fruit in segment_fruits {
+            merged.merge(fruit);
+        };
+        Ok(merged)
+    }
+}
+
+impl SegmentCollector for StatsSegmentCollector {
+    type Fruit = Summary;
+
+    fn collect(&mut self, doc: DocId, _: Score) {
+        match &mut self.source {
+            StatsSource::Fast(values) => self.summary.add(values.as_f64(doc), 1),
+            StatsSource::Terms { matched, .. } => matched[doc as usize] = true,
+        };
+    }
+
+    fn harvest(self) -> Self::Fruit {
+        let mut summary = self.summary;
+        if let StatsSource::Terms { inverted_index, field_type, matched } = self.source {
+            if !matched.iter().any(|is_matched| *is_matched) {
+                return summary;
+            };
+            let mut terms = inverted_index.terms().stream();
+            while terms.advance() {
+                let value = match decode_term(&field_type, terms.key()) {
+                    Some(value) => value,
+                    None => continue,
+                };
+                let mut postings = inverted_index.read_postings_from_terminfo(terms.value(), IndexRecordOption::Basic);
+                let mut count = 0u64;
+                let mut doc = postings.doc();
+                while doc != TERMINATED {
+                    if matched[doc as usize] {
+                        count += 1;
+                    };
+                    doc = postings.advance();
+                };
+                if count > 0 {
+                    summary.add(value, count);
+                };
+            };
+        };
+        summary
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_exact_percentiles() {
+        let mut digest = Digest::default();
+        for value in &[4f64, 1f64, 3f64, 2f64] {
+            digest.add(*value, 1);
+        };
+        assert_eq!(digest.percentile(0f64), Some(1f64));
+        assert_eq!(digest.percentile(50f64), Some(2.5f64));
+        assert_eq!(digest.percentile(100f64), Some(4f64));
+        digest.add(10f64, 3);
+        assert_eq!(digest.percentile(100f64), Some(10f64));
+        assert_eq!(digest.percentile(50f64), Some(4f64));
+        assert_eq!(Digest::default().percentile(50f64), None);
+    }
+
+    #[test]
+    fn validate_approximate_percentiles() {
+        let mut digest = Digest::default();
+        for value in 0..100_000 {
+            digest.add(value as f64, 1);
+        };
+        let median = digest.percentile(50f64).unwrap();
+        assert!((median - 50_000f64).abs() < 500f64);
+        let p99 = digest.percentile(99f64).unwrap();
+        assert!((p99 - 99_000f64).abs() < 500f64);
+        assert!(digest.centroids.len() <= 2 * DIGEST_CAPACITY);
+    }
+}
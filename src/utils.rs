@@ -58,10 +58,7 @@ fn resolve_text_option(key: &str, control: Option<&HashMap<String, Control>>) ->
             }
             let x = x.unwrap();
             match x {
-                Control::ControlTextOptions(opt) => {
-                    let option = opt.clone();
-                    option
-                }
+                Control::ControlTextOptions(opt) => opt.clone(),
                 _ => default
             }
         }
@@ -82,10 +79,7 @@ fn resolve_number_option(key: &str, control: Option<&HashMap<String, Control>>)
             }
             let x = x.unwrap();
             match x {
-                Control::ControlIntOptions(opt) => {
-                    let option = opt.clone();
-                    option
-                }
+                Control::ControlIntOptions(opt) => opt.clone(),
                 _ => default
             }
         }
@@ -99,10 +93,7 @@ pub fn join(head: &str, tail: &str) -> Option<String> {
     let tail = Path::new(tail);
     let path = head.join(tail);
     let path = path.to_str();
-    match path {
-        Some(p) => Some(p.to_string()),
-        None => None
-    }
+    path.map(|p| p.to_string())
 }
 
 /// Maps flat JSON structures
@@ -170,7 +161,7 @@ pub(crate) fn as_schema_builder<T: Serialize>(payload: &T, control: Option<&Hash
                     Value::Bool(_) => {
                         let options = resolve_text_option(k, control);
                         builder.add_text_field(k, options);
-                        field_type_mappings.insert(k.to_string(), SurferFieldTypes::String);
+                        field_type_mappings.insert(k.to_string(), SurferFieldTypes::Bool);
                     }
                     Value::U64(_) => {
                         let options = resolve_number_option(k, control);
@@ -284,11 +275,7 @@ pub fn block_thread(sleep_in_seconds: u64) -> u64 {
 }
 
 pub fn random_string(size: Option<usize>) -> String {
-    let size = if size.is_none() {
-        10
-    } else {
-        size.unwrap()
-    };
+    let size = size.unwrap_or(10);
     rand::thread_rng()
         .sample_iter(&Alphanumeric)
         .take(size)
@@ -322,14 +309,14 @@ mod tests {
         let text_options = TEXT;
         control.insert(key.to_string(), Control::ControlTextOptions(text_options));
         let options = resolve_text_option(key, Some(&control));
-        assert_eq!(options.is_stored(), false);
+        assert!(!options.is_stored());
     }
 
     #[test]
     fn validate_resolve_default_text_option() {
         let key = "dummy";
         let options = resolve_text_option(key, None);
-        assert_eq!(options.is_stored(), true);
+        assert!(options.is_stored());
     }
 
     #[test]
@@ -339,14 +326,14 @@ mod tests {
         let int_options = IntOptions::default();
         control.insert(key.to_string(), Control::ControlIntOptions(int_options));
         let options = resolve_number_option(key, Some(&control));
-        assert_eq!(options.is_stored(), false);
+        assert!(!options.is_stored());
     }
 
     #[test]
     fn validate_resolve_default_number_option() {
         let key = "dummy";
         let options = resolve_number_option(key, None);
-        assert_eq!(options.is_stored(), true);
+        assert!(options.is_stored());
     }
 
     #[test]
@@ -356,7 +343,7 @@ mod tests {
         let int_options = IntOptions::default();
         control.insert(key.to_string(), Control::ControlIntOptions(int_options));
         let options = resolve_text_option(key, Some(&control));
-        assert_eq!(options.is_stored(), true);
+        assert!(options.is_stored());
     }
 
     #[test]
@@ -365,7 +352,7 @@ mod tests {
         let mut control = HashMap::new();
         control.insert(key.to_string(), Control::ControlTextOptions(TextOptions::default()));
         let options = resolve_text_option(key, Some(&control));
-        assert_eq!(options.is_stored(), false);
+        assert!(!options.is_stored());
     }
 
     #[test]
@@ -374,7 +361,7 @@ mod tests {
         let mut control = HashMap::new();
         control.insert(key.to_string(), Control::ControlIntOptions(IntOptions::default()));
         let options = resolve_number_option(key, Some(&control));
-        assert_eq!(options.is_stored(), false);
+        assert!(!options.is_stored());
     }
 
 
@@ -385,7 +372,7 @@ mod tests {
         let text_options = TEXT;
         control.insert(key.to_string(), Control::ControlTextOptions(text_options));
         let options = resolve_number_option(key, Some(&control));
-        assert_eq!(options.is_stored(), true);
+        assert!(options.is_stored());
     }
 
     #[test]